@@ -0,0 +1,35 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+int __cxa_atexit(void (*function)(void*), void* arg, void* dso);
+extern void* __dso_handle;
+
+static void first(void) {
+    puts("first registered, last run");
+}
+
+static void late(void) {
+    puts("registered during exit");
+}
+
+static void registers_late(void) {
+    puts("registering another handler");
+    atexit(late);
+}
+
+static void destructor(void* arg) {
+    printf("destroying %s\n", (const char*)arg);
+}
+
+static void quick(void) {
+    puts("quick exit handlers don't run on exit");
+}
+
+int main(void) {
+    atexit(first);
+    __cxa_atexit(destructor, "object", &__dso_handle);
+    atexit(registers_late);
+    at_quick_exit(quick);
+    puts("returning from main");
+    return 0;
+}
@@ -0,0 +1,51 @@
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+#define PATH "/tmp/cloyster_buffering_test"
+
+static int size_of(int fd) {
+	struct stat st;
+	fstat(fd, &st);
+	return (int)st.st_size;
+}
+
+int main(void) {
+	int fd = open(PATH, O_RDWR | O_CREAT | O_TRUNC, 0644);
+	FILE* stream = fdopen(fd, "w");
+	if (stream == NULL) {
+		printf("fdopen failed\n");
+		return 1;
+	}
+
+	// Small writes wait in the buffer until it's flushed
+	fwrite("abc", 1, 3, stream);
+	fputc('d', stream);
+	fprintf(stream, "%d", 42);
+	printf("Before fflush: %d\n", size_of(fd));
+	int result = fflush(stream);
+	printf("fflush: %d size %d\n", result, size_of(fd));
+
+	// A write bigger than the buffer doesn't have to wait
+	static char big[3 * BUFSIZ];
+	memset(big, 'x', sizeof(big));
+	size_t count = fwrite(big, 1, sizeof(big), stream);
+	printf("Big fwrite: %d size at least %d: %d\n", (int)count, (int)sizeof(big),
+	       size_of(fd) >= (int)sizeof(big));
+	fputs("end", stream);
+
+	// Closing writes out the rest
+	result = fclose(stream);
+	fd = open(PATH, O_RDONLY);
+	char tail[4] = {0};
+	pread(fd, tail, 3, (off_t)sizeof(big) + 6);
+	printf("fclose: %d size %d ends with %s\n", result, size_of(fd), tail);
+	close(fd);
+
+	// Nothing to flush
+	printf("fflush(NULL): %d\n", fflush(NULL));
+	unlink(PATH);
+	return 0;
+}
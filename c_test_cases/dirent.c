@@ -0,0 +1,201 @@
+#define _GNU_SOURCE
+#include <dirent.h>
+#include <errno.h>
+#include <fcntl.h>
+#include <ftw.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+#define ROOT "/tmp/cloyster_dirent_test"
+
+static const char* type_name(int typeflag) {
+	switch (typeflag) {
+	case FTW_F:
+		return "F";
+	case FTW_D:
+		return "D";
+	case FTW_DNR:
+		return "DNR";
+	case FTW_NS:
+		return "NS";
+	case FTW_SL:
+		return "SL";
+	case FTW_DP:
+		return "DP";
+	case FTW_SLN:
+		return "SLN";
+	default:
+		return "?";
+	}
+}
+
+static int print_entry(const char* fpath, const struct stat* sb, int typeflag, struct FTW* ftwbuf) {
+	(void)sb;
+	printf("  %s %s level=%d name=%s\n", type_name(typeflag), fpath + strlen(ROOT), ftwbuf->level,
+	       fpath + ftwbuf->base);
+	return 0;
+}
+
+static int stop_at_sub(const char* fpath, const struct stat* sb, int typeflag,
+                       struct FTW* ftwbuf) {
+	(void)sb;
+	(void)typeflag;
+	(void)ftwbuf;
+	return strcmp(fpath, ROOT "/sub") == 0 ? 42 : 0;
+}
+
+static int files = 0;
+static int directories = 0;
+
+static int count(const char* fpath, const struct stat* sb, int typeflag) {
+	(void)fpath;
+	(void)sb;
+	if (typeflag == FTW_F) {
+		files++;
+	} else if (typeflag == FTW_D) {
+		directories++;
+	}
+	return 0;
+}
+
+static int skip_dots(const struct dirent* entry) {
+	return entry->d_name[0] != '.';
+}
+
+// Count the entries of `dir`, and whether `name` is one of them
+static int count_entries(DIR* dir, const char* name, int* found) {
+	int entries = 0;
+	*found = 0;
+	struct dirent* entry;
+	while ((entry = readdir(dir)) != NULL) {
+		entries++;
+		if (strcmp(entry->d_name, name) == 0) {
+			*found = 1;
+		}
+	}
+	return entries;
+}
+
+static void test_readdir(void) {
+	DIR* dir = opendir(ROOT);
+	printf("opendir: %d\n", dir != NULL);
+	int found;
+	errno = 0;
+	int entries = count_entries(dir, "a.txt", &found);
+	int saved_errno = errno;
+	printf("Entries: %d found a.txt: %d errno unchanged: %d\n", entries, found, saved_errno == 0);
+
+	rewinddir(dir);
+	struct dirent* first = readdir(dir);
+	char first_name[256];
+	strcpy(first_name, first->d_name);
+	long position = telldir(dir);
+	struct dirent* second = readdir(dir);
+	char second_name[256];
+	strcpy(second_name, second->d_name);
+	seekdir(dir, position);
+	printf("seekdir returns to the same entry: %d\n", strcmp(readdir(dir)->d_name, second_name) == 0);
+	rewinddir(dir);
+	printf("rewinddir returns to the start: %d\n", strcmp(readdir(dir)->d_name, first_name) == 0);
+
+	// Deprecated, but still part of the interface
+#pragma GCC diagnostic push
+#pragma GCC diagnostic ignored "-Wdeprecated-declarations"
+	struct dirent entry;
+	struct dirent* result;
+	rewinddir(dir);
+	int err = readdir_r(dir, &entry, &result);
+	printf("readdir_r: %d result is entry: %d same name: %d\n", err, result == &entry,
+	       strcmp(entry.d_name, first_name) == 0);
+	while (readdir(dir) != NULL) {
+	}
+	err = readdir_r(dir, &entry, &result);
+	printf("readdir_r at end: %d result: %p\n", err, (void*)result);
+#pragma GCC diagnostic pop
+
+	printf("dirfd is a descriptor: %d\n", dirfd(dir) >= 0);
+	printf("closedir: %d\n", closedir(dir));
+
+	int fd = open(ROOT "/sub", O_RDONLY);
+	dir = fdopendir(fd);
+	printf("fdopendir: %d dirfd matches: %d\n", dir != NULL, dirfd(dir) == fd);
+	entries = count_entries(dir, "deeper", &found);
+	printf("Entries: %d found deeper: %d\n", entries, found);
+	closedir(dir);
+
+	errno = 0;
+	dir = opendir(ROOT "/missing");
+	saved_errno = errno;
+	printf("Missing: %d errno == ENOENT: %d\n", dir == NULL, saved_errno == ENOENT);
+	errno = 0;
+	dir = opendir(ROOT "/a.txt");
+	saved_errno = errno;
+	printf("File: %d errno == ENOTDIR: %d\n", dir == NULL, saved_errno == ENOTDIR);
+}
+
+static void test_scandir(void) {
+	struct dirent** namelist;
+	int n = scandir(ROOT, &namelist, skip_dots, alphasort);
+	printf("scandir: %d\n", n);
+	for (int i = 0; i < n; i++) {
+		printf("  %s type=%d\n", namelist[i]->d_name, namelist[i]->d_type);
+		free(namelist[i]);
+	}
+	free(namelist);
+
+	n = scandir(ROOT "/sub/deeper", &namelist, NULL, NULL);
+	printf("scandir without filter: %d\n", n);
+	for (int i = 0; i < n; i++) {
+		free(namelist[i]);
+	}
+	free(namelist);
+
+	errno = 0;
+	n = scandir(ROOT "/missing", &namelist, NULL, alphasort);
+	int saved_errno = errno;
+	printf("scandir of missing: %d errno == ENOENT: %d\n", n, saved_errno == ENOENT);
+}
+
+static void test_nftw(void) {
+	printf("nftw:\n");
+	printf("Result: %d\n", nftw(ROOT, print_entry, 4, 0));
+	printf("nftw with FTW_PHYS:\n");
+	printf("Result: %d\n", nftw(ROOT, print_entry, 4, FTW_PHYS));
+	printf("nftw with FTW_DEPTH | FTW_MOUNT:\n");
+	printf("Result: %d\n", nftw(ROOT "/sub/", print_entry, 1, FTW_DEPTH | FTW_MOUNT));
+	// A link back up the tree is only followed to directories not yet visited
+	system("ln -s .. " ROOT "/sub/up && ln -s sub/deeper " ROOT "/deeper_link");
+	printf("nftw with a loop:\n");
+	printf("Result: %d\n", nftw(ROOT, print_entry, 4, 0));
+	system("rm " ROOT "/sub/up " ROOT "/deeper_link");
+	printf("nftw stopped by callback: %d\n", nftw(ROOT, stop_at_sub, 4, 0));
+
+	errno = 0;
+	int result = nftw(ROOT "/missing", print_entry, 4, 0);
+	int saved_errno = errno;
+	printf("nftw of missing: %d errno == ENOENT: %d\n", result, saved_errno == ENOENT);
+
+	printf("ftw: %d\n", ftw(ROOT, count, 4));
+	printf("Files: %d directories: %d\n", files, directories);
+}
+
+int main(void) {
+	// One of each kind of file
+	int status = system("rm -rf " ROOT " && mkdir -p " ROOT "/sub/deeper"
+	                    " && echo a > " ROOT "/a.txt"
+	                    " && echo b > " ROOT "/sub/b.txt"
+	                    " && echo c > " ROOT "/sub/deeper/c.txt"
+	                    " && ln -s a.txt " ROOT "/link"
+	                    " && ln -s nowhere " ROOT "/broken");
+	if (status != 0) {
+		printf("Couldn't create the test tree\n");
+		return 1;
+	}
+	test_readdir();
+	test_scandir();
+	test_nftw();
+	system("rm -rf " ROOT);
+	return 0;
+}
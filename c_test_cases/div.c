@@ -0,0 +1,27 @@
+#include <inttypes.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+int main(void) {
+    div_t d = div(7, 2);
+    printf("div(7, 2) = %d r %d\n", d.quot, d.rem);
+    d = div(-7, 2);
+    printf("div(-7, 2) = %d r %d\n", d.quot, d.rem);
+
+    ldiv_t ld = ldiv(-100000, 7);
+    printf("ldiv(-100000, 7) = %d r %d\n", (int)ld.quot, (int)ld.rem);
+
+    lldiv_t lld = lldiv(0x7fffffffffffffffLL, 0x100000000LL);
+    printf("lldiv = %x r %x\n", (int)lld.quot, (int)lld.rem);
+
+    imaxdiv_t md = imaxdiv(-9, -4);
+    printf("imaxdiv(-9, -4) = %d r %d\n", (int)md.quot, (int)md.rem);
+
+    printf("abs(-5) = %d\n", abs(-5));
+    printf("labs(-6) = %d\n", (int)labs(-6L));
+    printf("llabs = %x\n", (int)(llabs(-0x100000005LL) >> 32));
+    printf("imaxabs(-8) = %d\n", (int)imaxabs(-8));
+
+    return 0;
+}
@@ -0,0 +1,202 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/file.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#define LOCK_PATH "/tmp/cloyster_fcntl_test"
+
+static pid_t parent_pid;
+
+// Run `test` in a child process, and return its exit status
+static int in_child(int (*test)(void)) {
+	pid_t pid = fork();
+	if (pid == 0) {
+		_exit(test());
+	}
+	int status;
+	if (waitpid(pid, &status, 0) != pid) {
+		printf("waitpid failed\n");
+		exit(1);
+	}
+	return WEXITSTATUS(status);
+}
+
+static int open_lock_file(void) {
+	int fd = open(LOCK_PATH, O_RDWR | O_CREAT, 0644);
+	if (fd < 0) {
+		printf("open failed\n");
+		exit(1);
+	}
+	return fd;
+}
+
+static void test_pipe(void) {
+	int fds[2];
+	printf("pipe: %d\n", pipe(fds));
+	printf("write: %d\n", (int)write(fds[1], "hello", 5));
+	char buf[8] = {0};
+	printf("read: %d %s\n", (int)read(fds[0], buf, sizeof(buf) - 1), buf);
+
+	// Reading an empty non-blocking pipe fails instead of blocking
+	int flags = fcntl(fds[0], F_GETFL);
+	printf("O_NONBLOCK initially: %d\n", (flags & O_NONBLOCK) != 0);
+	printf("F_SETFL: %d\n", fcntl(fds[0], F_SETFL, flags | O_NONBLOCK));
+	printf("O_NONBLOCK now: %d\n", (fcntl(fds[0], F_GETFL) & O_NONBLOCK) != 0);
+	errno = 0;
+	int result = read(fds[0], buf, sizeof(buf));
+	int saved_errno = errno;
+	printf("Empty read: %d errno == EAGAIN: %d\n", result, saved_errno == EAGAIN);
+	close(fds[0]);
+	close(fds[1]);
+
+	printf("pipe2: %d\n", pipe2(fds, O_CLOEXEC));
+	printf("Read end FD_CLOEXEC: %d\n", fcntl(fds[0], F_GETFD));
+	printf("Write end FD_CLOEXEC: %d\n", fcntl(fds[1], F_GETFD));
+	close(fds[0]);
+	close(fds[1]);
+}
+
+static void test_dup(void) {
+	int fds[2];
+	pipe(fds);
+
+	int copy = dup(fds[1]);
+	printf("dup gives a new fd: %d\n", copy > fds[1]);
+	write(copy, "a", 1);
+	printf("dup2 onto itself: %d\n", dup2(copy, copy) == copy);
+	int target = copy + 10;
+	printf("dup2: %d\n", dup2(fds[1], target) == target);
+	write(target, "b", 1);
+	printf("dup3: %d\n", dup3(fds[1], target, O_CLOEXEC) == target);
+	printf("dup3 FD_CLOEXEC: %d\n", fcntl(target, F_GETFD));
+	write(target, "c", 1);
+
+	errno = 0;
+	int result = dup3(copy, copy, 0);
+	int saved_errno = errno;
+	printf("dup3 onto itself: %d errno == EINVAL: %d\n", result, saved_errno == EINVAL);
+
+	int above = fcntl(fds[1], F_DUPFD_CLOEXEC, 50);
+	printf("F_DUPFD_CLOEXEC: %d %d\n", above, fcntl(above, F_GETFD));
+	result = fcntl(above, F_SETFD, 0);
+	printf("F_SETFD: %d %d\n", result, fcntl(above, F_GETFD));
+	int below = fcntl(fds[1], F_DUPFD, 40);
+	printf("F_DUPFD: %d %d\n", below, fcntl(below, F_GETFD));
+	write(above, "d", 1);
+	write(below, "e", 1);
+
+	close(copy);
+	close(target);
+	close(above);
+	close(below);
+	close(fds[1]);
+	char buf[8] = {0};
+	read(fds[0], buf, sizeof(buf) - 1);
+	printf("Written through copies: %s\n", buf);
+	close(fds[0]);
+
+	errno = 0;
+	result = dup(-1);
+	saved_errno = errno;
+	printf("dup(-1): %d errno == EBADF: %d\n", result, saved_errno == EBADF);
+}
+
+static int try_process_lock(void) {
+	int fd = open_lock_file();
+	struct flock lock = {.l_type = F_WRLCK, .l_whence = SEEK_SET, .l_start = 0, .l_len = 10};
+	if (fcntl(fd, F_GETLK, &lock) != 0 || lock.l_type != F_WRLCK || lock.l_pid != parent_pid) {
+		return 1;
+	}
+	lock.l_type = F_WRLCK;
+	// Outside the locked range
+	lock.l_start = 10;
+	if (fcntl(fd, F_SETLK, &lock) != 0) {
+		return 2;
+	}
+	lock.l_start = 5;
+	if (fcntl(fd, F_SETLK, &lock) != -1 || (errno != EAGAIN && errno != EACCES)) {
+		return 3;
+	}
+	if (lockf(fd, F_TEST, 5) != -1 || errno != EACCES) {
+		return 4;
+	}
+	if (lockf(fd, F_TLOCK, 5) != -1) {
+		return 5;
+	}
+	lseek(fd, 20, SEEK_SET);
+	if (lockf(fd, F_TEST, 5) != 0 || lockf(fd, F_TLOCK, 5) != 0) {
+		return 6;
+	}
+	return 0;
+}
+
+static int try_flock(void) {
+	int fd = open_lock_file();
+	if (flock(fd, LOCK_EX | LOCK_NB) != -1 || errno != EWOULDBLOCK) {
+		return 1;
+	}
+	if (flock(fd, LOCK_SH | LOCK_NB) != -1) {
+		return 2;
+	}
+	return 0;
+}
+
+static int try_flock_after_unlock(void) {
+	int fd = open_lock_file();
+	return flock(fd, LOCK_EX | LOCK_NB) == 0 ? 0 : 1;
+}
+
+static void test_locks(void) {
+	int fd = open_lock_file();
+	struct flock lock = {.l_type = F_WRLCK, .l_whence = SEEK_SET, .l_start = 0, .l_len = 10};
+	printf("F_SETLK: %d\n", fcntl(fd, F_SETLK, &lock));
+	// Our own locks don't conflict with us
+	lock.l_type = F_WRLCK;
+	int result = fcntl(fd, F_GETLK, &lock);
+	printf("F_GETLK: %d unlocked: %d\n", result, lock.l_type == F_UNLCK);
+	printf("Process locks in child: %d\n", in_child(try_process_lock));
+	printf("lockf(F_TEST) of own lock: %d\n", lockf(fd, F_TEST, 10));
+	printf("lockf(F_ULOCK): %d\n", lockf(fd, F_ULOCK, 0));
+
+	printf("flock(LOCK_EX): %d\n", flock(fd, LOCK_EX));
+	printf("flock in child: %d\n", in_child(try_flock));
+	printf("flock(LOCK_UN): %d\n", flock(fd, LOCK_UN));
+	printf("flock in child after unlock: %d\n", in_child(try_flock_after_unlock));
+
+	// Open file description locks conflict between separate opens in the same process
+	int other = open_lock_file();
+	struct flock ofd = {.l_type = F_WRLCK, .l_whence = SEEK_SET, .l_start = 0, .l_len = 0};
+	printf("F_OFD_SETLK: %d\n", fcntl(fd, F_OFD_SETLK, &ofd));
+	errno = 0;
+	result = fcntl(other, F_OFD_SETLK, &ofd);
+	int saved_errno = errno;
+	printf("Conflicting F_OFD_SETLK: %d errno == EAGAIN: %d\n", result, saved_errno == EAGAIN);
+	ofd.l_type = F_RDLCK;
+	result = fcntl(other, F_OFD_GETLK, &ofd);
+	printf("F_OFD_GETLK: %d write locked: %d pid: %d\n", result, ofd.l_type == F_WRLCK,
+	       ofd.l_pid);
+	ofd = (struct flock){.l_type = F_UNLCK, .l_whence = SEEK_SET, .l_start = 0, .l_len = 0};
+	printf("F_OFD_SETLKW unlock: %d\n", fcntl(fd, F_OFD_SETLKW, &ofd));
+	ofd.l_type = F_WRLCK;
+	printf("F_OFD_SETLK after unlock: %d\n", fcntl(other, F_OFD_SETLK, &ofd));
+
+	errno = 0;
+	result = lockf(fd, 42, 0);
+	saved_errno = errno;
+	printf("Bad lockf command: %d errno == EINVAL: %d\n", result, saved_errno == EINVAL);
+
+	close(other);
+	close(fd);
+}
+
+int main(void) {
+	parent_pid = getpid();
+	test_pipe();
+	test_dup();
+	test_locks();
+	return 0;
+}
@@ -0,0 +1,127 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <pthread.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+static int prepared = 0;
+static int parents = 0;
+static int children = 0;
+
+static void prepare(void) {
+	prepared++;
+}
+
+static void parent(void) {
+	parents++;
+}
+
+static void child(void) {
+	children++;
+}
+
+// Fork a child that runs `run`, and return its status
+static int spawn(void (*run)(void)) {
+	pid_t pid = fork();
+	if (pid == 0) {
+		run();
+		_exit(99);
+	}
+	int status;
+	pid_t waited = waitpid(pid, &status, 0);
+	if (waited != pid) {
+		printf("waitpid failed\n");
+		exit(1);
+	}
+	return status;
+}
+
+static void run_true(void) {
+	execl("/bin/true", "true", (char*)NULL);
+}
+
+static void run_false(void) {
+	char* const argv[] = {"false", NULL};
+	// Found through PATH
+	execvp("false", argv);
+}
+
+static void run_sh(void) {
+	char* const argv[] = {"sh", "-c", "exit $CODE", NULL};
+	char* const envp[] = {"CODE=7", NULL};
+	execve("/bin/sh", argv, envp);
+}
+
+#define SCRIPT "/tmp/cloyster_fork_script"
+
+// A file without a `#!` line is run by the shell
+static void run_script(void) {
+	char* const argv[] = {"script", "5", NULL};
+	execvp(SCRIPT, argv);
+}
+
+static void run_missing(void) {
+	char* const argv[] = {"missing", NULL};
+	execvp("cloyster-no-such-program", argv);
+	_exit(errno == ENOENT ? 42 : 43);
+}
+
+static void run_exit(void) {
+	_exit(3);
+}
+
+// Only the child's own handler has run in its copy of the counters
+static void run_count_children(void) {
+	_exit(children);
+}
+
+static void run_abort(void) {
+	abort();
+}
+
+int main(void) {
+	if (pthread_atfork(prepare, parent, child) != 0) {
+		printf("pthread_atfork failed\n");
+		return 1;
+	}
+
+	int status = spawn(run_true);
+	printf("true: exited %d status %d\n", WIFEXITED(status), WEXITSTATUS(status));
+
+	status = spawn(run_false);
+	printf("false: exited %d status %d\n", WIFEXITED(status), WEXITSTATUS(status));
+
+	status = spawn(run_sh);
+	printf("sh: exited %d status %d\n", WIFEXITED(status), WEXITSTATUS(status));
+
+	if (system("printf 'exit $1\\n' > " SCRIPT " && chmod +x " SCRIPT) != 0) {
+		printf("Couldn't create the script\n");
+		return 1;
+	}
+	status = spawn(run_script);
+	printf("script: exited %d status %d\n", WIFEXITED(status), WEXITSTATUS(status));
+	remove(SCRIPT);
+
+	status = spawn(run_missing);
+	printf("missing: exited %d status %d\n", WIFEXITED(status), WEXITSTATUS(status));
+
+	status = spawn(run_exit);
+	printf("_exit: exited %d status %d\n", WIFEXITED(status), WEXITSTATUS(status));
+
+	status = spawn(run_abort);
+	printf("abort: signaled %d signal %d\n", WIFSIGNALED(status), WTERMSIG(status));
+
+	status = spawn(run_count_children);
+	printf("atfork: prepared %d parents %d children %d\n", prepared, parents, WEXITSTATUS(status));
+
+	// No children left
+	pid_t waited = wait(&status);
+	int error = errno;
+	printf("wait: %d %d\n", waited, error == ECHILD);
+	waited = waitpid(-1, NULL, WNOHANG);
+	error = errno;
+	printf("waitpid: %d %d\n", waited, error == ECHILD);
+	return 0;
+}
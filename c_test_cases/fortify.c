@@ -0,0 +1,53 @@
+// Call the checked variants that `_FORTIFY_SOURCE` redirects to, with destinations that are large
+// enough. test.sh doesn't optimise, so the fortified headers wouldn't kick in on their own
+#include <stdarg.h>
+#include <stdio.h>
+#include <string.h>
+
+void* __memcpy_chk(void* dst, const void* src, size_t len, size_t dstlen);
+void* __memmove_chk(void* dst, const void* src, size_t len, size_t dstlen);
+void* __memset_chk(void* dst, int c, size_t len, size_t dstlen);
+char* __strcpy_chk(char* dst, const char* src, size_t dstlen);
+char* __stpcpy_chk(char* dst, const char* src, size_t dstlen);
+char* __strncpy_chk(char* dst, const char* src, size_t n, size_t dstlen);
+char* __strcat_chk(char* dst, const char* src, size_t dstlen);
+int __printf_chk(int flag, const char* fmt, ...);
+int __fprintf_chk(FILE* stream, int flag, const char* fmt, ...);
+int __sprintf_chk(char* s, int flag, size_t slen, const char* fmt, ...);
+int __snprintf_chk(char* s, size_t maxlen, int flag, size_t slen, const char* fmt, ...);
+
+int main(void) {
+	char buffer[16];
+
+	__memset_chk(buffer, 'x', sizeof(buffer), sizeof(buffer));
+	buffer[sizeof(buffer) - 1] = '\0';
+	printf("%s\n", buffer);
+
+	__memcpy_chk(buffer, "memcpy", 7, sizeof(buffer));
+	printf("%s\n", buffer);
+
+	__memmove_chk(buffer + 1, buffer, 7, sizeof(buffer) - 1);
+	printf("%s\n", buffer);
+
+	__strcpy_chk(buffer, "strcpy", sizeof(buffer));
+	__strcat_chk(buffer, "+strcat", sizeof(buffer));
+	printf("%s\n", buffer);
+
+	char* end = __stpcpy_chk(buffer, "stpcpy", sizeof(buffer));
+	printf("%d\n", (int)(end - buffer));
+
+	// Exactly fills the buffer, including the padding
+	__strncpy_chk(buffer, "strncpy", sizeof(buffer), sizeof(buffer));
+	printf("%s %d\n", buffer, buffer[sizeof(buffer) - 1]);
+
+	int written = __sprintf_chk(buffer, 1, sizeof(buffer), "%d-%s", 42, "sprintf");
+	printf("%s %d\n", buffer, written);
+
+	// Truncated, but within the buffer
+	written = __snprintf_chk(buffer, 8, 1, sizeof(buffer), "%s", "snprintf_chk");
+	printf("%s %d\n", buffer, written);
+
+	__printf_chk(1, "%s %d\n", "printf_chk", 7);
+	__fprintf_chk(stdout, 1, "%s %x\n", "fprintf_chk", 255);
+	return 0;
+}
@@ -0,0 +1,154 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+#define ROOT "/tmp/cloyster_fs_test"
+
+static void print_result(const char* name, int result) {
+	int saved_errno = errno;
+	printf("%s: %d", name, result);
+	if (result < 0) {
+		printf(" (%s)", strerrorname_np(saved_errno));
+	}
+	printf("\n");
+	errno = 0;
+}
+
+static int permissions(const char* path) {
+	struct stat statbuf;
+	stat(path, &statbuf);
+	return statbuf.st_mode & 0777;
+}
+
+static int size(const char* path) {
+	struct stat statbuf;
+	stat(path, &statbuf);
+	return (int)statbuf.st_size;
+}
+
+int main(void) {
+	system("rm -rf " ROOT);
+	mode_t old_mask = umask(022);
+	print_result("mkdir", mkdir(ROOT, 0777));
+	print_result("Permissions are 0755", permissions(ROOT) == 0755);
+	print_result("mkdir again", mkdir(ROOT, 0777));
+	print_result("mkdir in missing directory", mkdir(ROOT "/missing/dir", 0777));
+
+	// Files
+	int fd = open(ROOT "/file", O_RDWR | O_CREAT | O_EXCL, 0666);
+	print_result("Created", fd >= 0);
+	print_result("Created again", open(ROOT "/file", O_RDWR | O_CREAT | O_EXCL, 0666));
+	print_result("Permissions are 0644", permissions(ROOT "/file") == 0644);
+	write(fd, "Twelve bytes", 12);
+	print_result("ftruncate", ftruncate(fd, 5));
+	print_result("Size", size(ROOT "/file"));
+	print_result("truncate", truncate(ROOT "/file", 20));
+	print_result("Size", size(ROOT "/file"));
+	print_result("truncate of missing", truncate(ROOT "/missing", 0));
+	print_result("ftruncate of bad fd", ftruncate(-1, 0));
+
+	print_result("chmod", chmod(ROOT "/file", 0600));
+	print_result("Permissions are 0600", permissions(ROOT "/file") == 0600);
+	print_result("fchmod", fchmod(fd, 0640));
+	print_result("Permissions are 0640", permissions(ROOT "/file") == 0640);
+	print_result("chown to the same owner", chown(ROOT "/file", -1, -1));
+	print_result("fchown to the same owner", fchown(fd, -1, -1));
+	print_result("fchownat", fchownat(AT_FDCWD, ROOT "/file", -1, -1, AT_SYMLINK_NOFOLLOW));
+	print_result("chown of missing", chown(ROOT "/missing", -1, -1));
+
+	// Timestamps
+	struct timespec times[2] = {{.tv_sec = 1000, .tv_nsec = 0}, {.tv_sec = 2000, .tv_nsec = 0}};
+	print_result("utimensat", utimensat(AT_FDCWD, ROOT "/file", times, 0));
+	struct stat statbuf;
+	stat(ROOT "/file", &statbuf);
+	print_result("Access time", (int)statbuf.st_atim.tv_sec);
+	print_result("Modification time", (int)statbuf.st_mtim.tv_sec);
+	times[0].tv_nsec = UTIME_OMIT;
+	times[1].tv_nsec = UTIME_NOW;
+	print_result("utimensat with UTIME_OMIT and UTIME_NOW",
+	             utimensat(AT_FDCWD, ROOT "/file", times, 0));
+	stat(ROOT "/file", &statbuf);
+	print_result("Access time", (int)statbuf.st_atim.tv_sec);
+	print_result("Modification time is now", statbuf.st_mtim.tv_sec > 2000);
+	times[0].tv_nsec = 0;
+	times[1].tv_nsec = 0;
+	utimensat(AT_FDCWD, ROOT "/file", times, 0);
+	print_result("futimens", futimens(fd, NULL));
+	stat(ROOT "/file", &statbuf);
+	print_result("Both times are now", statbuf.st_atim.tv_sec > 1000 && statbuf.st_mtim.tv_sec > 2000);
+	close(fd);
+
+	// Links
+	print_result("link", link(ROOT "/file", ROOT "/hard"));
+	stat(ROOT "/file", &statbuf);
+	print_result("Links", (int)statbuf.st_nlink);
+	print_result("link over existing", link(ROOT "/file", ROOT "/hard"));
+	print_result("symlink", symlink("file", ROOT "/soft"));
+	char buf[64];
+	ssize_t length = readlink(ROOT "/soft", buf, sizeof(buf));
+	print_result("readlink", (int)length);
+	buf[length] = '\0';
+	printf("Target: %s\n", buf);
+	memset(buf, 'x', sizeof(buf));
+	length = readlink(ROOT "/soft", buf, 2);
+	print_result("readlink into small buffer", (int)length);
+	print_result("Cut off without terminator", buf[0] == 'f' && buf[1] == 'i' && buf[2] == 'x');
+	print_result("readlink of file", (int)readlink(ROOT "/file", buf, sizeof(buf)));
+
+	// Relative to a directory
+	int dir = open(ROOT, O_RDONLY | O_DIRECTORY);
+	print_result("mkdirat", mkdirat(dir, "sub", 0700));
+	print_result("Permissions are 0700", permissions(ROOT "/sub") == 0700);
+	print_result("linkat", linkat(dir, "soft", dir, "sub/followed", AT_SYMLINK_FOLLOW));
+	print_result("linkat of link", linkat(dir, "soft", dir, "sub/unfollowed", 0));
+	lstat(ROOT "/sub/followed", &statbuf);
+	print_result("Followed is a file", S_ISREG(statbuf.st_mode));
+	lstat(ROOT "/sub/unfollowed", &statbuf);
+	print_result("Unfollowed is a link", S_ISLNK(statbuf.st_mode));
+	print_result("symlinkat", symlinkat("../file", dir, "sub/up"));
+	length = readlinkat(dir, "sub/up", buf, sizeof(buf));
+	print_result("readlinkat", (int)length);
+	buf[length] = '\0';
+	printf("Target: %s\n", buf);
+
+	// Renaming
+	print_result("rename", rename(ROOT "/hard", ROOT "/renamed"));
+	print_result("Old name is gone", stat(ROOT "/hard", &statbuf));
+	print_result("rename of missing", rename(ROOT "/hard", ROOT "/renamed"));
+	print_result("renameat", renameat(dir, "renamed", dir, "sub/renamed"));
+	print_result("renameat2 with RENAME_NOREPLACE",
+	             renameat2(dir, "file", dir, "sub/renamed", RENAME_NOREPLACE));
+	print_result("renameat2 with RENAME_EXCHANGE",
+	             renameat2(dir, "soft", dir, "sub/renamed", RENAME_EXCHANGE));
+	lstat(ROOT "/soft", &statbuf);
+	print_result("Exchanged for a file", S_ISREG(statbuf.st_mode));
+	print_result("rename directory over file", rename(ROOT "/sub", ROOT "/file"));
+
+	// Removing
+	print_result("unlink of directory", unlink(ROOT "/sub"));
+	print_result("rmdir of full directory", rmdir(ROOT "/sub"));
+	print_result("rmdir of file", rmdir(ROOT "/file"));
+	print_result("unlinkat", unlinkat(dir, "sub/renamed", 0));
+	print_result("unlinkat of directory", unlinkat(dir, "sub", 0));
+	print_result("unlink", unlink(ROOT "/sub/followed"));
+	print_result("unlink of missing", unlink(ROOT "/sub/followed"));
+	print_result("remove of link", remove(ROOT "/sub/unfollowed"));
+	print_result("remove of link", remove(ROOT "/sub/up"));
+	print_result("unlinkat with AT_REMOVEDIR", unlinkat(dir, "sub", AT_REMOVEDIR));
+	mkdir(ROOT "/empty", 0777);
+	print_result("remove of directory", remove(ROOT "/empty"));
+	print_result("remove of file", remove(ROOT "/file"));
+	print_result("remove of missing", remove(ROOT "/file"));
+	print_result("remove of full directory", remove(ROOT));
+	remove(ROOT "/soft");
+	close(dir);
+	print_result("rmdir", rmdir(ROOT));
+
+	print_result("Old umask was 022", umask(old_mask) == 022);
+	return 0;
+}
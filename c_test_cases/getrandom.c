@@ -0,0 +1,54 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/random.h>
+#include <unistd.h>
+
+static int all_zero(const unsigned char* buf, size_t len) {
+    for (size_t i = 0; i < len; i++) {
+        if (buf[i] != 0) {
+            return 0;
+        }
+    }
+    return 1;
+}
+
+int main(void) {
+    unsigned char buf[64] = {0};
+    printf("getrandom: %d\n", (int)getrandom(buf, sizeof(buf), 0));
+    printf("getrandom filled: %d\n", !all_zero(buf, sizeof(buf)));
+    printf("getrandom nonblock: %d\n", (int)getrandom(buf, 16, GRND_NONBLOCK));
+
+    memset(buf, 0, sizeof(buf));
+    printf("getentropy: %d\n", getentropy(buf, 32));
+    printf("getentropy filled: %d\n", !all_zero(buf, 32));
+    volatile size_t too_long = 257;
+    static unsigned char big[257];
+    printf("getentropy too long: %d\n", getentropy(big, too_long));
+
+    unsigned char first[32] = {0};
+    unsigned char second[32] = {0};
+    arc4random_buf(first, sizeof(first));
+    arc4random_buf(second, sizeof(second));
+    printf("arc4random_buf differs: %d\n", memcmp(first, second, sizeof(first)) != 0);
+
+    int in_range = 1;
+    for (int i = 0; i < 1000; i++) {
+        if (arc4random_uniform(10) >= 10) {
+            in_range = 0;
+        }
+    }
+    printf("arc4random_uniform in range: %d\n", in_range);
+    printf("arc4random_uniform(1): %d\n", (int)arc4random_uniform(1));
+    printf("arc4random_uniform(0): %d\n", (int)arc4random_uniform(0));
+
+    int distinct = 0;
+    unsigned int last = arc4random();
+    for (int i = 0; i < 10; i++) {
+        unsigned int next = arc4random();
+        distinct += next != last;
+        last = next;
+    }
+    printf("arc4random distinct: %d\n", distinct);
+    return 0;
+}
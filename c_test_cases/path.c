@@ -0,0 +1,131 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+// Declared here rather than with <libgen.h>, which would hide the GNU basename()
+char* dirname(char* path);
+char* __xpg_basename(char* path);
+
+#define ROOT "/tmp/cloyster_path_test"
+
+static void print_result(const char* name, const char* result) {
+	int saved_errno = errno;
+	if (result == NULL) {
+		printf("%s: NULL (%s)\n", name, strerrorname_np(saved_errno));
+	} else {
+		printf("%s: %s\n", name, result);
+	}
+	errno = 0;
+}
+
+static void test_getcwd(void) {
+	printf("chdir: %d\n", chdir(ROOT "/dir"));
+	char buf[256];
+	print_result("getcwd", getcwd(buf, sizeof(buf)));
+	char* allocated = getcwd(NULL, 0);
+	print_result("getcwd allocated", allocated);
+	free(allocated);
+	allocated = getcwd(NULL, 100);
+	print_result("getcwd allocated with size", allocated);
+	free(allocated);
+	print_result("getcwd into short buffer", getcwd(buf, 4));
+	print_result("getcwd allocated with short size", getcwd(NULL, 4));
+	print_result("getcwd with zero size", getcwd(buf, 0));
+
+	int fd = open(ROOT, O_RDONLY | O_DIRECTORY);
+	printf("fchdir: %d\n", fchdir(fd));
+	print_result("getcwd", getcwd(buf, sizeof(buf)));
+	close(fd);
+	errno = 0;
+	int result = chdir(ROOT "/missing");
+	int saved_errno = errno;
+	printf("chdir to missing: %d errno == ENOENT: %d\n", result, saved_errno == ENOENT);
+	errno = 0;
+	result = chdir(ROOT "/dir/file");
+	saved_errno = errno;
+	printf("chdir to file: %d errno == ENOTDIR: %d\n", result, saved_errno == ENOTDIR);
+	errno = 0;
+	result = fchdir(-1);
+	saved_errno = errno;
+	printf("fchdir to bad fd: %d errno == EBADF: %d\n", result, saved_errno == EBADF);
+}
+
+static void test_realpath(void) {
+	static const char* paths[] = {
+	    ROOT,
+	    ".",
+	    "dir/../dir/./file",
+	    "//tmp///cloyster_path_test/dir//",
+	    "link_to_dir/file",
+	    "link_to_dir/../link_to_file",
+	    "absolute_link/file",
+	    "chain",
+	    "/..",
+	    "",
+	    "missing",
+	    "dir/missing",
+	    "dangling",
+	    "loop",
+	    "dir/file/",
+	    "dir/file/..",
+	};
+	char buf[4096];
+	for (size_t i = 0; i < sizeof(paths) / sizeof(paths[0]); i++) {
+		printf("realpath(\"%s\")", paths[i]);
+		print_result("", realpath(paths[i], buf));
+	}
+	char* allocated = realpath("link_to_dir", NULL);
+	print_result("realpath allocated", allocated);
+	free(allocated);
+	print_result("realpath allocated of missing", realpath("missing", NULL));
+}
+
+static void test_libgen(void) {
+	static const char* paths[] = {
+	    "", "/", "//", "///", "/usr", "/usr/", "usr", "usr/", "/usr/lib", "//usr//lib//", "a//b", "..",
+	    "//a", "///a",
+	};
+	for (size_t i = 0; i < sizeof(paths) / sizeof(paths[0]); i++) {
+		char for_dirname[32];
+		char for_basename[32];
+		strcpy(for_dirname, paths[i]);
+		strcpy(for_basename, paths[i]);
+		const char* dir = dirname(for_dirname);
+		const char* base = __xpg_basename(for_basename);
+		printf("\"%s\": dirname \"%s\" basename \"%s\" GNU basename \"%s\"\n", paths[i], dir, base,
+		       basename(paths[i]));
+	}
+	const char* dir = dirname(NULL);
+	const char* base = __xpg_basename(NULL);
+	printf("NULL: dirname \"%s\" basename \"%s\"\n", dir, base);
+
+	// The result is part of the argument
+	char path[] = "/usr/lib/";
+	printf("dirname is in place: %d\n", dirname(path) == path);
+	strcpy(path, "/usr/lib/");
+	printf("basename is in place: %d\n", __xpg_basename(path) == path + 5);
+}
+
+int main(void) {
+	int status = system("rm -rf " ROOT " && mkdir -p " ROOT "/dir"
+	                    " && echo a > " ROOT "/dir/file"
+	                    " && ln -s dir " ROOT "/link_to_dir"
+	                    " && ln -s dir/file " ROOT "/link_to_file"
+	                    " && ln -s " ROOT "/dir " ROOT "/absolute_link"
+	                    " && ln -s link_to_dir/../link_to_file " ROOT "/chain"
+	                    " && ln -s nowhere " ROOT "/dangling"
+	                    " && ln -s loop " ROOT "/loop");
+	if (status != 0) {
+		printf("Couldn't create the test tree\n");
+		return 1;
+	}
+	test_getcwd();
+	test_realpath();
+	test_libgen();
+	system("rm -rf " ROOT);
+	return 0;
+}
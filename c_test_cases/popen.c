@@ -0,0 +1,87 @@
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/wait.h>
+
+#define OUTPUT_PATH "/tmp/cloyster_popen_test"
+
+// Read all of `stream` into `buf`
+static size_t read_all(FILE* stream, char* buf, size_t size) {
+	size_t total = 0;
+	size_t count;
+	while (total < size - 1 && (count = fread(buf + total, 1, size - 1 - total, stream)) > 0) {
+		total += count;
+	}
+	buf[total] = '\0';
+	return total;
+}
+
+static void test_system(void) {
+	printf("Shell available: %d\n", system(NULL) != 0);
+
+	int status = system("exit 3");
+	printf("exit 3: exited=%d status=%d\n", WIFEXITED(status), WEXITSTATUS(status));
+
+	status = system("kill -9 $$");
+	printf("kill -9: signaled=%d signal=%d\n", WIFSIGNALED(status), WTERMSIG(status));
+
+	// The caller ignores SIGINT while the command runs
+	status = system("kill -INT $PPID");
+	printf("kill -INT $PPID: exited=%d status=%d\n", WIFEXITED(status), WEXITSTATUS(status));
+
+	status = system("nonexistent_command_for_cloyster 2>/dev/null");
+	printf("Missing command: status=%d\n", WEXITSTATUS(status));
+}
+
+static void test_read(void) {
+	FILE* stream = popen("echo hello; echo world", "r");
+	if (stream == NULL) {
+		printf("popen failed\n");
+		exit(1);
+	}
+	char buf[64];
+	size_t count = read_all(stream, buf, sizeof(buf));
+	printf("Read %d bytes: %s", (int)count, buf);
+	int status = pclose(stream);
+	printf("pclose: exited=%d status=%d\n", WIFEXITED(status), WEXITSTATUS(status));
+}
+
+static void test_write(void) {
+	FILE* stream = popen("cat > " OUTPUT_PATH "; exit 5", "w");
+	if (stream == NULL) {
+		printf("popen failed\n");
+		exit(1);
+	}
+	const char message[] = "Written through a pipe\n";
+	size_t count = fwrite(message, 1, strlen(message), stream);
+	printf("Wrote %d bytes\n", (int)count);
+	int status = pclose(stream);
+	printf("pclose: exited=%d status=%d\n", WIFEXITED(status), WEXITSTATUS(status));
+
+	FILE* file = fopen(OUTPUT_PATH, "r");
+	if (file == NULL) {
+		printf("fopen failed\n");
+		exit(1);
+	}
+	char buf[64];
+	read_all(file, buf, sizeof(buf));
+	printf("Command wrote: %s", buf);
+	fclose(file);
+}
+
+static void test_bad_mode(void) {
+	errno = 0;
+	FILE* stream = popen("true", "x");
+	int saved_errno = errno;
+	printf("Bad mode: null=%d errno == EINVAL: %d\n", stream == NULL, saved_errno == EINVAL);
+}
+
+int main(void) {
+	test_system();
+	test_read();
+	test_write();
+	test_bad_mode();
+	return 0;
+}
@@ -0,0 +1,62 @@
+#define _GNU_SOURCE
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+static int compare_ints(const void* a, const void* b) {
+    int x = *(const int*)a;
+    int y = *(const int*)b;
+    return (x > y) - (x < y);
+}
+
+static int compare_strings(const void* a, const void* b) {
+    return strcmp(*(const char* const*)a, *(const char* const*)b);
+}
+
+// Sort by distance from `*arg`
+static int compare_distance(const void* a, const void* b, void* arg) {
+    int target = *(int*)arg;
+    int x = abs(*(const int*)a - target);
+    int y = abs(*(const int*)b - target);
+    return x != y ? (x > y) - (x < y) : compare_ints(a, b);
+}
+
+static void print_ints(const int* values, size_t len) {
+    for (size_t i = 0; i < len; i++) {
+        printf("%d ", values[i]);
+    }
+    printf("\n");
+}
+
+int main() {
+    int values[100];
+    unsigned state = 12345;
+    for (size_t i = 0; i < 100; i++) {
+        state = state * 1103515245 + 12345;
+        values[i] = (int)(state >> 16) % 1000 - 500;
+    }
+    qsort(values, 100, sizeof(int), compare_ints);
+    print_ints(values, 100);
+
+    int target = 0;
+    qsort_r(values, 100, sizeof(int), compare_distance, &target);
+    print_ints(values, 10);
+    qsort(values, 100, sizeof(int), compare_ints);
+
+    int key = values[42];
+    int* found = bsearch(&key, values, 100, sizeof(int), compare_ints);
+    printf("%d\n", found != nullptr && *found == key);
+    key = 1000;
+    printf("%d\n", bsearch(&key, values, 100, sizeof(int), compare_ints) == nullptr);
+
+    const char* words[] = {"oyster", "clam", "mussel", "scallop", "abalone", "cockle"};
+    qsort(words, 6, sizeof(char*), compare_strings);
+    for (size_t i = 0; i < 6; i++) {
+        printf("%s\n", words[i]);
+    }
+
+    // Nothing to sort
+    qsort(nullptr, 0, sizeof(int), compare_ints);
+    printf("%d\n", bsearch(&key, nullptr, 0, sizeof(int), compare_ints) == nullptr);
+    return 0;
+}
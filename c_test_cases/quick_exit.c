@@ -0,0 +1,27 @@
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+// stdio isn't flushed by quick_exit(), so write directly
+static void say(const char* s) {
+    write(STDOUT_FILENO, s, strlen(s));
+}
+
+static void first(void) {
+    say("first\n");
+}
+
+static void second(void) {
+    say("second\n");
+}
+
+static void not_run(void) {
+    say("atexit handlers don't run on quick_exit\n");
+}
+
+int main(void) {
+    atexit(not_run);
+    at_quick_exit(first);
+    at_quick_exit(second);
+    quick_exit(0);
+}
@@ -0,0 +1,55 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+static void print_rand(int count) {
+    for (int i = 0; i < count; i++) {
+        printf("%d ", rand());
+    }
+    printf("\n");
+}
+
+int main() {
+    // Unseeded, then seeded
+    print_rand(5);
+    srand(1);
+    print_rand(5);
+    srand(0);
+    print_rand(3);
+    srand(0xdeadbeef);
+    print_rand(3);
+
+    unsigned seed = 42;
+    for (int i = 0; i < 5; i++) {
+        printf("%d ", rand_r(&seed));
+    }
+    printf("%x\n", seed);
+
+    // Different state sizes pick different generators
+    static char small[8], medium[64], large[256];
+    char* original = initstate(7, small, sizeof(small));
+    printf("%d %d\n", (int)random(), (int)random());
+    initstate(7, medium, sizeof(medium));
+    printf("%d %d\n", (int)random(), (int)random());
+    initstate(7, large, sizeof(large));
+    printf("%d %d\n", (int)random(), (int)random());
+    setstate(medium);
+    printf("%d %d\n", (int)random(), (int)random());
+    setstate(original);
+    printf("%d %d\n", (int)random(), (int)random());
+    printf("%d\n", initstate(1, small, 4) == nullptr);
+
+    srand48(1234);
+    printf("%d %d %d\n", (int)lrand48(), (int)mrand48(), (int)(drand48() * 1000000));
+    unsigned short xsubi[3] = {1, 2, 3};
+    printf("%d %d %d\n", (int)nrand48(xsubi), (int)jrand48(xsubi), (int)(erand48(xsubi) * 1000000));
+    printf("%x %x %x\n", xsubi[0], xsubi[1], xsubi[2]);
+
+    unsigned short seed16v[3] = {4, 5, 6};
+    unsigned short* previous = seed48(seed16v);
+    printf("%x %x %x %d\n", previous[0], previous[1], previous[2], (int)lrand48());
+
+    unsigned short param[7] = {1, 2, 3, 0x1234, 0x5678, 0x9, 17};
+    lcong48(param);
+    printf("%d %d\n", (int)lrand48(), (int)mrand48());
+    return 0;
+}
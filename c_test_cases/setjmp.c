@@ -0,0 +1,67 @@
+#include <setjmp.h>
+#include <signal.h>
+#include <stdio.h>
+
+static jmp_buf env;
+static sigjmp_buf sigenv;
+
+static void leap(int val) {
+    longjmp(env, val);
+}
+
+static void jump(int depth, int val) {
+    if (depth > 0) {
+        jump(depth - 1, val);
+    } else {
+        leap(val);
+    }
+}
+
+static int usr1_blocked(void) {
+    sigset_t current;
+    sigprocmask(SIG_BLOCK, NULL, &current);
+    return sigismember(&current, SIGUSR1);
+}
+
+int main(void) {
+    volatile int calls = 0;
+    int val = setjmp(env);
+    calls++;
+    printf("setjmp returned %d (call %d)\n", val, calls);
+    if (val == 0) {
+        jump(10, 42);
+    } else if (val == 42) {
+        // longjmp() can't make setjmp() return 0
+        longjmp(env, 0);
+    }
+
+    // Callee-saved registers and the stack are restored
+    volatile double half = 0.5;
+    long locals[64];
+    for (int i = 0; i < 64; i++) {
+        locals[i] = i * i;
+    }
+    if (_setjmp(env) == 0) {
+        half *= 3;
+        _longjmp(env, 1);
+    }
+    printf("half: %d/2, locals[63]: %d\n", (int)(half * 2), (int)locals[63]);
+
+    // sigsetjmp() only restores the signal mask if asked to
+    sigset_t usr1;
+    sigemptyset(&usr1);
+    sigaddset(&usr1, SIGUSR1);
+    if (sigsetjmp(sigenv, 1) == 0) {
+        sigprocmask(SIG_BLOCK, &usr1, NULL);
+        printf("blocked before jumping: %d\n", usr1_blocked());
+        siglongjmp(sigenv, 1);
+    }
+    printf("blocked after jumping back with savemask: %d\n", usr1_blocked());
+
+    if (sigsetjmp(sigenv, 0) == 0) {
+        sigprocmask(SIG_BLOCK, &usr1, NULL);
+        siglongjmp(sigenv, 1);
+    }
+    printf("blocked after jumping back without savemask: %d\n", usr1_blocked());
+    return 0;
+}
@@ -0,0 +1,86 @@
+#include <setjmp.h>
+#include <signal.h>
+#include <stdint.h>
+#include <string.h>
+#include <unistd.h>
+
+static char alternate_stack[65536];
+static sigjmp_buf recover;
+
+// Write directly, so output is in order in spite of stdio buffering
+static void say(const char* s) {
+    write(STDOUT_FILENO, s, strlen(s));
+}
+
+static void on_segv(int sig) {
+    (void)sig;
+    char local;
+    uintptr_t address = (uintptr_t)&local;
+    uintptr_t start = (uintptr_t)alternate_stack;
+    say("caught stack overflow\n");
+    if (address >= start && address < start + sizeof(alternate_stack)) {
+        say("running on the alternate stack\n");
+    }
+
+    stack_t current;
+    sigaltstack(NULL, &current);
+    if (current.ss_flags & SS_ONSTACK) {
+        say("sigaltstack reports SS_ONSTACK\n");
+    }
+    siglongjmp(recover, 1);
+}
+
+static int recurse(int depth) {
+    volatile char frame[1024];
+    frame[0] = (char)depth;
+    if (depth < 0) {
+        return 0;
+    }
+    return recurse(depth + 1) + frame[0];
+}
+
+int main(void) {
+    stack_t old;
+    if (sigaltstack(NULL, &old) == 0 && (old.ss_flags & SS_DISABLE)) {
+        say("no alternate stack yet\n");
+    }
+
+    stack_t ss = {
+        .ss_sp = alternate_stack,
+        .ss_flags = 0,
+        .ss_size = sizeof(alternate_stack),
+    };
+    if (sigaltstack(&ss, NULL) != 0) {
+        say("sigaltstack failed\n");
+    }
+
+    stack_t too_small = ss;
+    too_small.ss_size = MINSIGSTKSZ - 1;
+    if (sigaltstack(&too_small, NULL) == -1) {
+        say("undersized stack rejected\n");
+    }
+
+    struct sigaction act;
+    memset(&act, 0, sizeof(act));
+    act.sa_handler = on_segv;
+    act.sa_flags = SA_ONSTACK;
+    sigemptyset(&act.sa_mask);
+    sigaction(SIGSEGV, &act, NULL);
+
+    if (sigsetjmp(recover, 1) == 0) {
+        say("overflowing the stack\n");
+        return recurse(0);
+    }
+    say("recovered\n");
+
+    sigset_t blocked;
+    sigprocmask(SIG_BLOCK, NULL, &blocked);
+    if (!sigismember(&blocked, SIGSEGV)) {
+        say("SIGSEGV unblocked again\n");
+    }
+    sigaltstack(NULL, &old);
+    if (!(old.ss_flags & SS_ONSTACK)) {
+        say("back on the main stack\n");
+    }
+    return 0;
+}
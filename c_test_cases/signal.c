@@ -0,0 +1,119 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+
+static volatile sig_atomic_t usr1_count = 0;
+static volatile sig_atomic_t usr2_count = 0;
+static volatile sig_atomic_t info_signo = 0;
+static volatile sig_atomic_t info_code = 0;
+static volatile sig_atomic_t info_pid_matches = 0;
+
+static void on_usr1(int sig) {
+    (void)sig;
+    usr1_count++;
+}
+
+static void on_usr2(int sig) {
+    (void)sig;
+    usr2_count++;
+}
+
+static void on_usr1_info(int sig, siginfo_t* info, void* context) {
+    (void)sig;
+    (void)context;
+    info_signo = info->si_signo;
+    info_code = info->si_code;
+    info_pid_matches = info->si_pid == getpid();
+}
+
+int main(void) {
+    // sigaction() with a plain handler
+    struct sigaction act;
+    memset(&act, 0, sizeof(act));
+    act.sa_handler = on_usr1;
+    sigemptyset(&act.sa_mask);
+    printf("sigaction: %d\n", sigaction(SIGUSR1, &act, NULL));
+    printf("raise: %d\n", raise(SIGUSR1));
+    printf("raise: %d\n", raise(SIGUSR1));
+    printf("SIGUSR1 handled %d times\n", usr1_count);
+
+    // SA_SIGINFO handlers get details about the signal
+    act.sa_sigaction = on_usr1_info;
+    act.sa_flags = SA_SIGINFO;
+    struct sigaction old;
+    printf("sigaction: %d\n", sigaction(SIGUSR1, &act, &old));
+    printf("old handler matches: %d\n", old.sa_handler == on_usr1);
+    printf("kill: %d\n", kill(getpid(), SIGUSR1));
+    printf("si_signo: %d, si_code: %d, si_pid matches: %d\n", info_signo, info_code,
+           info_pid_matches);
+    sigaction(SIGUSR1, NULL, &old);
+    printf("SA_SIGINFO set: %d\n", (old.sa_flags & SA_SIGINFO) != 0);
+
+    // signal() returns the previous handler
+    printf("signal returned SIG_DFL: %d\n", signal(SIGUSR2, on_usr2) == SIG_DFL);
+    printf("signal returned on_usr2: %d\n", signal(SIGUSR2, on_usr2) == on_usr2);
+    raise(SIGUSR2);
+    printf("SIGUSR2 handled %d times\n", usr2_count);
+
+    // Blocked signals stay pending until they're unblocked
+    sigset_t set;
+    sigset_t previous;
+    sigemptyset(&set);
+    sigaddset(&set, SIGUSR2);
+    printf("sigprocmask: %d\n", sigprocmask(SIG_BLOCK, &set, &previous));
+    printf("SIGUSR2 was blocked: %d\n", sigismember(&previous, SIGUSR2));
+    raise(SIGUSR2);
+    printf("SIGUSR2 handled %d times while blocked\n", usr2_count);
+    sigset_t pending;
+    sigpending(&pending);
+    printf("SIGUSR2 pending: %d, SIGUSR1 pending: %d\n", sigismember(&pending, SIGUSR2),
+           sigismember(&pending, SIGUSR1));
+    printf("pthread_sigmask: %d\n", pthread_sigmask(SIG_UNBLOCK, &set, NULL));
+    printf("SIGUSR2 handled %d times after unblocking\n", usr2_count);
+
+    // sigsuspend() returns once a handler has run
+    sigprocmask(SIG_BLOCK, &set, NULL);
+    raise(SIGUSR2);
+    sigemptyset(&set);
+    errno = 0;
+    int res = sigsuspend(&set);
+    printf("sigsuspend: %d, EINTR: %d, handled %d times\n", res, errno == EINTR, usr2_count);
+    sigprocmask(SIG_SETMASK, &set, NULL);
+
+    // Ignored signals are discarded
+    signal(SIGUSR2, SIG_IGN);
+    raise(SIGUSR2);
+    printf("SIGUSR2 handled %d times after ignoring\n", usr2_count);
+
+    // Signal set manipulation
+    sigfillset(&set);
+    printf("full set has SIGINT: %d, SIGRTMIN: %d\n", sigismember(&set, SIGINT),
+           sigismember(&set, SIGRTMIN));
+    sigdelset(&set, SIGINT);
+    printf("after sigdelset: %d\n", sigismember(&set, SIGINT));
+    sigemptyset(&set);
+    printf("empty: %d\n", sigisemptyset(&set));
+    // Arguments aren't evaluated in order, so errno is checked separately
+    errno = 0;
+    res = sigaddset(&set, 0);
+    printf("sigaddset(0): %d, EINVAL: %d\n", res, errno == EINVAL);
+    errno = 0;
+    res = sigaddset(&set, 65);
+    printf("sigaddset(65): %d, EINVAL: %d\n", res, errno == EINVAL);
+    errno = 0;
+    res = sigaction(SIGKILL, &act, NULL);
+    printf("sigaction(SIGKILL): %d, EINVAL: %d\n", res, errno == EINVAL);
+    errno = 0;
+    res = signal(0, on_usr1) == SIG_ERR;
+    printf("signal(0) failed: %d, EINVAL: %d\n", res, errno == EINVAL);
+
+    // Descriptions
+    int sigs[] = {SIGHUP, SIGABRT, SIGUSR1, SIGCHLD, SIGSYS, 32, SIGRTMIN, SIGRTMAX, 0, 65};
+    for (size_t i = 0; i < sizeof(sigs) / sizeof(sigs[0]); i++) {
+        printf("%d: %s\n", sigs[i], strsignal(sigs[i]));
+    }
+    return 0;
+}
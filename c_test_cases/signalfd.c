@@ -0,0 +1,41 @@
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <sys/signalfd.h>
+#include <unistd.h>
+
+int main(void) {
+    sigset_t mask;
+    sigemptyset(&mask);
+    sigaddset(&mask, SIGUSR1);
+    sigaddset(&mask, SIGUSR2);
+    sigprocmask(SIG_BLOCK, &mask, NULL);
+
+    int fd = signalfd(-1, &mask, SFD_NONBLOCK | SFD_CLOEXEC);
+    printf("signalfd: %d\n", fd >= 0);
+
+    struct signalfd_siginfo info;
+    errno = 0;
+    int count = read(fd, &info, sizeof(info));
+    printf("empty read: %d, EAGAIN: %d\n", count, errno == EAGAIN);
+
+    raise(SIGUSR2);
+    kill(getpid(), SIGUSR1);
+    for (int i = 0; i < 2; i++) {
+        count = read(fd, &info, sizeof(info));
+        printf("read %d bytes: signal %d, code %d, from us: %d\n", count, (int)info.ssi_signo,
+               info.ssi_code, info.ssi_pid == (uint32_t)getpid());
+    }
+
+    // Stop watching SIGUSR1
+    sigdelset(&mask, SIGUSR1);
+    printf("update mask: %d\n", signalfd(fd, &mask, 0) == fd);
+    raise(SIGUSR1);
+    errno = 0;
+    count = read(fd, &info, sizeof(info));
+    printf("SIGUSR1 not read: %d, EAGAIN: %d\n", count, errno == EAGAIN);
+
+    printf("bad fd: %d\n", signalfd(12345, &mask, 0));
+    close(fd);
+    return 0;
+}
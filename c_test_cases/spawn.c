@@ -0,0 +1,125 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <fcntl.h>
+#include <signal.h>
+#include <spawn.h>
+#include <stdio.h>
+#include <sys/wait.h>
+
+#define OUTPUT "/tmp/cloyster_spawn_test"
+
+static char* const environment[] = {"PATH=/usr/bin:/bin", NULL};
+
+// Run `script` with sh, with its output going to OUTPUT, then print the output and exit status
+static void run(const char* name, const char* script, posix_spawn_file_actions_t* actions,
+    const posix_spawnattr_t* attr) {
+	char* const argv[] = {"sh", "-c", (char*)script, NULL};
+	posix_spawn_file_actions_addopen(actions, 1, OUTPUT, O_WRONLY | O_CREAT | O_TRUNC, 0644);
+
+	pid_t pid;
+	int error = posix_spawnp(&pid, "sh", actions, attr, argv, environment);
+	if (error != 0) {
+		printf("%s: spawn failed: %d\n", name, error);
+		return;
+	}
+	int status;
+	if (waitpid(pid, &status, 0) != pid) {
+		printf("%s: waitpid failed\n", name);
+		return;
+	}
+
+	char buffer[256] = {0};
+	FILE* file = fopen(OUTPUT, "r");
+	size_t count = fread(buffer, 1, sizeof(buffer) - 1, file);
+	buffer[count] = '\0';
+	fclose(file);
+	printf("%s: status %d\n%s", name, WEXITSTATUS(status), buffer);
+}
+
+int main(void) {
+	posix_spawn_file_actions_t actions;
+	posix_spawnattr_t attr;
+
+	posix_spawn_file_actions_init(&actions);
+	run("plain", "echo hello; exit 3", &actions, NULL);
+	posix_spawn_file_actions_destroy(&actions);
+
+	// Descriptors are set up in order
+	posix_spawn_file_actions_init(&actions);
+	posix_spawn_file_actions_addopen(&actions, 5, "/dev/null", O_RDONLY, 0);
+	posix_spawn_file_actions_adddup2(&actions, 5, 6);
+	posix_spawn_file_actions_addclose(&actions, 5);
+	posix_spawn_file_actions_addchdir_np(&actions, "/");
+	run("file actions",
+		"[ -e /proc/self/fd/5 ] || echo 5 closed; [ -e /proc/self/fd/6 ] && echo 6 open; pwd",
+		&actions, NULL);
+	posix_spawn_file_actions_destroy(&actions);
+
+	// The child blocks SIGUSR1 instead of inheriting the parent's mask
+	sigset_t set;
+	sigemptyset(&set);
+	sigaddset(&set, SIGUSR2);
+	sigprocmask(SIG_BLOCK, &set, NULL);
+	posix_spawnattr_init(&attr);
+	sigemptyset(&set);
+	sigaddset(&set, SIGUSR1);
+	posix_spawnattr_setsigmask(&attr, &set);
+	posix_spawnattr_setflags(&attr, POSIX_SPAWN_SETSIGMASK);
+	posix_spawn_file_actions_init(&actions);
+	run("sigmask", "exec grep SigBlk /proc/self/status", &actions, &attr);
+	posix_spawn_file_actions_destroy(&actions);
+	posix_spawnattr_destroy(&attr);
+
+	// Ignored signals stay ignored, unless they're reset. glibc ignores its internal signals in the
+	// child, so only look at the first 28
+	signal(SIGTERM, SIG_IGN);
+	signal(SIGHUP, SIG_IGN);
+	posix_spawnattr_init(&attr);
+	sigemptyset(&set);
+	sigaddset(&set, SIGTERM);
+	posix_spawnattr_setsigdefault(&attr, &set);
+	posix_spawnattr_setflags(&attr, POSIX_SPAWN_SETSIGDEF);
+	posix_spawn_file_actions_init(&actions);
+	run("sigdefault", "grep SigIgn /proc/self/status | cut -c 18-", &actions, &attr);
+	posix_spawn_file_actions_destroy(&actions);
+	posix_spawnattr_destroy(&attr);
+
+	// The child leads its own process group, and then its own session
+	posix_spawnattr_init(&attr);
+	posix_spawnattr_setpgroup(&attr, 0);
+	posix_spawnattr_setflags(&attr, POSIX_SPAWN_SETPGROUP);
+	posix_spawn_file_actions_init(&actions);
+	run("pgroup",
+		"read pid comm state ppid pgrp sid rest < /proc/self/stat; [ $pgrp = $$ ] && echo leader",
+		&actions, &attr);
+	posix_spawn_file_actions_destroy(&actions);
+	posix_spawnattr_setflags(&attr, POSIX_SPAWN_SETSID);
+	posix_spawn_file_actions_init(&actions);
+	run("setsid",
+		"read pid comm state ppid pgrp sid rest < /proc/self/stat; [ $sid = $$ ] && echo leader",
+		&actions, &attr);
+	posix_spawn_file_actions_destroy(&actions);
+
+	short flags;
+	posix_spawnattr_getflags(&attr, &flags);
+	printf("flags: %d\n", flags);
+	posix_spawnattr_destroy(&attr);
+
+	// Failures are reported to the caller
+	char* const argv[] = {"missing", NULL};
+	pid_t pid;
+	int error = posix_spawn(&pid, "/nonexistent/program", NULL, NULL, argv, environment);
+	printf("missing: %d\n", error == ENOENT);
+	printf("missing from PATH: %d\n",
+		posix_spawnp(&pid, "cloyster-no-such-program", NULL, NULL, argv, environment) == ENOENT);
+	posix_spawn_file_actions_init(&actions);
+	posix_spawn_file_actions_addopen(&actions, 3, "/nonexistent/file", O_RDONLY, 0);
+	error = posix_spawn(&pid, "/bin/true", &actions, NULL, argv, environment);
+	printf("bad file action: %d\n", error == ENOENT);
+	printf("bad fd: %d\n", posix_spawn_file_actions_addclose(&actions, -1) == EBADF);
+	posix_spawn_file_actions_destroy(&actions);
+
+	// Nothing was left behind
+	printf("children: %d\n", wait(NULL) == -1 && errno == ECHILD);
+	return 0;
+}
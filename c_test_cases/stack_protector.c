@@ -0,0 +1,31 @@
+// Protect every function, so the canary set up at startup is checked on each return
+#pragma GCC optimize("stack-protector-all")
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+static int copy_and_measure(const char* string) {
+	char buffer[32];
+	strcpy(buffer, string);
+	return (int)strlen(buffer);
+}
+
+static int nested(int depth) {
+	char buffer[8] = "nested";
+	if (depth == 0) {
+		return (int)strlen(buffer);
+	}
+	return nested(depth - 1) + 1;
+}
+
+// Runs after main() returns, so the canary must still be readable
+static void at_exit(void) {
+	printf("%d\n", copy_and_measure("Goodbye, world!"));
+}
+
+int main(void) {
+	atexit(at_exit);
+	printf("%d\n", copy_and_measure("Hello, world!"));
+	printf("%d\n", nested(10));
+	return 0;
+}
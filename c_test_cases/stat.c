@@ -0,0 +1,92 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+#define PATH "/tmp/cloyster_stat_test"
+
+static void print_type(const char* name, mode_t mode) {
+	printf("%s: reg=%d dir=%d chr=%d fifo=%d lnk=%d\n", name, S_ISREG(mode), S_ISDIR(mode),
+	       S_ISCHR(mode), S_ISFIFO(mode), S_ISLNK(mode));
+}
+
+int main(void) {
+	int fd = open(PATH, O_RDWR | O_CREAT | O_TRUNC, 0600);
+	if (fd < 0) {
+		printf("open failed\n");
+		return 1;
+	}
+	write(fd, "Twelve bytes", 12);
+
+	struct stat by_fd;
+	printf("fstat: %d\n", fstat(fd, &by_fd));
+	print_type("File", by_fd.st_mode);
+	printf("Size: %d\n", (int)by_fd.st_size);
+	printf("Permissions are 0600: %d\n", (by_fd.st_mode & 0777) == 0600);
+	printf("Links: %d\n", (int)by_fd.st_nlink);
+	printf("Has block size: %d\n", by_fd.st_blksize > 0);
+
+	struct stat by_path;
+	printf("stat: %d\n", stat(PATH, &by_path));
+	printf("Same inode: %d\n", by_path.st_ino == by_fd.st_ino && by_path.st_dev == by_fd.st_dev);
+	printf("Same mtime: %d\n", by_path.st_mtim.tv_sec == by_fd.st_mtim.tv_sec &&
+	                               by_path.st_mtim.tv_nsec == by_fd.st_mtim.tv_nsec);
+
+	int dir = open("/tmp", O_RDONLY);
+	struct stat relative;
+	printf("fstatat: %d\n", fstatat(dir, "cloyster_stat_test", &relative, 0));
+	printf("Same inode: %d\n", relative.st_ino == by_fd.st_ino);
+	printf("fstatat with AT_EMPTY_PATH: %d\n", fstatat(dir, "", &relative, AT_EMPTY_PATH));
+	print_type("/tmp", relative.st_mode);
+	close(dir);
+
+	struct stat other;
+	stat("/dev/null", &other);
+	print_type("/dev/null", other.st_mode);
+	int fds[2];
+	pipe(fds);
+	fstat(fds[0], &other);
+	print_type("Pipe", other.st_mode);
+	close(fds[0]);
+	close(fds[1]);
+
+	// Only lstat() looks at the link itself
+	stat("/proc/self/exe", &other);
+	print_type("stat of link", other.st_mode);
+	lstat("/proc/self/exe", &other);
+	print_type("lstat of link", other.st_mode);
+	fstatat(AT_FDCWD, "/proc/self/exe", &other, AT_SYMLINK_NOFOLLOW);
+	print_type("fstatat of link", other.st_mode);
+
+	struct statx extended;
+	int result = statx(AT_FDCWD, PATH, 0, STATX_TYPE | STATX_SIZE, &extended);
+	printf("statx: %d\n", result);
+	printf("Has size and type: %d\n",
+	       (extended.stx_mask & (STATX_TYPE | STATX_SIZE)) == (STATX_TYPE | STATX_SIZE));
+	print_type("statx", extended.stx_mode);
+	printf("Size: %d\n", (int)extended.stx_size);
+	result = statx(fd, "", AT_EMPTY_PATH, STATX_INO, &extended);
+	printf("statx with AT_EMPTY_PATH: %d same inode: %d\n", result,
+	       extended.stx_ino == by_fd.st_ino);
+	close(fd);
+
+	errno = 0;
+	result = stat("/tmp/cloyster_no_such_file", &other);
+	int saved_errno = errno;
+	printf("Missing file: %d errno == ENOENT: %d\n", result, saved_errno == ENOENT);
+	errno = 0;
+	result = stat(PATH "/child", &other);
+	saved_errno = errno;
+	printf("Through a file: %d errno == ENOTDIR: %d\n", result, saved_errno == ENOTDIR);
+	errno = 0;
+	result = fstat(-1, &other);
+	saved_errno = errno;
+	printf("Bad fd: %d errno == EBADF: %d\n", result, saved_errno == EBADF);
+	errno = 0;
+	result = statx(AT_FDCWD, PATH, 0, 0x80000000U, &extended);
+	saved_errno = errno;
+	printf("Reserved statx mask: %d errno == EINVAL: %d\n", result, saved_errno == EINVAL);
+	return 0;
+}
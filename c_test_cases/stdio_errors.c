@@ -0,0 +1,61 @@
+// Error paths of stdio and malloc, which must report standard errno values
+#define _GNU_SOURCE
+#include <errno.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+static void report(const char* what, long ret) {
+    printf("%s: %d %s\n", what, (int)ret, strerrorname_np(errno));
+    errno = 0;
+}
+
+int main() {
+    errno = 0;
+    FILE* fp = fopen("/nonexistent/file", "r");
+    report("fopen", fp == nullptr);
+
+    fp = fopen(__FILE__, "r");
+    report("ferror on open", ferror(fp) != 0);
+
+    // Read only whole items
+    char buffer[16] = {0};
+    report("fread", (long)fread(buffer, 4, 3, fp));
+    printf("%s\n", buffer);
+
+    int c;
+    long count = 0;
+    while ((c = getc(fp)) != EOF) {
+        count++;
+    }
+    report("getc to end", count > 100);
+    report("feof", feof(fp) != 0);
+    report("ferror at end", ferror(fp) != 0);
+    report("fread at end", (long)fread(buffer, 1, 4, fp));
+
+    clearerr(fp);
+    report("feof after clearerr", feof(fp) != 0);
+
+    // The stream is read-only
+    report("fwrite", (long)fwrite("x", 1, 1, fp));
+    report("ferror after fwrite", ferror(fp) != 0);
+    clearerr(fp);
+    report("fputc", fputc('x', fp));
+    report("ferror after fputc", ferror(fp) != 0);
+    report("fclose", fclose(fp));
+
+    // snprintf truncates, but returns the untruncated length
+    memset(buffer, 'x', sizeof(buffer));
+    report("snprintf", snprintf(buffer, 4, "[%d]", 12345));
+    printf("%s\n", buffer);
+    report("empty snprintf", snprintf(buffer, sizeof(buffer), ""));
+    printf("[%s]\n", buffer);
+
+    // Hide the size from the compiler, which rejects impossible allocations
+    volatile size_t huge = SIZE_MAX;
+    report("malloc", malloc(huge) == nullptr);
+    report("calloc", calloc(huge, 2) == nullptr);
+
+    return 0;
+}
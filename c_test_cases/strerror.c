@@ -0,0 +1,21 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+
+int main() {
+    for (int i = 0; i < 140; i++) {
+        const char* name = strerrorname_np(i);
+        printf("%d %s: %s\n", i, name ? name : "(none)", strerror(i));
+    }
+    printf("%s\n", strerror(-1));
+
+    char buf[8];
+    printf("%s\n", strerror_r(EPERM, buf, sizeof(buf)));
+    printf("%s\n", strerror_r(4096, buf, sizeof(buf)));
+
+    errno = ENOENT;
+    printf("errno: %d\n", errno);
+    perror("perror");
+    return 0;
+}
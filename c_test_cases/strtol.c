@@ -0,0 +1,63 @@
+#include <errno.h>
+#include <limits.h>
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+static const char* end;
+
+static void check_long(const char* s, int base) {
+    errno = 0;
+    long value = strtol(s, (char**)&end, base);
+    printf("strtol(\"%s\", %d) = %x%x, end +%d, errno %d\n", s, base,
+           (unsigned)((unsigned long)value >> 32), (unsigned)value, (int)(end - s), errno);
+}
+
+static void check_ulong(const char* s, int base) {
+    errno = 0;
+    unsigned long value = strtoul(s, (char**)&end, base);
+    printf("strtoul(\"%s\", %d) = %x%x, end +%d, errno %d\n", s, base, (unsigned)(value >> 32),
+           (unsigned)value, (int)(end - s), errno);
+}
+
+static void check_double(const char* s, double expected) {
+    errno = 0;
+    double value = strtod(s, (char**)&end);
+    int same = value == expected || (isnan(value) && isnan(expected));
+    printf("strtod(\"%s\") %s, negative %d, end +%d, errno %d\n", s, same ? "ok" : "WRONG",
+           (int)!!signbit(value), (int)(end - s), errno);
+}
+
+int main() {
+    check_long("  -42abc", 10);
+    check_long("+0x1F", 0);
+    check_long("0x", 16);
+    check_long("0777", 0);
+    check_long("zZ", 36);
+    check_long("9223372036854775808", 10);
+    check_long("-9223372036854775809", 10);
+    check_long("   ", 10);
+    check_long("10", 1);
+    check_ulong("-1", 10);
+    check_ulong("18446744073709551616", 10);
+    printf("%d %d %d\n", atoi(" 42abc"), atoi("+7"), atoi("abc"));
+    printf("%d\n", (int)atoll("-1099511627776") == 0 && atoll("-1099511627776") < 0);
+
+    check_double("  1.5e3x", 1500.0);
+    check_double("-.5", -0.5);
+    check_double("0.1", 0.1);
+    check_double("1e", 1.0);
+    check_double("0x1.8p1", 3.0);
+    check_double("0X.4P-1", 0.125);
+    check_double("0x1.fffffffffffff8p0", 2.0);
+    check_double("1e400", HUGE_VAL);
+    check_double("-1e-400", -0.0);
+    check_double("4e-320", 4e-320);
+    check_double("-Infinity", -INFINITY);
+    check_double("nan(123)", NAN);
+    check_double("nanny", NAN);
+    check_double("junk", 0.0);
+    printf("%d\n", strtof("0x1.000001p0", nullptr) == 1.0f);
+    printf("%d\n", atof("  2.5e-1 ") == 0.25);
+    return 0;
+}
@@ -0,0 +1,93 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/uio.h>
+#include <unistd.h>
+
+#define PATH "/tmp/cloyster_uio_test"
+
+static void print_position(int fd) {
+	printf("Position: %d\n", (int)lseek(fd, 0, SEEK_CUR));
+}
+
+int main(void) {
+	int fd = open(PATH, O_RDWR | O_CREAT | O_TRUNC, 0644);
+	if (fd < 0) {
+		printf("open failed\n");
+		return 1;
+	}
+
+	char first[] = "Hello";
+	char second[] = ", ";
+	char third[] = "world!";
+	struct iovec out[] = {
+		{.iov_base = first, .iov_len = 5},
+		{.iov_base = second, .iov_len = 2},
+		{.iov_base = third, .iov_len = 6},
+	};
+	printf("writev: %d\n", (int)writev(fd, out, 3));
+	print_position(fd);
+
+	lseek(fd, 0, SEEK_SET);
+	char a[4] = {0};
+	char b[16] = {0};
+	struct iovec in[] = {
+		{.iov_base = a, .iov_len = 3},
+		{.iov_base = b, .iov_len = 15},
+	};
+	printf("readv: %d %s|%s\n", (int)readv(fd, in, 2), a, b);
+	print_position(fd);
+
+	// Positional I/O leaves the file offset alone
+	lseek(fd, 2, SEEK_SET);
+	printf("pwrite: %d\n", (int)pwrite(fd, "J", 1, 0));
+	char buf[16] = {0};
+	printf("pread: %d %s\n", (int)pread(fd, buf, 5, 0), buf);
+	print_position(fd);
+
+	char x[] = "--";
+	char y[] = "++";
+	struct iovec patch[] = {{.iov_base = x, .iov_len = 2}, {.iov_base = y, .iov_len = 2}};
+	printf("pwritev: %d\n", (int)pwritev(fd, patch, 2, 7));
+	char c[5] = {0};
+	char d[5] = {0};
+	struct iovec halves[] = {{.iov_base = c, .iov_len = 4}, {.iov_base = d, .iov_len = 4}};
+	printf("preadv: %d %s|%s\n", (int)preadv(fd, halves, 2, 5), c, d);
+	print_position(fd);
+
+	// An offset of -1 uses and moves the file offset
+	char e[4] = {0};
+	struct iovec one[] = {{.iov_base = e, .iov_len = 3}};
+	printf("preadv2 at -1: %d %s\n", (int)preadv2(fd, one, 1, -1, 0), e);
+	print_position(fd);
+	printf("preadv2 at 0: %d %s\n", (int)preadv2(fd, one, 1, 0, 0), e);
+	print_position(fd);
+
+	char tail[] = "?";
+	struct iovec append[] = {{.iov_base = tail, .iov_len = 1}};
+	printf("pwritev2 appending: %d\n", (int)pwritev2(fd, append, 1, 0, RWF_APPEND));
+	printf("pread: %d %s\n", (int)pread(fd, buf, sizeof(buf) - 1, 0), buf);
+
+	// Hidden from the compiler, which rejects a literal negative count
+	volatile int negative = -1;
+	errno = 0;
+	int result = readv(fd, in, negative);
+	int saved_errno = errno;
+	printf("readv with negative count: %d errno == EINVAL: %d\n", result, saved_errno == EINVAL);
+	errno = 0;
+	result = pread(fd, buf, 1, -1);
+	saved_errno = errno;
+	printf("pread at negative offset: %d errno == EINVAL: %d\n", result, saved_errno == EINVAL);
+	printf("readv of nothing: %d\n", (int)readv(fd, NULL, 0));
+	close(fd);
+
+	errno = 0;
+	result = writev(fd, out, 3);
+	saved_errno = errno;
+	printf("writev to closed fd: %d errno == EBADF: %d\n", result, saved_errno == EBADF);
+
+	puts("puts writes the line and newline together");
+	return 0;
+}
@@ -1,6 +1,9 @@
-use core::ffi::{CStr, c_char, c_uint};
+use core::{
+    ffi::{CStr, c_char, c_uint},
+    fmt::Write,
+};
 // Abort the program after false assertion
-//a
+//
 // C signature:
 // `void __assert_fail(const char * assertion, const char * file, unsigned int line, const char *
 // function);`
@@ -12,12 +15,16 @@ unsafe extern "C" fn __assert_fail(
     file: *const c_char,
     line: c_uint,
     function: *const c_char,
-) {
+) -> ! {
     assert!(!assertion.is_null());
     assert!(!file.is_null());
     assert!(!function.is_null());
-    let assertion = unsafe { CStr::from_ptr(assertion) }.to_str().unwrap();
-    let file = unsafe { CStr::from_ptr(file) }.to_str().unwrap();
-    let function = unsafe { CStr::from_ptr(function) }.to_str().unwrap();
-    panic!("{file}:{line}: {function}: Assertion `{assertion}` failed");
+    let assertion = unsafe { CStr::from_ptr(assertion) }.to_str().unwrap_or("?");
+    let file = unsafe { CStr::from_ptr(file) }.to_str().unwrap_or("?");
+    let function = unsafe { CStr::from_ptr(function) }.to_str().unwrap_or("?");
+    let _ = writeln!(
+        shellder::stdio::Descriptor::stderr(),
+        "{file}:{line}: {function}: Assertion `{assertion}' failed."
+    );
+    shellder::stdlib::abort();
 }
@@ -19,5 +19,10 @@ unsafe extern "C" fn __errno_location() -> *const c_int {
 
 pub(crate) fn set_errno(val: Errno) {
     let errno = ERRNO.lock();
-    *errno.borrow_mut() = val.as_positive();
+    *errno.borrow_mut() = val.to_posix().as_positive();
+}
+
+pub(crate) fn get_errno() -> c_int {
+    let errno = ERRNO.lock();
+    *errno.borrow()
 }
@@ -0,0 +1,47 @@
+//! Translation of shellder results into C return conventions
+//!
+//! Shellder reports failures as [Errno]s, some of which are Cloyster-specific and mean nothing to
+//! C. Exports return a sentinel value instead, with `errno` set to the POSIX equivalent of the
+//! error (see [Errno::to_posix])
+use crate::errno::set_errno;
+use shellder::Errno;
+
+pub(crate) trait CResult<T> {
+    /// Return the success value, or set `errno` and return `failure`
+    fn or_errno(self, failure: T) -> T;
+}
+
+impl<T> CResult<T> for Result<T, Errno> {
+    fn or_errno(self, failure: T) -> T {
+        self.unwrap_or_else(|err| {
+            set_errno(err);
+            failure
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errno::get_errno;
+
+    #[test]
+    fn translates_errors() {
+        assert_eq!(Ok(5).or_errno(-1), 5);
+
+        for (err, posix) in [
+            (Errno::ENOENT, Errno::ENOENT),
+            (Errno::CloysterFmtError, Errno::EIO),
+            (Errno::CloysterSyscallFailed, Errno::EIO),
+            (Errno::CloysterUnicodeError, Errno::EILSEQ),
+            (Errno::CloysterParseError, Errno::EINVAL),
+            (Errno::CloysterConversionError, Errno::EOVERFLOW),
+            (Errno::CloysterOverflow, Errno::EOVERFLOW),
+            (Errno::CloysterBufferOverflow, Errno::ERANGE),
+            (Errno::CloysterAlloc, Errno::ENOMEM),
+        ] {
+            assert_eq!(Err(err).or_errno(-1), -1);
+            assert_eq!(get_errno(), posix.as_positive());
+        }
+    }
+}
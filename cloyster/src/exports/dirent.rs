@@ -0,0 +1,181 @@
+use super::c_result::CResult;
+use alloc::vec::Vec;
+use core::{
+    ffi::{CStr, c_char, c_int, c_long},
+    mem,
+    ptr::{self, NonNull},
+};
+use shellder::{
+    Errno,
+    dirent::{self, Dir},
+    malloc::{free, malloc},
+    types::*,
+};
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn opendir(name: *const c_char) -> *mut Dir {
+    assert!(!name.is_null());
+    dirent::opendir(unsafe { CStr::from_ptr(name) })
+        .map(NonNull::as_ptr)
+        .or_errno(ptr::null_mut())
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fdopendir(fd: c_int) -> *mut Dir {
+    dirent::fdopendir(fd)
+        .map(NonNull::as_ptr)
+        .or_errno(ptr::null_mut())
+}
+
+// Returns NULL without changing `errno` at the end of the directory
+#[unsafe(no_mangle)]
+unsafe extern "C" fn readdir(dirp: Option<NonNull<Dir>>) -> *mut Dirent {
+    let dirp = dirp.expect("Unexpected null arg to `readdir()`");
+    unsafe { dirent::readdir(dirp) }
+        .map(|entry| entry.map_or(ptr::null_mut(), NonNull::as_ptr))
+        .or_errno(ptr::null_mut())
+}
+
+// Returns an error number instead of setting `errno`
+#[unsafe(no_mangle)]
+unsafe extern "C" fn readdir_r(
+    dirp: Option<NonNull<Dir>>,
+    entry: Option<NonNull<Dirent>>,
+    result: Option<NonNull<*mut Dirent>>,
+) -> c_int {
+    let dirp = dirp.expect("Unexpected null arg to `readdir_r()`");
+    let entry = entry.expect("Unexpected null arg to `readdir_r()`");
+    let mut result = result.expect("Unexpected null arg to `readdir_r()`");
+    let (found, err) = match unsafe { dirent::readdir(dirp) } {
+        Ok(Some(found)) => {
+            unsafe { *entry.as_ptr() = *found.as_ptr() };
+            (entry.as_ptr(), 0)
+        }
+        Ok(None) => (ptr::null_mut(), 0),
+        Err(err) => (ptr::null_mut(), err.as_positive()),
+    };
+    unsafe { *result.as_mut() = found };
+    err
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn rewinddir(dirp: Option<NonNull<Dir>>) {
+    let dirp = dirp.expect("Unexpected null arg to `rewinddir()`");
+    let _ = unsafe { dirent::rewinddir(dirp) };
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn seekdir(dirp: Option<NonNull<Dir>>, loc: c_long) {
+    let dirp = dirp.expect("Unexpected null arg to `seekdir()`");
+    let _ = unsafe { dirent::seekdir(dirp, loc as off_t) };
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn telldir(dirp: Option<NonNull<Dir>>) -> c_long {
+    let dirp = dirp.expect("Unexpected null arg to `telldir()`");
+    unsafe { dirent::telldir(dirp) as c_long }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn dirfd(dirp: Option<NonNull<Dir>>) -> c_int {
+    let dirp = dirp.expect("Unexpected null arg to `dirfd()`");
+    unsafe { dirent::dirfd(dirp) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn closedir(dirp: Option<NonNull<Dir>>) -> c_int {
+    let dirp = dirp.expect("Unexpected null arg to `closedir()`");
+    unsafe { dirent::closedir(dirp) }.map(|()| 0).or_errno(-1)
+}
+
+type Filter = Option<unsafe extern "C" fn(*const Dirent) -> c_int>;
+type Comparator = Option<unsafe extern "C" fn(*const *const Dirent, *const *const Dirent) -> c_int>;
+
+// Copy the entries of `dir` that pass `filter` to the heap
+unsafe fn collect_entries(
+    dir: NonNull<Dir>,
+    filter: Filter,
+) -> Result<Vec<NonNull<Dirent>>, Errno> {
+    let mut entries = Vec::new();
+    let result = (|| {
+        while let Some(entry) = unsafe { dirent::readdir(dir)? } {
+            if filter.is_some_and(|filter| unsafe { filter(entry.as_ptr()) } == 0) {
+                continue;
+            }
+            entries.try_reserve(1).map_err(|_| Errno::ENOMEM)?;
+            let copy = malloc(mem::size_of::<Dirent>())?.cast::<Dirent>();
+            unsafe { *copy.as_ptr() = *entry.as_ptr() };
+            entries.push(copy);
+        }
+        Ok(())
+    })();
+    match result {
+        Ok(()) => Ok(entries),
+        Err(err) => {
+            for entry in entries {
+                let _ = unsafe { free(entry.cast()) };
+            }
+            Err(err)
+        }
+    }
+}
+
+// List the entries of `dir` that pass `filter`, sorted with `compar`, into a heap array of
+// heap-allocated entries. The caller frees each entry and the array
+unsafe fn scan(
+    dir: &CStr,
+    filter: Filter,
+    compar: Comparator,
+) -> Result<(NonNull<*mut Dirent>, usize), Errno> {
+    let dir = dirent::opendir(dir)?;
+    let entries = unsafe { collect_entries(dir, filter) };
+    let _ = unsafe { dirent::closedir(dir) };
+    let entries = entries?;
+
+    let list = match malloc(mem::size_of::<*mut Dirent>() * entries.len().max(1)) {
+        Ok(list) => list.cast::<NonNull<Dirent>>(),
+        Err(err) => {
+            for entry in entries {
+                let _ = unsafe { free(entry.cast()) };
+            }
+            return Err(err);
+        }
+    };
+    unsafe {
+        ptr::copy_nonoverlapping(entries.as_ptr(), list.as_ptr(), entries.len());
+        if let Some(compar) = compar {
+            shellder::stdlib::qsort(
+                list.as_ptr().cast(),
+                entries.len(),
+                mem::size_of::<*mut Dirent>(),
+                |a, b| compar(a.cast(), b.cast()).cmp(&0),
+            );
+        }
+    }
+    Ok((list.cast(), entries.len()))
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn scandir(
+    dirp: *const c_char,
+    namelist: Option<NonNull<*mut *mut Dirent>>,
+    filter: Filter,
+    compar: Comparator,
+) -> c_int {
+    assert!(!dirp.is_null());
+    let mut namelist = namelist.expect("Unexpected null arg to `scandir()`");
+    unsafe { scan(CStr::from_ptr(dirp), filter, compar) }
+        .and_then(|(list, len)| {
+            unsafe { *namelist.as_mut() = list.as_ptr() };
+            Ok(c_int::try_from(len)?)
+        })
+        .or_errno(-1)
+}
+
+// Compare entries by name, for `scandir()`
+#[unsafe(no_mangle)]
+unsafe extern "C" fn alphasort(a: *const *const Dirent, b: *const *const Dirent) -> c_int {
+    assert!(!a.is_null() && !b.is_null());
+    let (a, b) = unsafe { (&**a, &**b) };
+    shellder::string::strcmp(a.name(), b.name()) as c_int
+}
@@ -1,31 +1,123 @@
 use alloc::vec::Vec;
-use core::{cell::RefCell, ffi::c_int, mem};
+use core::{
+    ffi::{c_int, c_void},
+    mem, ptr,
+};
 use spin::Mutex;
 
-static AT_EXIT_FNS: Mutex<RefCell<Vec<extern "C" fn()>>> = Mutex::new(RefCell::new(Vec::new()));
+enum Callback {
+    // Registered with `atexit()` or `at_quick_exit()`
+    Plain(extern "C" fn()),
+    // Registered with `__cxa_atexit()`, usually to run a C++ destructor
+    WithArg(extern "C" fn(*mut c_void), *mut c_void),
+}
+
+struct Handler {
+    callback: Callback,
+    // Shared object the handler belongs to, for `__cxa_finalize()`
+    dso: *mut c_void,
+}
 
+// Safety: the pointers are only handed back to the C code that registered them
+unsafe impl Send for Handler {}
+
+impl Handler {
+    fn call(self) {
+        match self.callback {
+            Callback::Plain(function) => function(),
+            Callback::WithArg(function, arg) => function(arg),
+        }
+    }
+}
+
+static AT_EXIT_FNS: Mutex<Vec<Handler>> = Mutex::new(Vec::new());
+static AT_QUICK_EXIT_FNS: Mutex<Vec<Handler>> = Mutex::new(Vec::new());
+
+/// Identifies the main executable to `__cxa_atexit()`. Shared objects get their own from crtbegin
+// Test binaries link crtbegin, which already defines it
+#[cfg(not(test))]
 #[unsafe(no_mangle)]
-extern "C" fn atexit(function: extern "C" fn()) -> c_int {
-    let vec = AT_EXIT_FNS.lock();
-    let mut vec = vec.borrow_mut();
-    vec.push(function);
+#[allow(non_upper_case_globals)]
+static mut __dso_handle: *mut c_void = ptr::null_mut();
+
+fn register(handlers: &Mutex<Vec<Handler>>, callback: Callback, dso: *mut c_void) -> c_int {
+    let mut handlers = handlers.lock();
+    if handlers.try_reserve(1).is_err() {
+        return -1;
+    }
+    handlers.push(Handler { callback, dso });
     0
 }
 
+// Run the handlers matching `filter` in reverse order of registration. The lock isn't held while a
+// handler runs, so handlers can register more handlers, which run next
+fn run_handlers(handlers: &Mutex<Vec<Handler>>, filter: impl Fn(&Handler) -> bool) {
+    loop {
+        let handler = {
+            let mut handlers = handlers.lock();
+            match handlers.iter().rposition(&filter) {
+                Some(index) => handlers.remove(index),
+                None => break,
+            }
+        };
+        handler.call();
+    }
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn atexit(function: extern "C" fn()) -> c_int {
+    register(&AT_EXIT_FNS, Callback::Plain(function), ptr::null_mut())
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn at_quick_exit(function: extern "C" fn()) -> c_int {
+    register(
+        &AT_QUICK_EXIT_FNS,
+        Callback::Plain(function),
+        ptr::null_mut(),
+    )
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn __cxa_atexit(
+    function: extern "C" fn(*mut c_void),
+    arg: *mut c_void,
+    dso: *mut c_void,
+) -> c_int {
+    register(&AT_EXIT_FNS, Callback::WithArg(function, arg), dso)
+}
+
+/// Run the exit handlers registered by the shared object `dso`, or all of them if `dso` is null
+#[unsafe(no_mangle)]
+extern "C" fn __cxa_finalize(dso: *mut c_void) {
+    run_handlers(&AT_EXIT_FNS, |handler| dso.is_null() || handler.dso == dso);
+}
+
 #[unsafe(no_mangle)]
 pub(crate) extern "C" fn exit(status: c_int) -> ! {
-    let vec = AT_EXIT_FNS.lock();
-    let mut vec = vec.borrow_mut();
-    let mut funcs = Vec::new();
-    mem::swap(&mut funcs, &mut *vec);
+    run_handlers(&AT_EXIT_FNS, |_| true);
+    let _ = unsafe { shellder::stdio::fflush(None) };
 
-    for func in funcs.into_iter() {
-        func();
-    }
+    // Release the handler lists, so they aren't reported as leaks
+    drop(mem::take(&mut *AT_EXIT_FNS.lock()));
+    drop(mem::take(&mut *AT_QUICK_EXIT_FNS.lock()));
+    drop(mem::take(&mut *super::process::AT_FORK_FNS.lock()));
+    drop(mem::take(&mut *super::shell::PIPES.lock()));
 
     shellder::stdlib::exit_without_cleanup(status);
 }
 
+#[unsafe(no_mangle)]
+extern "C" fn quick_exit(status: c_int) -> ! {
+    run_handlers(&AT_QUICK_EXIT_FNS, |_| true);
+    shellder::unistd::exit(status);
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn _Exit(status: c_int) -> ! {
+    shellder::unistd::exit(status);
+}
+
 /// Causes abnormal process termination
 #[unsafe(no_mangle)]
 extern "C" fn abort() -> ! {
@@ -0,0 +1,228 @@
+//! Checked variants of string and stdio functions, called by code built with `_FORTIFY_SOURCE`
+//!
+//! The compiler passes the size of the destination object when it can work it out, or
+//! `usize::MAX` when it can't. Each function aborts instead of writing past the object
+use super::c_result::CResult;
+use core::{
+    ffi::{CStr, VaListImpl, c_char, c_int, c_void},
+    fmt::Write,
+    ptr::NonNull,
+};
+use shellder::stdio::File;
+
+// Report an overflowing write and abort
+//
+// C signature:
+// `void __chk_fail(void);`
+#[unsafe(no_mangle)]
+extern "C" fn __chk_fail() -> ! {
+    let _ = writeln!(
+        shellder::stdio::Descriptor::stderr(),
+        "*** buffer overflow detected ***: terminated"
+    );
+    shellder::stdlib::abort();
+}
+
+// Abort if `len` bytes don't fit in an object of `objlen` bytes
+fn check(len: usize, objlen: usize) {
+    if len > objlen {
+        __chk_fail();
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __memcpy_chk(
+    dst: *mut u8,
+    src: *const u8,
+    n: usize,
+    dstlen: usize,
+) -> *mut u8 {
+    check(n, dstlen);
+    unsafe { shellder::string::memcpy(dst, src, n) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __memmove_chk(
+    dst: *mut u8,
+    src: *const u8,
+    n: usize,
+    dstlen: usize,
+) -> *mut u8 {
+    check(n, dstlen);
+    unsafe { shellder::string::memmove(dst, src, n) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __memset_chk(dst: *mut u8, c: c_int, n: usize, dstlen: usize) -> *mut u8 {
+    check(n, dstlen);
+    let dst = NonNull::new(dst).expect("Unexpected null arg to `memset()`");
+    unsafe { shellder::string::memset(dst, c, n).as_ptr() }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __strcpy_chk(
+    dst: *mut c_char,
+    src: *const c_char,
+    dstlen: usize,
+) -> *mut c_char {
+    assert!(!src.is_null());
+    let dst = NonNull::new(dst).expect("Unexpected NULL argument to `strcpy`");
+    let src = unsafe { CStr::from_ptr(src) };
+    check(src.count_bytes() + 1, dstlen);
+    unsafe { shellder::string::strcpy(dst, src).as_ptr() }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __stpcpy_chk(
+    dst: *mut c_char,
+    src: *const c_char,
+    dstlen: usize,
+) -> *mut c_char {
+    assert!(!src.is_null());
+    let dst = NonNull::new(dst).expect("Unexpected NULL argument to `stpcpy`");
+    let src = unsafe { CStr::from_ptr(src) };
+    check(src.count_bytes() + 1, dstlen);
+    unsafe { shellder::string::stpcpy(dst, src).as_ptr() }
+}
+
+// `strncpy()` always writes `n` bytes, padding with nulls
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __strncpy_chk(
+    dst: *mut c_char,
+    src: *const c_char,
+    n: usize,
+    dstlen: usize,
+) -> *mut c_char {
+    assert!(!src.is_null());
+    check(n, dstlen);
+    let dst = NonNull::new(dst).expect("Unexpected NULL argument to `strncpy`");
+    let src = unsafe { CStr::from_ptr(src) };
+    unsafe { shellder::string::strncpy(dst, src, n).as_ptr() }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __strcat_chk(
+    dst: *mut c_char,
+    src: *const c_char,
+    dstlen: usize,
+) -> *mut c_char {
+    assert!(!src.is_null());
+    let dst = NonNull::new(dst).expect("Unexpected NULL argument to `strcat`");
+    let src = unsafe { CStr::from_ptr(src) };
+    let existing = unsafe { CStr::from_ptr(dst.as_ptr()) };
+    check(existing.count_bytes() + src.count_bytes() + 1, dstlen);
+    unsafe { shellder::string::strcat(dst, src).as_ptr() }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __read_chk(fd: c_int, buf: *mut c_void, count: usize, buflen: usize) -> c_int {
+    check(count, buflen);
+    assert!(!buf.is_null());
+    unsafe { shellder::unistd::read(fd, buf, count) }.or_errno(-1)
+}
+
+// `flag` asks for extra format string checks, such as rejecting `%n` in writable memory. There's
+// no `%n` support to protect, so it's ignored
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __printf_chk(flag: c_int, fmt: *const c_char, args: ...) -> c_int {
+    unsafe { __vprintf_chk(flag, fmt, args) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __vprintf_chk(_flag: c_int, fmt: *const c_char, args: VaListImpl) -> c_int {
+    assert!(!fmt.is_null());
+    unsafe {
+        let fmt = CStr::from_ptr(fmt);
+        shellder::stdio::printf(fmt, args)
+    }
+    .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __fprintf_chk(
+    stream: *mut File,
+    flag: c_int,
+    fmt: *const c_char,
+    args: ...
+) -> c_int {
+    unsafe { __vfprintf_chk(stream, flag, fmt, args) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __vfprintf_chk(
+    stream: *mut File,
+    _flag: c_int,
+    fmt: *const c_char,
+    args: VaListImpl,
+) -> c_int {
+    assert!(!fmt.is_null());
+    let stream = NonNull::new(stream).expect("Unexpected null arg to `fprintf()`");
+    unsafe {
+        let fmt = CStr::from_ptr(fmt);
+        shellder::stdio::fprintf(stream, fmt, args)
+    }
+    .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __sprintf_chk(
+    s: Option<NonNull<c_char>>,
+    flag: c_int,
+    slen: usize,
+    fmt: *const c_char,
+    args: ...
+) -> c_int {
+    unsafe { __vsprintf_chk(s, flag, slen, fmt, args) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __vsprintf_chk(
+    s: Option<NonNull<c_char>>,
+    _flag: c_int,
+    slen: usize,
+    fmt: *const c_char,
+    args: VaListImpl,
+) -> c_int {
+    assert!(!fmt.is_null());
+    let s = s.expect("Unexpected null arg to `sprintf()`");
+    let written = unsafe {
+        let fmt = CStr::from_ptr(fmt);
+        shellder::stdio::snprintf(s, slen, fmt, args)
+    };
+    // The output was truncated to fit, but `sprintf()` would have overflowed
+    if let Ok(written) = written {
+        check(written as usize + 1, slen);
+    }
+    written.or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __snprintf_chk(
+    s: Option<NonNull<c_char>>,
+    maxlen: usize,
+    flag: c_int,
+    slen: usize,
+    fmt: *const c_char,
+    args: ...
+) -> c_int {
+    unsafe { __vsnprintf_chk(s, maxlen, flag, slen, fmt, args) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __vsnprintf_chk(
+    s: Option<NonNull<c_char>>,
+    maxlen: usize,
+    _flag: c_int,
+    slen: usize,
+    fmt: *const c_char,
+    args: VaListImpl,
+) -> c_int {
+    assert!(!fmt.is_null());
+    check(maxlen, slen);
+    let s = s.expect("Unexpected null arg to `snprintf()`");
+    unsafe {
+        let fmt = CStr::from_ptr(fmt);
+        shellder::stdio::snprintf(s, maxlen, fmt, args)
+    }
+    .or_errno(-1)
+}
@@ -0,0 +1,237 @@
+//! File tree walks, with `ftw()` and `nftw()`
+use crate::errno::set_errno;
+use alloc::vec::Vec;
+use core::ffi::{CStr, c_char, c_int};
+use shellder::{Errno, dirent, types::*, unistd};
+
+// Why a walk ended early
+enum Stop {
+    // The callback returned nonzero
+    Callback(c_int),
+    Error(Errno),
+}
+
+impl From<Errno> for Stop {
+    fn from(err: Errno) -> Self {
+        Self::Error(err)
+    }
+}
+
+struct Walk<F> {
+    flags: FtwFlags,
+    // Device the walk started on, for `FTW_MOUNT`
+    dev: u64,
+    // Device and inode of each directory visited, sorted, when following links. A link back to
+    // one of them would otherwise make the walk go round forever
+    visited: Vec<(u64, u64)>,
+    // Path of the current file, null-terminated
+    path: Vec<u8>,
+    callback: F,
+}
+
+// Read the names of the entries of directory `path`, other than "." and "..". Reading them all
+// up front means only one directory is open at a time, however deep the walk goes
+fn read_names(path: &CStr) -> Result<Vec<Vec<u8>>, Errno> {
+    let dir = dirent::opendir(path)?;
+    let mut names = Vec::new();
+    let result = (|| {
+        while let Some(entry) = unsafe { dirent::readdir(dir)? } {
+            let name = unsafe { entry.as_ref() }.name().to_bytes();
+            if name == b"." || name == b".." {
+                continue;
+            }
+            let mut owned = Vec::new();
+            owned.try_reserve(name.len()).map_err(|_| Errno::ENOMEM)?;
+            owned.extend_from_slice(name);
+            names.try_reserve(1).map_err(|_| Errno::ENOMEM)?;
+            names.push(owned);
+        }
+        Ok(())
+    })();
+    let _ = unsafe { dirent::closedir(dir) };
+    result.map(|()| names)
+}
+
+impl<F: FnMut(&CStr, &Stat, c_int, Ftw) -> c_int> Walk<F> {
+    fn path(&self) -> &CStr {
+        CStr::from_bytes_with_nul(&self.path).expect("Walk path is null-terminated")
+    }
+
+    fn report(&mut self, stat: &Stat, typeflag: c_int, ftw: Ftw) -> Result<(), Stop> {
+        let path = CStr::from_bytes_with_nul(&self.path).expect("Walk path is null-terminated");
+        match (self.callback)(path, stat, typeflag, ftw) {
+            0 => Ok(()),
+            value => Err(Stop::Callback(value)),
+        }
+    }
+
+    fn visit(&mut self, ftw: Ftw) -> Result<(), Stop> {
+        let follow = !self.flags.contains(FtwFlags::FTW_PHYS);
+        let at_flags = if follow {
+            AtFlags::empty()
+        } else {
+            AtFlags::AT_SYMLINK_NOFOLLOW
+        };
+        let stat = match unistd::fstatat(AT_FDCWD, self.path(), at_flags) {
+            Ok(stat) => stat,
+            Err(err) => {
+                // A link to nothing is reported as the link itself
+                let link = unistd::fstatat(AT_FDCWD, self.path(), AtFlags::AT_SYMLINK_NOFOLLOW)
+                    .ok()
+                    .filter(|stat| follow && err == Errno::ENOENT && stat.mode & S_IFMT == S_IFLNK);
+                return match link {
+                    Some(stat) => self.report(&stat, FTW_SLN, ftw),
+                    // Where the walk starts has to exist
+                    None if ftw.level == 0 => Err(Stop::Error(err)),
+                    None => self.report(&Stat::default(), FTW_NS, ftw),
+                };
+            }
+        };
+        if ftw.level == 0 {
+            self.dev = stat.dev;
+        } else if self.flags.contains(FtwFlags::FTW_MOUNT) && stat.dev != self.dev {
+            return Ok(());
+        }
+
+        match stat.mode & S_IFMT {
+            // Like glibc, each directory is only reported once
+            S_IFDIR if follow => {
+                let id = (stat.dev, stat.ino);
+                let Err(index) = self.visited.binary_search(&id) else {
+                    return Ok(());
+                };
+                self.visited.try_reserve(1).map_err(|_| Errno::ENOMEM)?;
+                self.visited.insert(index, id);
+                self.visit_dir(&stat, ftw)
+            }
+            S_IFDIR => self.visit_dir(&stat, ftw),
+            S_IFLNK => self.report(&stat, FTW_SL, ftw),
+            _ => self.report(&stat, FTW_F, ftw),
+        }
+    }
+
+    fn visit_dir(&mut self, stat: &Stat, ftw: Ftw) -> Result<(), Stop> {
+        let names = match read_names(self.path()) {
+            Ok(names) => names,
+            Err(Errno::EACCES) => return self.report(stat, FTW_DNR, ftw),
+            Err(err) => return Err(Stop::Error(err)),
+        };
+        let depth_first = self.flags.contains(FtwFlags::FTW_DEPTH);
+        if !depth_first {
+            self.report(stat, FTW_D, ftw)?;
+        }
+
+        // Drop the null terminator, and add a separator unless there is one
+        self.path.pop();
+        let dir_len = self.path.len();
+        if self.path.last() != Some(&b'/') {
+            self.path.try_reserve(1).map_err(|_| Errno::ENOMEM)?;
+            self.path.push(b'/');
+        }
+        let base = self.path.len();
+        for name in names {
+            self.path.truncate(base);
+            self.path
+                .try_reserve(name.len() + 1)
+                .map_err(|_| Errno::ENOMEM)?;
+            self.path.extend_from_slice(&name);
+            self.path.push(0);
+            let child = Ftw {
+                base: base as c_int,
+                level: ftw.level + 1,
+            };
+            self.visit(child)?;
+        }
+        self.path.truncate(dir_len);
+        self.path.push(0);
+
+        if depth_first {
+            self.report(stat, FTW_DP, ftw)?;
+        }
+        Ok(())
+    }
+}
+
+// Walk the tree under `path`, calling `callback` on each file
+fn walk(
+    path: &CStr,
+    flags: FtwFlags,
+    callback: impl FnMut(&CStr, &Stat, c_int, Ftw) -> c_int,
+) -> c_int {
+    // Trailing slashes are dropped, unless the path is all slashes
+    let name = path.to_bytes();
+    let trailing = name.iter().rev().take_while(|c| **c == b'/').count();
+    let name = &name[..(name.len() - trailing).max(name.len().min(1))];
+    let mut owned = Vec::new();
+    if owned.try_reserve(name.len() + 1).is_err() {
+        set_errno(Errno::ENOMEM);
+        return -1;
+    }
+    owned.extend_from_slice(name);
+    owned.push(0);
+
+    // The name starts after the last slash
+    let base = name
+        .iter()
+        .rposition(|c| *c == b'/')
+        .filter(|slash| slash + 1 < name.len())
+        .map_or(0, |slash| slash + 1);
+
+    let mut walk = Walk {
+        flags,
+        dev: 0,
+        visited: Vec::new(),
+        path: owned,
+        callback,
+    };
+    let root = Ftw {
+        base: base as c_int,
+        level: 0,
+    };
+    match walk.visit(root) {
+        Ok(()) => 0,
+        Err(Stop::Callback(value)) => value,
+        Err(Stop::Error(err)) => {
+            set_errno(err);
+            -1
+        }
+    }
+}
+
+type NftwFn = unsafe extern "C" fn(*const c_char, *const Stat, c_int, *mut Ftw) -> c_int;
+type FtwFn = unsafe extern "C" fn(*const c_char, *const Stat, c_int) -> c_int;
+
+// `nopenfd` is the most directories to hold open at once, but only one ever is
+#[unsafe(no_mangle)]
+unsafe extern "C" fn nftw(
+    dirpath: *const c_char,
+    callback: Option<NftwFn>,
+    _nopenfd: c_int,
+    flags: FtwFlags,
+) -> c_int {
+    assert!(!dirpath.is_null());
+    let callback = callback.expect("Unexpected null callback to `nftw()`");
+    // Not supported
+    if flags.intersects(FtwFlags::FTW_CHDIR | FtwFlags::FTW_ACTIONRETVAL) {
+        set_errno(Errno::EINVAL);
+        return -1;
+    }
+    let path = unsafe { CStr::from_ptr(dirpath) };
+    walk(path, flags, |path, stat, typeflag, mut ftw| unsafe {
+        callback(path.as_ptr(), stat, typeflag, &raw mut ftw)
+    })
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn ftw(
+    dirpath: *const c_char,
+    callback: Option<FtwFn>,
+    _nopenfd: c_int,
+) -> c_int {
+    assert!(!dirpath.is_null());
+    let callback = callback.expect("Unexpected null callback to `ftw()`");
+    let path = unsafe { CStr::from_ptr(dirpath) };
+    walk(path, FtwFlags::empty(), |path, stat, typeflag, _| unsafe {
+        callback(path.as_ptr(), stat, typeflag)
+    })
+}
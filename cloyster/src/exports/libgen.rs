@@ -0,0 +1,38 @@
+use core::ffi::{CStr, c_char};
+
+// Null-terminated bytes of the string at `path`
+unsafe fn bytes<'a>(path: *mut c_char) -> &'a mut [u8] {
+    let len = unsafe { CStr::from_ptr(path) }.to_bytes_with_nul().len();
+    unsafe { core::slice::from_raw_parts_mut(path.cast::<u8>(), len) }
+}
+
+// Modifies `path`
+#[unsafe(no_mangle)]
+unsafe extern "C" fn dirname(path: *mut c_char) -> *mut c_char {
+    if path.is_null() {
+        return c".".as_ptr().cast_mut();
+    }
+    shellder::libgen::dirname(unsafe { bytes(path) })
+        .as_ptr()
+        .cast_mut()
+}
+
+// The POSIX `basename()`, which `<libgen.h>` renames to this. Modifies `path`
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __xpg_basename(path: *mut c_char) -> *mut c_char {
+    if path.is_null() {
+        return c".".as_ptr().cast_mut();
+    }
+    shellder::libgen::basename(unsafe { bytes(path) })
+        .as_ptr()
+        .cast_mut()
+}
+
+// The GNU `basename()`, which doesn't modify `path`
+#[unsafe(no_mangle)]
+unsafe extern "C" fn basename(path: *const c_char) -> *mut c_char {
+    assert!(!path.is_null());
+    shellder::libgen::basename_gnu(unsafe { CStr::from_ptr(path) })
+        .as_ptr()
+        .cast_mut()
+}
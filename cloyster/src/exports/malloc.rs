@@ -1,48 +1,50 @@
+// With `malloc_trace`, these are exported through the shims in `malloc_trace`
+use super::c_result::CResult;
 use core::{
     alloc::Layout,
     ffi::c_void,
     ptr::{self, NonNull},
 };
+use shellder::Errno;
 
-#[unsafe(no_mangle)]
-extern "C" fn malloc(size: usize) -> *mut c_void {
-    shellder::malloc::malloc(size)
-        .map(|v| v.cast().as_ptr())
-        .unwrap_or(ptr::null_mut())
+// POSIX requires allocation failures to set ENOMEM, whatever went wrong internally
+fn out_of_memory<T>(res: Result<NonNull<T>, Errno>) -> *mut c_void {
+    res.map(|v| v.cast().as_ptr())
+        .map_err(|_| Errno::ENOMEM)
+        .or_errno(ptr::null_mut())
 }
 
-#[unsafe(no_mangle)]
-extern "C" fn aligned_alloc(alignment: usize, size: usize) -> *mut c_void {
+#[cfg_attr(not(feature = "malloc_trace"), unsafe(no_mangle))]
+pub(super) extern "C" fn malloc(size: usize) -> *mut c_void {
+    out_of_memory(shellder::malloc::malloc(size))
+}
+
+#[cfg_attr(not(feature = "malloc_trace"), unsafe(no_mangle))]
+pub(super) extern "C" fn aligned_alloc(alignment: usize, size: usize) -> *mut c_void {
     let Ok(layout) = Layout::from_size_align(size, alignment) else {
-        return ptr::null_mut();
+        return Err(Errno::EINVAL).or_errno(ptr::null_mut());
     };
-    shellder::malloc::aligned_alloc(layout)
-        .map(|v| v.cast().as_ptr())
-        .unwrap_or(ptr::null_mut())
+    out_of_memory(shellder::malloc::aligned_alloc(layout))
 }
 
-#[unsafe(no_mangle)]
-extern "C" fn calloc(nmemb: usize, size: usize) -> *mut c_void {
-    shellder::malloc::calloc(nmemb, size)
-        .map(|v| v.cast().as_ptr())
-        .unwrap_or(ptr::null_mut())
+#[cfg_attr(not(feature = "malloc_trace"), unsafe(no_mangle))]
+pub(super) extern "C" fn calloc(nmemb: usize, size: usize) -> *mut c_void {
+    out_of_memory(shellder::malloc::calloc(nmemb, size))
 }
 
-#[unsafe(no_mangle)]
-unsafe extern "C" fn realloc(ptr: Option<NonNull<c_void>>, size: usize) -> *mut c_void {
+#[cfg_attr(not(feature = "malloc_trace"), unsafe(no_mangle))]
+pub(super) unsafe extern "C" fn realloc(ptr: Option<NonNull<c_void>>, size: usize) -> *mut c_void {
     unsafe {
         if let Some(ptr) = ptr {
-            shellder::malloc::realloc(ptr.cast(), size)
-                .map(|v| v.cast().as_ptr())
-                .unwrap_or(ptr::null_mut())
+            out_of_memory(shellder::malloc::realloc(ptr.cast(), size))
         } else {
             malloc(size)
         }
     }
 }
 
-#[unsafe(no_mangle)]
-extern "C" fn free(ptr: Option<NonNull<c_void>>) {
+#[cfg_attr(not(feature = "malloc_trace"), unsafe(no_mangle))]
+pub(super) extern "C" fn free(ptr: Option<NonNull<c_void>>) {
     if let Some(ptr) = ptr {
         unsafe { shellder::malloc::free(ptr.cast()).expect("Failed to free") }
     }
@@ -0,0 +1,67 @@
+//! Allocation tracing
+//!
+//! The allocation functions are exported by assembly shims that append the caller's return
+//! address to the arguments, then call the traced implementations below
+use super::malloc;
+use core::{arch::global_asm, ffi::c_void, ptr::NonNull};
+use shellder::malloc::trace::{self, Event, Op};
+
+#[cfg(target_arch = "x86_64")]
+global_asm!(include_str!("x86_64.S"));
+#[cfg(target_arch = "riscv64")]
+global_asm!(include_str!("riscv64.S"));
+
+fn record(op: Op, ptr: *mut c_void, size: usize, old_ptr: Option<NonNull<c_void>>, caller: usize) {
+    trace::record(Event {
+        op,
+        ptr: NonNull::new(ptr.cast()),
+        size,
+        old_ptr: old_ptr.map(NonNull::cast),
+        caller,
+    });
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn __cloyster_traced_malloc(size: usize, caller: usize) -> *mut c_void {
+    let ptr = malloc::malloc(size);
+    record(Op::Malloc, ptr, size, None, caller);
+    ptr
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn __cloyster_traced_calloc(nmemb: usize, size: usize, caller: usize) -> *mut c_void {
+    let ptr = malloc::calloc(nmemb, size);
+    record(Op::Calloc, ptr, nmemb.saturating_mul(size), None, caller);
+    ptr
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __cloyster_traced_realloc(
+    old_ptr: Option<NonNull<c_void>>,
+    size: usize,
+    caller: usize,
+) -> *mut c_void {
+    let ptr = unsafe { malloc::realloc(old_ptr, size) };
+    record(Op::Realloc, ptr, size, old_ptr, caller);
+    ptr
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn __cloyster_traced_aligned_alloc(
+    alignment: usize,
+    size: usize,
+    caller: usize,
+) -> *mut c_void {
+    let ptr = malloc::aligned_alloc(alignment, size);
+    record(Op::AlignedAlloc, ptr, size, None, caller);
+    ptr
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn __cloyster_traced_free(ptr: Option<NonNull<c_void>>, caller: usize) {
+    // Record before freeing, so the event can't be ordered after the region is reused
+    if let Some(ptr) = ptr {
+        record(Op::Free, ptr.as_ptr(), 0, None, caller);
+    }
+    malloc::free(ptr);
+}
@@ -1,8 +1,29 @@
+mod c_result;
+mod dirent;
 pub(crate) mod exit;
+mod fortify;
+mod ftw;
+mod libgen;
 #[cfg(not(test))]
 mod malloc;
+#[cfg(all(not(test), feature = "malloc_trace"))]
+mod malloc_trace;
 mod math;
+mod process;
+// Needs the register saving assembly
+#[cfg(not(test))]
+mod setjmp;
+mod shell;
+// Needs the signal restorer from crt0
+#[cfg(not(test))]
+mod signal;
+mod spawn;
+mod stat;
+mod stdbit;
 mod stdio;
 mod stdlib;
 mod string;
 mod unistd;
+// Test binaries have no allocator registered for `user_alloc`
+#[cfg(all(test, feature = "user_alloc"))]
+mod user_alloc;
@@ -0,0 +1,182 @@
+use super::c_result::CResult;
+use crate::errno::set_errno;
+use alloc::vec::Vec;
+use core::{
+    ffi::{CStr, VaListImpl, c_char, c_int},
+    ptr::NonNull,
+};
+use shellder::{Errno, types::*};
+use spin::Mutex;
+
+// Handlers registered with `pthread_atfork()`
+#[derive(Copy, Clone)]
+pub(super) struct AtFork {
+    prepare: Option<extern "C" fn()>,
+    parent: Option<extern "C" fn()>,
+    child: Option<extern "C" fn()>,
+}
+
+pub(super) static AT_FORK_FNS: Mutex<Vec<AtFork>> = Mutex::new(Vec::new());
+
+#[unsafe(no_mangle)]
+extern "C" fn pthread_atfork(
+    prepare: Option<extern "C" fn()>,
+    parent: Option<extern "C" fn()>,
+    child: Option<extern "C" fn()>,
+) -> c_int {
+    let mut handlers = AT_FORK_FNS.lock();
+    if handlers.try_reserve(1).is_err() {
+        return Errno::ENOMEM.as_positive();
+    }
+    handlers.push(AtFork {
+        prepare,
+        parent,
+        child,
+    });
+    0
+}
+
+// Call the handlers picked by `select`, in reverse order of registration if `reverse` is set. The
+// lock isn't held while a handler runs, so handlers can register more handlers
+fn run_atfork_handlers(select: fn(&AtFork) -> Option<extern "C" fn()>, reverse: bool) {
+    let count = AT_FORK_FNS.lock().len();
+    for i in 0..count {
+        let index = if reverse { count - 1 - i } else { i };
+        let handler = AT_FORK_FNS.lock().get(index).and_then(select);
+        if let Some(handler) = handler {
+            handler();
+        }
+    }
+}
+
+// `prepare` handlers run in reverse order of registration, and the others in order
+#[unsafe(no_mangle)]
+extern "C" fn fork() -> pid_t {
+    run_atfork_handlers(|handlers| handlers.prepare, true);
+    let pid = shellder::malloc::while_locked(shellder::unistd::fork);
+    match pid {
+        Ok(0) => run_atfork_handlers(|handlers| handlers.child, false),
+        _ => run_atfork_handlers(|handlers| handlers.parent, false),
+    }
+    pid.or_errno(-1)
+}
+
+// POSIX allows `vfork()` to be `fork()`. Like glibc's, it doesn't run the atfork handlers
+#[unsafe(no_mangle)]
+extern "C" fn vfork() -> pid_t {
+    shellder::malloc::while_locked(shellder::unistd::fork).or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn execve(
+    pathname: *const c_char,
+    argv: *const *const c_char,
+    envp: *const *const c_char,
+) -> c_int {
+    assert!(!pathname.is_null());
+    let pathname = unsafe { CStr::from_ptr(pathname) };
+    set_errno(unsafe { shellder::unistd::execve(pathname, argv, envp) });
+    -1
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn execv(pathname: *const c_char, argv: *const *const c_char) -> c_int {
+    unsafe { execve(pathname, argv, shellder::stdlib::environ()) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn execvpe(
+    file: *const c_char,
+    argv: *const *const c_char,
+    envp: *const *const c_char,
+) -> c_int {
+    assert!(!file.is_null());
+    let file = unsafe { CStr::from_ptr(file) };
+    set_errno(unsafe { shellder::unistd::execvpe(file, argv, envp) });
+    -1
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn execvp(file: *const c_char, argv: *const *const c_char) -> c_int {
+    unsafe { execvpe(file, argv, shellder::stdlib::environ()) }
+}
+
+// Collect `arg0` and the rest of the NULL-terminated variadic arguments into an argv array
+unsafe fn collect_args(
+    arg0: *const c_char,
+    args: &mut VaListImpl,
+) -> Result<Vec<*const c_char>, Errno> {
+    let mut argv = Vec::new();
+    let mut arg = arg0;
+    loop {
+        argv.try_reserve(1).map_err(|_| Errno::ENOMEM)?;
+        argv.push(arg);
+        if arg.is_null() {
+            return Ok(argv);
+        }
+        arg = unsafe { args.arg::<*const c_char>() };
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn execl(pathname: *const c_char, arg0: *const c_char, mut args: ...) -> c_int {
+    match unsafe { collect_args(arg0, &mut args) } {
+        Ok(argv) => unsafe { execv(pathname, argv.as_ptr()) },
+        Err(err) => {
+            set_errno(err);
+            -1
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn execlp(file: *const c_char, arg0: *const c_char, mut args: ...) -> c_int {
+    match unsafe { collect_args(arg0, &mut args) } {
+        Ok(argv) => unsafe { execvp(file, argv.as_ptr()) },
+        Err(err) => {
+            set_errno(err);
+            -1
+        }
+    }
+}
+
+// The environment follows the argument list's terminating NULL
+#[unsafe(no_mangle)]
+unsafe extern "C" fn execle(pathname: *const c_char, arg0: *const c_char, mut args: ...) -> c_int {
+    match unsafe { collect_args(arg0, &mut args) } {
+        Ok(argv) => unsafe {
+            let envp = args.arg::<*const *const c_char>();
+            execve(pathname, argv.as_ptr(), envp)
+        },
+        Err(err) => {
+            set_errno(err);
+            -1
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn wait4(
+    pid: pid_t,
+    status: Option<NonNull<c_int>>,
+    options: WaitFlags,
+    rusage: Option<NonNull<RUsage>>,
+) -> pid_t {
+    let status = status.map(|mut status| unsafe { status.as_mut() });
+    let rusage = rusage.map(|mut rusage| unsafe { rusage.as_mut() });
+    shellder::unistd::wait4(pid, status, options, rusage).or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn waitpid(
+    pid: pid_t,
+    status: Option<NonNull<c_int>>,
+    options: WaitFlags,
+) -> pid_t {
+    unsafe { wait4(pid, status, options, None) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn wait(status: Option<NonNull<c_int>>) -> pid_t {
+    unsafe { wait4(-1, status, WaitFlags::empty(), None) }
+}
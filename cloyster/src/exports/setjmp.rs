@@ -0,0 +1,58 @@
+use core::ffi::c_int;
+use shellder::types::*;
+
+// Saved registers, written and read by the assembly in `crate::setjmp`
+#[cfg(target_arch = "x86_64")]
+const REGISTERS: usize = 8;
+#[cfg(target_arch = "riscv64")]
+const REGISTERS: usize = 26;
+
+// Laid out like glibc's `struct __jmp_buf_tag`
+#[repr(C)]
+struct JmpBuf {
+    registers: [usize; REGISTERS],
+    mask_was_saved: c_int,
+    saved_mask: SigSet,
+}
+
+unsafe extern "C" {
+    fn __cloyster_longjmp(env: *mut JmpBuf, val: c_int) -> !;
+}
+
+// Finishes `sigsetjmp()` once the registers have been saved
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __cloyster_sigjmp_save(env: *mut JmpBuf, savemask: c_int) -> c_int {
+    let env = unsafe { &mut *env };
+    env.mask_was_saved = c_int::from(
+        savemask != 0
+            && shellder::unistd::rt_sigprocmask(SIG_BLOCK, None, Some(&mut env.saved_mask)).is_ok(),
+    );
+    0
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn siglongjmp(env: *mut JmpBuf, val: c_int) -> ! {
+    assert!(!env.is_null());
+    unsafe {
+        if (*env).mask_was_saved != 0 {
+            let _ = shellder::unistd::rt_sigprocmask(SIG_SETMASK, Some(&(*env).saved_mask), None);
+        }
+        __cloyster_longjmp(env, val)
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn longjmp(env: *mut JmpBuf, val: c_int) -> ! {
+    unsafe { siglongjmp(env, val) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn _longjmp(env: *mut JmpBuf, val: c_int) -> ! {
+    unsafe { siglongjmp(env, val) }
+}
+
+// Used in place of `longjmp()` by code built with `_FORTIFY_SOURCE`
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __longjmp_chk(env: *mut JmpBuf, val: c_int) -> ! {
+    unsafe { siglongjmp(env, val) }
+}
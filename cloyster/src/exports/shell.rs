@@ -0,0 +1,197 @@
+//! Running shell commands, with `system()` and `popen()`
+use super::{
+    c_result::CResult,
+    spawn::{FileAction, spawn_process},
+};
+use alloc::vec::Vec;
+use core::{
+    ffi::{CStr, c_char, c_int},
+    ptr::{self, NonNull},
+};
+use shellder::{Errno, stdio::File, types::*, unistd};
+use spin::Mutex;
+
+const SHELL: &CStr = c"/bin/sh";
+
+// Status reported when the shell couldn't be run, as if it had exited with 127
+const NO_SHELL_STATUS: c_int = 127 << 8;
+
+// Run `sh -c command` in a new process, returning its PID
+unsafe fn spawn_shell(
+    command: &CStr,
+    actions: &[FileAction],
+    attr: SpawnAttr,
+) -> Result<pid_t, Errno> {
+    let argv = [
+        c"sh".as_ptr(),
+        c"-c".as_ptr(),
+        command.as_ptr(),
+        ptr::null(),
+    ];
+    unsafe {
+        spawn_process(
+            SHELL,
+            false,
+            actions,
+            attr,
+            argv.as_ptr(),
+            shellder::stdlib::environ(),
+        )
+    }
+}
+
+// Wait for child `pid` to exit, returning its status
+fn wait_for(pid: pid_t) -> Result<c_int, Errno> {
+    loop {
+        let mut status = 0;
+        match unistd::wait4(pid, Some(&mut status), WaitFlags::empty(), None) {
+            Ok(_) => return Ok(status),
+            Err(Errno::EINTR) => continue,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+// POSIX asks for `SIGINT` and `SIGQUIT` to be ignored and `SIGCHLD` blocked while the command
+// runs. The child gets the caller's original dispositions and mask back
+fn run_system(command: &CStr) -> Result<c_int, Errno> {
+    let ignore = KernelSigAction {
+        handler: SIG_IGN,
+        ..Default::default()
+    };
+    let mut old_int = KernelSigAction::default();
+    let mut old_quit = KernelSigAction::default();
+    unsafe {
+        unistd::rt_sigaction(SIGINT, Some(&ignore), Some(&mut old_int))?;
+        unistd::rt_sigaction(SIGQUIT, Some(&ignore), Some(&mut old_quit))?;
+    }
+    let mut chld = SigSet::empty();
+    chld.add(SIGCHLD)?;
+    let mut old_mask = SigSet::empty();
+    let blocked = unistd::rt_sigprocmask(SIG_BLOCK, Some(&chld), Some(&mut old_mask));
+
+    let status = blocked.and_then(|()| {
+        let mut attr = SpawnAttr::default();
+        attr.flags = SpawnFlags::POSIX_SPAWN_SETSIGDEF | SpawnFlags::POSIX_SPAWN_SETSIGMASK;
+        attr.sigmask = old_mask;
+        for (sig, old) in [(SIGINT, &old_int), (SIGQUIT, &old_quit)] {
+            if old.handler != SIG_IGN {
+                attr.sigdefault.add(sig)?;
+            }
+        }
+        match unsafe { spawn_shell(command, &[], attr) } {
+            Ok(pid) => wait_for(pid),
+            // Like glibc, whatever stopped the shell from running
+            Err(_) => Ok(NO_SHELL_STATUS),
+        }
+    });
+
+    if blocked.is_ok() {
+        let _ = unistd::rt_sigprocmask(SIG_SETMASK, Some(&old_mask), None);
+    }
+    unsafe {
+        let _ = unistd::rt_sigaction(SIGINT, Some(&old_int), None);
+        let _ = unistd::rt_sigaction(SIGQUIT, Some(&old_quit), None);
+    }
+    status
+}
+
+// Run `command` with the shell, and wait for it
+//
+// If `command` is NULL, check whether a shell is available
+#[unsafe(no_mangle)]
+unsafe extern "C" fn system(command: *const c_char) -> c_int {
+    if command.is_null() {
+        return c_int::from(run_system(c"exit 0") == Ok(0));
+    }
+    run_system(unsafe { CStr::from_ptr(command) }).or_errno(-1)
+}
+
+// A stream opened by `popen()`, and the shell on the other end of it
+pub(super) struct Pipe {
+    fd: c_int,
+    pid: pid_t,
+}
+
+pub(super) static PIPES: Mutex<Vec<Pipe>> = Mutex::new(Vec::new());
+
+// `mode` is "r" to read the command's output or "w" to write its input, optionally followed by
+// 'e' to close the stream on exec
+fn open_pipe(command: &CStr, mode: &CStr) -> Result<NonNull<File>, Errno> {
+    let (reading, cloexec) = match mode.to_bytes() {
+        [b'r', rest @ ..] if rest.iter().all(|c| *c == b'e') => (true, !rest.is_empty()),
+        [b'w', rest @ ..] if rest.iter().all(|c| *c == b'e') => (false, !rest.is_empty()),
+        _ => return Err(Errno::EINVAL),
+    };
+
+    let mut pipes = PIPES.lock();
+    pipes.try_reserve(1).map_err(|_| Errno::ENOMEM)?;
+    // The command mustn't inherit the streams of earlier commands, or they'd never see EOF
+    let mut actions = Vec::new();
+    actions
+        .try_reserve(pipes.len() + 1)
+        .map_err(|_| Errno::ENOMEM)?;
+
+    let [read_end, write_end] = unistd::pipe2(OpenFlags::O_CLOEXEC)?;
+    let (ours, theirs, target) = if reading {
+        (read_end, write_end, 1)
+    } else {
+        (write_end, read_end, 0)
+    };
+    actions.push(FileAction::Dup2(theirs, target));
+    actions.extend(pipes.iter().map(|pipe| FileAction::Close(pipe.fd)));
+
+    let spawned = unsafe { spawn_shell(command, &actions, SpawnAttr::default()) };
+    let _ = unistd::close(theirs);
+    let pid = spawned.inspect_err(|_| {
+        let _ = unistd::close(ours);
+    })?;
+
+    let file = (|| {
+        if !cloexec {
+            unistd::fcntl(ours, F_SETFD, 0)?;
+        }
+        shellder::stdio::fdopen(ours, mode)
+    })();
+    match file {
+        Ok(file) => {
+            pipes.push(Pipe { fd: ours, pid });
+            Ok(file)
+        }
+        Err(err) => {
+            let _ = unistd::close(ours);
+            let _ = wait_for(pid);
+            Err(err)
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn popen(command: *const c_char, mode: *const c_char) -> *mut File {
+    assert!(!command.is_null());
+    assert!(!mode.is_null());
+    let (command, mode) = unsafe { (CStr::from_ptr(command), CStr::from_ptr(mode)) };
+    open_pipe(command, mode)
+        .map(NonNull::as_ptr)
+        .or_errno(ptr::null_mut())
+}
+
+// Close a stream opened by `popen()`, and wait for its command to exit
+//
+// Returns the command's status
+#[unsafe(no_mangle)]
+unsafe extern "C" fn pclose(stream: Option<NonNull<File>>) -> c_int {
+    let stream = stream.expect("Unexpected null arg to `pclose()`");
+    let fd = unsafe { stream.as_ref() }.fd.0;
+    let pid = {
+        let mut pipes = PIPES.lock();
+        let Some(index) = pipes.iter().position(|pipe| pipe.fd == fd) else {
+            crate::errno::set_errno(Errno::ECHILD);
+            return -1;
+        };
+        pipes.remove(index).pid
+    };
+    unsafe { shellder::stdio::fclose(stream) }
+        .and_then(|()| wait_for(pid))
+        .or_errno(-1)
+}
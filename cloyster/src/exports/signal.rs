@@ -0,0 +1,211 @@
+use super::c_result::CResult;
+use crate::errno::set_errno;
+use core::{ffi::c_int, ptr::NonNull};
+use shellder::{Errno, types::*};
+
+unsafe extern "C" {
+    // Calls `rt_sigreturn` when a handler returns. Defined in crt0
+    fn __cloyster_restore_rt();
+}
+
+// Install `act`, returning through our restorer when a handler is done
+unsafe fn set_action(
+    sig: c_int,
+    act: Option<&SigAction>,
+    oldact: Option<&mut SigAction>,
+) -> Result<(), Errno> {
+    let act = act.map(|act| SigAction {
+        flags: act.flags | SigActionFlags::SA_RESTORER,
+        restorer: __cloyster_restore_rt as *const () as usize,
+        ..*act
+    });
+    unsafe { shellder::unistd::sigaction(sig, act.as_ref(), oldact) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn sigaction(
+    sig: c_int,
+    act: Option<NonNull<SigAction>>,
+    oldact: Option<NonNull<SigAction>>,
+) -> c_int {
+    let act = act.map(|act| unsafe { act.as_ref() });
+    let oldact = oldact.map(|mut oldact| unsafe { oldact.as_mut() });
+    unsafe { set_action(sig, act, oldact) }
+        .map(|_| 0)
+        .or_errno(-1)
+}
+
+// BSD semantics, like glibc: the signal is blocked while its handler runs, and interrupted
+// syscalls are restarted
+#[unsafe(no_mangle)]
+unsafe extern "C" fn signal(sig: c_int, handler: usize) -> usize {
+    let mut mask = SigSet::empty();
+    if let Err(err) = mask.add(sig) {
+        set_errno(err);
+        return SIG_ERR;
+    }
+    let act = SigAction {
+        handler,
+        mask,
+        flags: SigActionFlags::SA_RESTART,
+        restorer: 0,
+    };
+    let mut oldact = SigAction::default();
+    unsafe { set_action(sig, Some(&act), Some(&mut oldact)) }
+        .map(|_| oldact.handler)
+        .or_errno(SIG_ERR)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn raise(sig: c_int) -> c_int {
+    shellder::unistd::raise(sig).map(|_| 0).or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn kill(pid: pid_t, sig: c_int) -> c_int {
+    shellder::unistd::kill(pid, sig).map(|_| 0).or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn sigprocmask(
+    how: c_int,
+    set: Option<NonNull<SigSet>>,
+    oldset: Option<NonNull<SigSet>>,
+) -> c_int {
+    let set = set.map(|set| unsafe { set.as_ref() });
+    let oldset = oldset.map(|mut oldset| unsafe { oldset.as_mut() });
+    shellder::unistd::rt_sigprocmask(how, set, oldset)
+        .map(|_| 0)
+        .or_errno(-1)
+}
+
+// Like `sigprocmask()`, but returns the error rather than setting errno
+#[unsafe(no_mangle)]
+unsafe extern "C" fn pthread_sigmask(
+    how: c_int,
+    set: Option<NonNull<SigSet>>,
+    oldset: Option<NonNull<SigSet>>,
+) -> c_int {
+    let set = set.map(|set| unsafe { set.as_ref() });
+    let oldset = oldset.map(|mut oldset| unsafe { oldset.as_mut() });
+    match shellder::unistd::rt_sigprocmask(how, set, oldset) {
+        Ok(()) => 0,
+        Err(err) => err.to_posix().as_positive(),
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn sigsuspend(mask: Option<NonNull<SigSet>>) -> c_int {
+    let mask = mask.expect("Unexpected null arg to `sigsuspend()`");
+    shellder::unistd::sigsuspend(unsafe { mask.as_ref() })
+        .map(|_| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn sigpending(set: Option<NonNull<SigSet>>) -> c_int {
+    let Some(set) = set else {
+        set_errno(Errno::EFAULT);
+        return -1;
+    };
+    shellder::unistd::sigpending()
+        .map(|pending| unsafe { *set.as_ptr() = pending })
+        .map(|_| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn sigaltstack(
+    ss: Option<NonNull<StackT>>,
+    old_ss: Option<NonNull<StackT>>,
+) -> c_int {
+    let ss = ss.map(|ss| unsafe { ss.as_ref() });
+    let old_ss = old_ss.map(|mut old_ss| unsafe { old_ss.as_mut() });
+    unsafe { shellder::unistd::sigaltstack(ss, old_ss) }
+        .map(|_| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn signalfd(
+    fd: c_int,
+    mask: Option<NonNull<SigSet>>,
+    flags: SignalFdFlags,
+) -> c_int {
+    let Some(mask) = mask else {
+        set_errno(Errno::EFAULT);
+        return -1;
+    };
+    shellder::unistd::signalfd(fd, unsafe { mask.as_ref() }, flags).or_errno(-1)
+}
+
+// Apply `function` to a signal set, failing with `EINVAL` if it's null
+unsafe fn with_set<T>(
+    set: Option<NonNull<SigSet>>,
+    function: impl FnOnce(&mut SigSet) -> Result<T, Errno>,
+) -> Result<T, Errno> {
+    let mut set = set.ok_or(Errno::EINVAL)?;
+    function(unsafe { set.as_mut() })
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn sigemptyset(set: Option<NonNull<SigSet>>) -> c_int {
+    unsafe {
+        with_set(set, |set| {
+            *set = SigSet::empty();
+            Ok(())
+        })
+    }
+    .map(|_| 0)
+    .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn sigfillset(set: Option<NonNull<SigSet>>) -> c_int {
+    unsafe {
+        with_set(set, |set| {
+            *set = SigSet::full();
+            Ok(())
+        })
+    }
+    .map(|_| 0)
+    .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn sigaddset(set: Option<NonNull<SigSet>>, signum: c_int) -> c_int {
+    unsafe { with_set(set, |set| set.add(signum)) }
+        .map(|_| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn sigdelset(set: Option<NonNull<SigSet>>, signum: c_int) -> c_int {
+    unsafe { with_set(set, |set| set.remove(signum)) }
+        .map(|_| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn sigismember(set: Option<NonNull<SigSet>>, signum: c_int) -> c_int {
+    unsafe { with_set(set, |set| set.contains(signum)) }
+        .map(c_int::from)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn sigisemptyset(set: Option<NonNull<SigSet>>) -> c_int {
+    unsafe { with_set(set, |set| Ok(set.is_empty())) }
+        .map(c_int::from)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn __libc_current_sigrtmin() -> c_int {
+    SIGRTMIN
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn __libc_current_sigrtmax() -> c_int {
+    SIGRTMAX
+}
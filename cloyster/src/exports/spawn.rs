@@ -0,0 +1,564 @@
+//! `posix_spawn()`, which starts a program without copying the caller's memory
+//!
+//! The child is created with `CLONE_VM | CLONE_VFORK`, so it runs in the caller's address space,
+//! on a stack of its own, while the caller waits for it to exec or exit
+use alloc::{borrow::ToOwned, ffi::CString, vec::Vec};
+use core::{
+    ffi::{CStr, c_char, c_int, c_short, c_void},
+    mem::ManuallyDrop,
+    ptr::{self, NonNull},
+    slice,
+};
+use shellder::{Errno, types::*, unistd};
+
+// Enough for the child to set itself up and search `PATH`
+const STACK_SIZE: usize = 0x10000;
+
+/// Something for the child to do before it execs
+pub(super) enum FileAction {
+    Open {
+        fd: c_int,
+        path: CString,
+        flags: OpenFlags,
+        mode: ModeFlags,
+    },
+    Close(c_int),
+    Dup2(c_int, c_int),
+    Chdir(CString),
+    Fchdir(c_int),
+}
+
+// Laid out like glibc's `posix_spawn_file_actions_t`, with the parts of a `Vec` of actions stored
+// in glibc's fields
+#[repr(C)]
+struct FileActions {
+    allocated: c_int,
+    used: c_int,
+    actions: *mut FileAction,
+    pad: [c_int; 16],
+}
+
+impl FileActions {
+    const fn new() -> Self {
+        Self {
+            allocated: 0,
+            used: 0,
+            actions: ptr::null_mut(),
+            pad: [0; 16],
+        }
+    }
+
+    fn actions(&self) -> &[FileAction] {
+        if self.actions.is_null() {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(self.actions, self.used as usize) }
+    }
+
+    // Take ownership of the actions, leaving none behind
+    fn take(&mut self) -> Vec<FileAction> {
+        if self.actions.is_null() {
+            return Vec::new();
+        }
+        let actions = unsafe {
+            Vec::from_raw_parts(self.actions, self.used as usize, self.allocated as usize)
+        };
+        *self = Self::new();
+        actions
+    }
+
+    fn push(&mut self, action: FileAction) -> c_int {
+        let mut actions = self.take();
+        let result = match actions.try_reserve(1) {
+            Ok(()) => {
+                actions.push(action);
+                0
+            }
+            Err(_) => Errno::ENOMEM.as_positive(),
+        };
+        let mut actions = ManuallyDrop::new(actions);
+        self.allocated = actions.capacity() as c_int;
+        self.used = actions.len() as c_int;
+        self.actions = actions.as_mut_ptr();
+        result
+    }
+}
+
+// Add `action` to `file_actions`, returning an error number like the `posix_spawn_*` functions
+unsafe fn add_action(file_actions: Option<NonNull<FileActions>>, action: FileAction) -> c_int {
+    let mut file_actions = file_actions.expect("Unexpected null `posix_spawn_file_actions_t`");
+    unsafe { file_actions.as_mut() }.push(action)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawn_file_actions_init(
+    file_actions: Option<NonNull<FileActions>>,
+) -> c_int {
+    let file_actions =
+        file_actions.expect("Unexpected null arg to `posix_spawn_file_actions_init()`");
+    unsafe { file_actions.write(FileActions::new()) };
+    0
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawn_file_actions_destroy(
+    file_actions: Option<NonNull<FileActions>>,
+) -> c_int {
+    let mut file_actions =
+        file_actions.expect("Unexpected null arg to `posix_spawn_file_actions_destroy()`");
+    drop(unsafe { file_actions.as_mut() }.take());
+    0
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawn_file_actions_addopen(
+    file_actions: Option<NonNull<FileActions>>,
+    fd: c_int,
+    path: *const c_char,
+    flags: OpenFlags,
+    mode: ModeFlags,
+) -> c_int {
+    assert!(!path.is_null());
+    if fd < 0 {
+        return Errno::EBADF.as_positive();
+    }
+    let path = unsafe { CStr::from_ptr(path) }.to_owned();
+    unsafe {
+        add_action(
+            file_actions,
+            FileAction::Open {
+                fd,
+                path,
+                flags,
+                mode,
+            },
+        )
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawn_file_actions_addclose(
+    file_actions: Option<NonNull<FileActions>>,
+    fd: c_int,
+) -> c_int {
+    if fd < 0 {
+        return Errno::EBADF.as_positive();
+    }
+    unsafe { add_action(file_actions, FileAction::Close(fd)) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawn_file_actions_adddup2(
+    file_actions: Option<NonNull<FileActions>>,
+    fd: c_int,
+    newfd: c_int,
+) -> c_int {
+    if fd < 0 || newfd < 0 {
+        return Errno::EBADF.as_positive();
+    }
+    unsafe { add_action(file_actions, FileAction::Dup2(fd, newfd)) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawn_file_actions_addchdir(
+    file_actions: Option<NonNull<FileActions>>,
+    path: *const c_char,
+) -> c_int {
+    assert!(!path.is_null());
+    let path = unsafe { CStr::from_ptr(path) }.to_owned();
+    unsafe { add_action(file_actions, FileAction::Chdir(path)) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawn_file_actions_addfchdir(
+    file_actions: Option<NonNull<FileActions>>,
+    fd: c_int,
+) -> c_int {
+    if fd < 0 {
+        return Errno::EBADF.as_positive();
+    }
+    unsafe { add_action(file_actions, FileAction::Fchdir(fd)) }
+}
+
+// The names glibc used before the functions were standardized
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawn_file_actions_addchdir_np(
+    file_actions: Option<NonNull<FileActions>>,
+    path: *const c_char,
+) -> c_int {
+    unsafe { posix_spawn_file_actions_addchdir(file_actions, path) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawn_file_actions_addfchdir_np(
+    file_actions: Option<NonNull<FileActions>>,
+    fd: c_int,
+) -> c_int {
+    unsafe { posix_spawn_file_actions_addfchdir(file_actions, fd) }
+}
+
+// Apply `function` to a set of attributes, which must not be null
+unsafe fn with_attr(
+    attr: Option<NonNull<SpawnAttr>>,
+    function: impl FnOnce(&mut SpawnAttr) -> Result<(), Errno>,
+) -> c_int {
+    let mut attr = attr.expect("Unexpected null `posix_spawnattr_t`");
+    match function(unsafe { attr.as_mut() }) {
+        Ok(()) => 0,
+        Err(err) => err.as_positive(),
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawnattr_init(attr: Option<NonNull<SpawnAttr>>) -> c_int {
+    let attr = attr.expect("Unexpected null arg to `posix_spawnattr_init()`");
+    unsafe { attr.write(SpawnAttr::default()) };
+    0
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawnattr_destroy(attr: Option<NonNull<SpawnAttr>>) -> c_int {
+    unsafe { with_attr(attr, |_| Ok(())) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawnattr_getflags(
+    attr: Option<NonNull<SpawnAttr>>,
+    flags: NonNull<c_short>,
+) -> c_int {
+    unsafe {
+        with_attr(attr, |attr| {
+            flags.write(attr.flags.bits());
+            Ok(())
+        })
+    }
+}
+
+// Fails with `EINVAL` for flags that aren't supported, such as the scheduling ones
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawnattr_setflags(
+    attr: Option<NonNull<SpawnAttr>>,
+    flags: c_short,
+) -> c_int {
+    unsafe {
+        with_attr(attr, |attr| {
+            attr.flags = SpawnFlags::from_bits(flags).ok_or(Errno::EINVAL)?;
+            Ok(())
+        })
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawnattr_getpgroup(
+    attr: Option<NonNull<SpawnAttr>>,
+    pgroup: NonNull<pid_t>,
+) -> c_int {
+    unsafe {
+        with_attr(attr, |attr| {
+            pgroup.write(attr.pgroup);
+            Ok(())
+        })
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawnattr_setpgroup(
+    attr: Option<NonNull<SpawnAttr>>,
+    pgroup: pid_t,
+) -> c_int {
+    unsafe {
+        with_attr(attr, |attr| {
+            attr.pgroup = pgroup;
+            Ok(())
+        })
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawnattr_getsigdefault(
+    attr: Option<NonNull<SpawnAttr>>,
+    sigdefault: NonNull<SigSet>,
+) -> c_int {
+    unsafe {
+        with_attr(attr, |attr| {
+            sigdefault.write(attr.sigdefault);
+            Ok(())
+        })
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawnattr_setsigdefault(
+    attr: Option<NonNull<SpawnAttr>>,
+    sigdefault: NonNull<SigSet>,
+) -> c_int {
+    unsafe {
+        with_attr(attr, |attr| {
+            attr.sigdefault = sigdefault.read();
+            Ok(())
+        })
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawnattr_getsigmask(
+    attr: Option<NonNull<SpawnAttr>>,
+    sigmask: NonNull<SigSet>,
+) -> c_int {
+    unsafe {
+        with_attr(attr, |attr| {
+            sigmask.write(attr.sigmask);
+            Ok(())
+        })
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawnattr_setsigmask(
+    attr: Option<NonNull<SpawnAttr>>,
+    sigmask: NonNull<SigSet>,
+) -> c_int {
+    unsafe {
+        with_attr(attr, |attr| {
+            attr.sigmask = sigmask.read();
+            Ok(())
+        })
+    }
+}
+
+// Everything the child needs, shared with the parent
+struct Child<'a> {
+    path: &'a CStr,
+    // Whether to search `PATH` for `path`
+    search: bool,
+    actions: &'a [FileAction],
+    attr: SpawnAttr,
+    argv: *const *const c_char,
+    envp: *const *const c_char,
+    // Room for the arguments of a script found through `PATH`, allocated by the parent so nothing
+    // is left on its heap once the child execs
+    script_argv: Vec<*const c_char>,
+    // The parent's blocked signals, for the child to restore
+    mask: SigSet,
+    // Why the child couldn't exec
+    error: Option<Errno>,
+}
+
+impl Child<'_> {
+    // Apply the attributes and file actions
+    fn prepare(&self) -> Result<(), Errno> {
+        let flags = self.attr.flags;
+
+        // The child shares the parent's memory, so the parent's handlers mustn't run in it
+        for sig in 1..NSIG {
+            // Their actions can't be changed
+            if [SIGKILL, SIGSTOP].contains(&sig) {
+                continue;
+            }
+            let mut action = KernelSigAction::default();
+            unsafe { unistd::rt_sigaction(sig, None, Some(&mut action))? };
+            let reset = (flags.contains(SpawnFlags::POSIX_SPAWN_SETSIGDEF)
+                && self.attr.sigdefault.contains(sig)?)
+                || ![SIG_DFL, SIG_IGN].contains(&action.handler);
+            if reset {
+                let default = KernelSigAction {
+                    handler: SIG_DFL,
+                    ..Default::default()
+                };
+                unsafe { unistd::rt_sigaction(sig, Some(&default), None)? };
+            }
+        }
+
+        if flags.contains(SpawnFlags::POSIX_SPAWN_SETSID) {
+            unistd::setsid()?;
+        }
+        if flags.contains(SpawnFlags::POSIX_SPAWN_SETPGROUP) {
+            unistd::setpgid(0, self.attr.pgroup)?;
+        }
+
+        for action in self.actions {
+            match action {
+                FileAction::Open {
+                    fd,
+                    path,
+                    flags,
+                    mode,
+                } => {
+                    let opened = unsafe { unistd::open(path, *flags, *mode)? };
+                    if opened != *fd {
+                        unistd::dup2(opened, *fd)?;
+                        unistd::close(opened)?;
+                    }
+                }
+                // Like glibc, it isn't an error if the descriptor wasn't open
+                FileAction::Close(fd) => {
+                    let _ = unistd::close(*fd);
+                }
+                // Duplicating a descriptor onto itself keeps it open across the exec
+                FileAction::Dup2(fd, newfd) if fd == newfd => {
+                    let fd_flags = unistd::fcntl(*fd, F_GETFD, 0)?;
+                    unistd::fcntl(*fd, F_SETFD, (fd_flags & !FD_CLOEXEC) as usize)?;
+                }
+                FileAction::Dup2(fd, newfd) => {
+                    unistd::dup2(*fd, *newfd)?;
+                }
+                FileAction::Chdir(path) => unistd::chdir(path)?,
+                FileAction::Fchdir(fd) => unistd::fchdir(*fd)?,
+            }
+        }
+
+        let mask = if flags.contains(SpawnFlags::POSIX_SPAWN_SETSIGMASK) {
+            &self.attr.sigmask
+        } else {
+            &self.mask
+        };
+        unistd::rt_sigprocmask(SIG_SETMASK, Some(mask), None)
+    }
+
+    // Set up and exec, only returning on failure
+    fn exec(&mut self) -> Errno {
+        if let Err(err) = self.prepare() {
+            return err;
+        }
+        unsafe {
+            if self.search {
+                unistd::execvpe_in(self.path, self.argv, self.envp, &mut self.script_argv)
+            } else {
+                unistd::execve(self.path, self.argv, self.envp)
+            }
+        }
+    }
+}
+
+extern "C" fn run_child(child: *mut c_void) -> c_int {
+    let child = unsafe { &mut *(child as *mut Child) };
+    child.error = Some(child.exec());
+    127
+}
+
+// Start the child, and wait for it to exec
+fn start(child: &mut Child) -> Result<pid_t, Errno> {
+    let stack = unsafe {
+        unistd::mmap(
+            ptr::null(),
+            STACK_SIZE,
+            MmapProtFlags::PROT_READ | MmapProtFlags::PROT_WRITE,
+            MmapFlags::MAP_PRIVATE | MmapFlags::MAP_ANONYMOUS,
+            0,
+            0,
+        )?
+    };
+
+    // No handlers may run in the child until it has reset them
+    let blocked = unistd::rt_sigprocmask(SIG_SETMASK, Some(&SigSet::full()), Some(&mut child.mask));
+    let pid = blocked.and_then(|()| unsafe {
+        unistd::clone(
+            CloneFlags::CLONE_VM | CloneFlags::CLONE_VFORK,
+            SIGCHLD,
+            stack.cast::<u8>().add(STACK_SIZE),
+            run_child,
+            ptr::from_mut(child).cast(),
+        )
+    });
+    if blocked.is_ok() {
+        let _ = unistd::rt_sigprocmask(SIG_SETMASK, Some(&child.mask), None);
+    }
+    let _ = unsafe { unistd::munmap(stack, STACK_SIZE) };
+
+    let pid = pid?;
+    if let Some(err) = child.error {
+        // Collect the child, which has already exited
+        let _ = unistd::wait4(pid, None, WaitFlags::empty(), None);
+        return Err(err);
+    }
+    Ok(pid)
+}
+
+/// Start the program at `path`, applying `actions` and `attr` in the child, like `posix_spawn()`
+///
+/// # Safety
+///
+/// `argv` and `envp` must be NULL-terminated arrays of pointers to null-terminated strings
+pub(super) unsafe fn spawn_process(
+    path: &CStr,
+    search: bool,
+    actions: &[FileAction],
+    attr: SpawnAttr,
+    argv: *const *const c_char,
+    envp: *const *const c_char,
+) -> Result<pid_t, Errno> {
+    let script_argv = if search {
+        let len = unsafe { unistd::script_argv_len(argv) };
+        let mut script_argv = Vec::new();
+        script_argv
+            .try_reserve_exact(len)
+            .map_err(|_| Errno::ENOMEM)?;
+        script_argv.resize(len, ptr::null());
+        script_argv
+    } else {
+        Vec::new()
+    };
+    let mut child = Child {
+        path,
+        search,
+        actions,
+        attr,
+        argv,
+        envp,
+        script_argv,
+        mask: SigSet::empty(),
+        error: None,
+    };
+    start(&mut child)
+}
+
+unsafe fn spawn(
+    pid: Option<NonNull<pid_t>>,
+    path: *const c_char,
+    file_actions: Option<NonNull<FileActions>>,
+    attr: Option<NonNull<SpawnAttr>>,
+    argv: *const *const c_char,
+    envp: *const *const c_char,
+    search: bool,
+) -> c_int {
+    assert!(!path.is_null());
+    let path = unsafe { CStr::from_ptr(path) };
+    let actions = file_actions.map_or(&[][..], |file_actions| unsafe {
+        file_actions.as_ref().actions()
+    });
+    let attr = attr.map_or_else(SpawnAttr::default, |attr| unsafe { attr.read() });
+    match unsafe { spawn_process(path, search, actions, attr, argv, envp) } {
+        Ok(child_pid) => {
+            if let Some(pid) = pid {
+                unsafe { pid.write(child_pid) };
+            }
+            0
+        }
+        Err(err) => err.to_posix().as_positive(),
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawn(
+    pid: Option<NonNull<pid_t>>,
+    path: *const c_char,
+    file_actions: Option<NonNull<FileActions>>,
+    attr: Option<NonNull<SpawnAttr>>,
+    argv: *const *const c_char,
+    envp: *const *const c_char,
+) -> c_int {
+    unsafe { spawn(pid, path, file_actions, attr, argv, envp, false) }
+}
+
+// Like `posix_spawn()`, but searches `PATH` for `file` if it doesn't contain a slash
+#[unsafe(no_mangle)]
+unsafe extern "C" fn posix_spawnp(
+    pid: Option<NonNull<pid_t>>,
+    file: *const c_char,
+    file_actions: Option<NonNull<FileActions>>,
+    attr: Option<NonNull<SpawnAttr>>,
+    argv: *const *const c_char,
+    envp: *const *const c_char,
+) -> c_int {
+    unsafe { spawn(pid, file, file_actions, attr, argv, envp, true) }
+}
@@ -0,0 +1,112 @@
+use super::c_result::CResult;
+use core::{
+    ffi::{CStr, c_char, c_int},
+    ptr::NonNull,
+};
+use shellder::types::*;
+
+// Store the result of a stat call in `statbuf`
+fn store<T>(result: Result<T, shellder::Errno>, statbuf: Option<NonNull<T>>) -> c_int {
+    let mut statbuf = statbuf.expect("Unexpected null buffer to stat function");
+    result
+        .map(|stat| {
+            unsafe { *statbuf.as_mut() = stat };
+            0
+        })
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fstat(fd: c_int, statbuf: Option<NonNull<Stat>>) -> c_int {
+    store(shellder::unistd::fstat(fd), statbuf)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn fstatat(
+    dirfd: c_int,
+    pathname: *const c_char,
+    statbuf: Option<NonNull<Stat>>,
+    flags: AtFlags,
+) -> c_int {
+    assert!(!pathname.is_null());
+    let pathname = unsafe { CStr::from_ptr(pathname) };
+    store(shellder::unistd::fstatat(dirfd, pathname, flags), statbuf)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn stat(pathname: *const c_char, statbuf: Option<NonNull<Stat>>) -> c_int {
+    unsafe { fstatat(AT_FDCWD, pathname, statbuf, AtFlags::empty()) }
+}
+
+// Like `stat()`, but doesn't follow a symbolic link at the end of `pathname`
+#[unsafe(no_mangle)]
+unsafe extern "C" fn lstat(pathname: *const c_char, statbuf: Option<NonNull<Stat>>) -> c_int {
+    unsafe { fstatat(AT_FDCWD, pathname, statbuf, AtFlags::AT_SYMLINK_NOFOLLOW) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn statx(
+    dirfd: c_int,
+    pathname: *const c_char,
+    flags: AtFlags,
+    mask: StatxMask,
+    statxbuf: Option<NonNull<Statx>>,
+) -> c_int {
+    assert!(!pathname.is_null());
+    let pathname = unsafe { CStr::from_ptr(pathname) };
+    store(
+        shellder::unistd::statx(dirfd, pathname, flags, mask),
+        statxbuf,
+    )
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn mkdirat(dirfd: c_int, pathname: *const c_char, mode: ModeFlags) -> c_int {
+    assert!(!pathname.is_null());
+    shellder::unistd::mkdirat(dirfd, unsafe { CStr::from_ptr(pathname) }, mode)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn mkdir(pathname: *const c_char, mode: ModeFlags) -> c_int {
+    unsafe { mkdirat(AT_FDCWD, pathname, mode) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn chmod(pathname: *const c_char, mode: ModeFlags) -> c_int {
+    assert!(!pathname.is_null());
+    shellder::unistd::chmod(unsafe { CStr::from_ptr(pathname) }, mode)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fchmod(fd: c_int, mode: ModeFlags) -> c_int {
+    shellder::unistd::fchmod(fd, mode).map(|()| 0).or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn umask(mask: ModeFlags) -> ModeFlags {
+    shellder::unistd::umask(mask)
+}
+
+// A null `pathname` means the file open as `dirfd`. Null `times` means now
+#[unsafe(no_mangle)]
+unsafe extern "C" fn utimensat(
+    dirfd: c_int,
+    pathname: *const c_char,
+    times: Option<NonNull<[TimeSpec; 2]>>,
+    flags: AtFlags,
+) -> c_int {
+    let pathname = (!pathname.is_null()).then(|| unsafe { CStr::from_ptr(pathname) });
+    let times = times.map(|times| unsafe { times.as_ref() });
+    shellder::unistd::utimensat(dirfd, pathname, times, flags)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn futimens(fd: c_int, times: Option<NonNull<[TimeSpec; 2]>>) -> c_int {
+    unsafe { utimensat(fd, core::ptr::null(), times, AtFlags::empty()) }
+}
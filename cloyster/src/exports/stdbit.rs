@@ -0,0 +1,70 @@
+use core::ffi::{c_uchar, c_uint, c_ulong, c_ulonglong, c_ushort};
+
+// Export a `stdc_*` function for each of the `_uc`, `_us`, `_ui`, `_ul` and `_ull` suffixes
+macro_rules! stdbit {
+    ($function:ident -> $return:ty: $uc:ident, $us:ident, $ui:ident, $ul:ident, $ull:ident) => {
+        stdbit!(@one $function, $uc, c_uchar, $return);
+        stdbit!(@one $function, $us, c_ushort, $return);
+        stdbit!(@one $function, $ui, c_uint, $return);
+        stdbit!(@one $function, $ul, c_ulong, $return);
+        stdbit!(@one $function, $ull, c_ulonglong, $return);
+    };
+    // Functions returning a value of the argument's type
+    ($function:ident: $uc:ident, $us:ident, $ui:ident, $ul:ident, $ull:ident) => {
+        stdbit!(@one $function, $uc, c_uchar, c_uchar);
+        stdbit!(@one $function, $us, c_ushort, c_ushort);
+        stdbit!(@one $function, $ui, c_uint, c_uint);
+        stdbit!(@one $function, $ul, c_ulong, c_ulong);
+        stdbit!(@one $function, $ull, c_ulonglong, c_ulonglong);
+    };
+    (@one $function:ident, $name:ident, $type:ty, $return:ty) => {
+        #[must_use]
+        #[unsafe(no_mangle)]
+        extern "C" fn $name(value: $type) -> $return {
+            shellder::stdbit::$function(value)
+        }
+    };
+}
+
+stdbit!(leading_zeros -> c_uint:
+    stdc_leading_zeros_uc, stdc_leading_zeros_us, stdc_leading_zeros_ui,
+    stdc_leading_zeros_ul, stdc_leading_zeros_ull);
+stdbit!(leading_ones -> c_uint:
+    stdc_leading_ones_uc, stdc_leading_ones_us, stdc_leading_ones_ui,
+    stdc_leading_ones_ul, stdc_leading_ones_ull);
+stdbit!(trailing_zeros -> c_uint:
+    stdc_trailing_zeros_uc, stdc_trailing_zeros_us, stdc_trailing_zeros_ui,
+    stdc_trailing_zeros_ul, stdc_trailing_zeros_ull);
+stdbit!(trailing_ones -> c_uint:
+    stdc_trailing_ones_uc, stdc_trailing_ones_us, stdc_trailing_ones_ui,
+    stdc_trailing_ones_ul, stdc_trailing_ones_ull);
+stdbit!(first_leading_zero -> c_uint:
+    stdc_first_leading_zero_uc, stdc_first_leading_zero_us, stdc_first_leading_zero_ui,
+    stdc_first_leading_zero_ul, stdc_first_leading_zero_ull);
+stdbit!(first_leading_one -> c_uint:
+    stdc_first_leading_one_uc, stdc_first_leading_one_us, stdc_first_leading_one_ui,
+    stdc_first_leading_one_ul, stdc_first_leading_one_ull);
+stdbit!(first_trailing_zero -> c_uint:
+    stdc_first_trailing_zero_uc, stdc_first_trailing_zero_us, stdc_first_trailing_zero_ui,
+    stdc_first_trailing_zero_ul, stdc_first_trailing_zero_ull);
+stdbit!(first_trailing_one -> c_uint:
+    stdc_first_trailing_one_uc, stdc_first_trailing_one_us, stdc_first_trailing_one_ui,
+    stdc_first_trailing_one_ul, stdc_first_trailing_one_ull);
+stdbit!(count_zeros -> c_uint:
+    stdc_count_zeros_uc, stdc_count_zeros_us, stdc_count_zeros_ui,
+    stdc_count_zeros_ul, stdc_count_zeros_ull);
+stdbit!(count_ones -> c_uint:
+    stdc_count_ones_uc, stdc_count_ones_us, stdc_count_ones_ui,
+    stdc_count_ones_ul, stdc_count_ones_ull);
+stdbit!(has_single_bit -> bool:
+    stdc_has_single_bit_uc, stdc_has_single_bit_us, stdc_has_single_bit_ui,
+    stdc_has_single_bit_ul, stdc_has_single_bit_ull);
+stdbit!(bit_width -> c_uint:
+    stdc_bit_width_uc, stdc_bit_width_us, stdc_bit_width_ui,
+    stdc_bit_width_ul, stdc_bit_width_ull);
+stdbit!(bit_floor:
+    stdc_bit_floor_uc, stdc_bit_floor_us, stdc_bit_floor_ui,
+    stdc_bit_floor_ul, stdc_bit_floor_ull);
+stdbit!(bit_ceil:
+    stdc_bit_ceil_uc, stdc_bit_ceil_us, stdc_bit_ceil_ui,
+    stdc_bit_ceil_ul, stdc_bit_ceil_ull);
@@ -1,11 +1,13 @@
+use super::c_result::CResult;
 use crate::errno;
 use core::{
     ffi::{CStr, VaListImpl, c_char, c_int, c_long, c_void},
     ptr::{self, NonNull},
 };
-use shellder::stdio::File;
-
-const EOF: c_int = -1;
+use shellder::{
+    stdio::{EOF, File},
+    types::*,
+};
 
 #[unsafe(no_mangle)]
 #[must_use]
@@ -13,10 +15,7 @@ unsafe extern "C" fn puts(s: *const c_char) -> c_int {
     assert!(!s.is_null());
     let s = unsafe { CStr::from_ptr(s) };
 
-    match shellder::stdio::puts(s) {
-        Ok(val) => val,
-        Err(_err) => EOF,
-    }
+    shellder::stdio::puts(s).or_errno(EOF)
 }
 
 #[unsafe(no_mangle)]
@@ -27,10 +26,7 @@ unsafe extern "C" fn fputs(s: *const c_char, stream: Option<NonNull<File>>) -> c
     let stream = stream.expect("Unexpected null arg to `fputs()`");
     let s = unsafe { CStr::from_ptr(s) };
 
-    match unsafe { shellder::stdio::fputs(s, stream) } {
-        Ok(val) => val,
-        Err(_err) => EOF,
-    }
+    unsafe { shellder::stdio::fputs(s, stream) }.or_errno(EOF)
 }
 
 #[unsafe(no_mangle)]
@@ -45,7 +41,7 @@ unsafe extern "C" fn clearerr(stream: Option<NonNull<File>>) {
 #[must_use]
 unsafe extern "C" fn ferror(stream: Option<NonNull<File>>) -> c_int {
     let stream = stream.expect("Unexpected null arg to `ferror()`");
-    unsafe { shellder::stdio::ferror(stream).as_positive() }
+    unsafe { shellder::stdio::ferror(stream) }.map_or(0, |err| err.as_positive())
 }
 
 #[unsafe(no_mangle)]
@@ -55,13 +51,16 @@ unsafe extern "C" fn feof(stream: Option<NonNull<File>>) -> c_int {
     unsafe { shellder::stdio::feof(stream).into() }
 }
 
+#[unsafe(no_mangle)]
+unsafe extern "C" fn perror(s: *const c_char) {
+    let s = (!s.is_null()).then(|| unsafe { CStr::from_ptr(s) });
+    let _ = shellder::stdio::perror(s, errno::get_errno());
+}
+
 #[unsafe(no_mangle)]
 #[must_use]
 extern "C" fn putchar(c: c_int) -> c_int {
-    match shellder::stdio::putchar(c) {
-        Ok(val) => val,
-        Err(_err) => EOF,
-    }
+    shellder::stdio::putchar(c).or_errno(EOF)
 }
 
 // This is an alias for `fputc`
@@ -75,31 +74,20 @@ unsafe extern "C" fn putc(c: c_int, stream: Option<NonNull<File>>) -> c_int {
 #[must_use]
 unsafe extern "C" fn fputc(c: c_int, stream: Option<NonNull<File>>) -> c_int {
     unsafe {
-        match shellder::stdio::fputc(c, stream.expect("Unexpected null arg to `putc()`")) {
-            Ok(val) => val,
-            Err(_err) => EOF,
-        }
+        shellder::stdio::fputc(c, stream.expect("Unexpected null arg to `putc()`")).or_errno(EOF)
     }
 }
 
 #[unsafe(no_mangle)]
 #[must_use]
 unsafe extern "C" fn getc(stream: Option<NonNull<File>>) -> c_int {
-    unsafe {
-        match shellder::stdio::getc(stream.expect("Unexpected null arg to `getc()`")) {
-            Ok(val) => val,
-            Err(_err) => EOF,
-        }
-    }
+    unsafe { shellder::stdio::getc(stream.expect("Unexpected null arg to `getc()`")).or_errno(EOF) }
 }
 
 #[unsafe(no_mangle)]
 #[must_use]
 extern "C" fn getchar() -> c_int {
-    match shellder::stdio::getchar() {
-        Ok(val) => val,
-        Err(_err) => EOF,
-    }
+    shellder::stdio::getchar().or_errno(EOF)
 }
 
 #[unsafe(no_mangle)]
@@ -116,7 +104,7 @@ unsafe extern "C" fn vprintf(fmt: *const c_char, args: VaListImpl) -> c_int {
         let fmt = CStr::from_ptr(fmt);
         shellder::stdio::printf(fmt, args)
     }
-    .unwrap_or_else(|err| err.as_negative())
+    .or_errno(-1)
 }
 
 #[unsafe(no_mangle)]
@@ -134,7 +122,7 @@ unsafe extern "C" fn sprintf(s: Option<NonNull<c_char>>, fmt: *const c_char, arg
         let fmt = CStr::from_ptr(fmt);
         shellder::stdio::snprintf(s, usize::MAX, fmt, args)
     }
-    .unwrap_or_else(|err| err.as_negative())
+    .or_errno(-1)
 }
 
 #[unsafe(no_mangle)]
@@ -151,7 +139,7 @@ unsafe extern "C" fn snprintf(
         let fmt = CStr::from_ptr(fmt);
         shellder::stdio::snprintf(s, size, fmt, args)
     }
-    .unwrap_or_else(|err| err.as_negative())
+    .or_errno(-1)
 }
 
 #[unsafe(no_mangle)]
@@ -163,7 +151,7 @@ unsafe extern "C" fn vfprintf(stream: *mut File, fmt: *const c_char, args: VaLis
         let fmt = CStr::from_ptr(fmt);
         shellder::stdio::fprintf(stream, fmt, args)
     }
-    .unwrap_or_else(|err| err.as_negative())
+    .or_errno(-1)
 }
 
 #[unsafe(no_mangle)]
@@ -173,13 +161,20 @@ unsafe extern "C" fn fopen(pathname: *const c_char, mode: *const c_char) -> *mut
     assert!(!mode.is_null());
     let (pathname, mode) = unsafe { (CStr::from_ptr(pathname), CStr::from_ptr(mode)) };
 
-    match shellder::stdio::fopen(pathname, mode) {
-        Ok(val) => val.as_ptr(),
-        Err(err) => {
-            errno::set_errno(err);
-            ptr::null_mut()
-        }
-    }
+    shellder::stdio::fopen(pathname, mode)
+        .map(NonNull::as_ptr)
+        .or_errno(ptr::null_mut())
+}
+
+#[unsafe(no_mangle)]
+#[must_use]
+unsafe extern "C" fn fdopen(fd: c_int, mode: *const c_char) -> *mut File {
+    assert!(!mode.is_null());
+    let mode = unsafe { CStr::from_ptr(mode) };
+
+    shellder::stdio::fdopen(fd, mode)
+        .map(NonNull::as_ptr)
+        .or_errno(ptr::null_mut())
 }
 
 #[unsafe(no_mangle)]
@@ -193,15 +188,7 @@ unsafe extern "C" fn fread(
     let ptr = ptr.expect("Unexpected null arg to `fread()`");
     let file = file.expect("Unexpected null arg to `fread()`");
 
-    unsafe {
-        match shellder::stdio::fread(ptr.cast(), size, nmemb, file) {
-            Ok(val) => val,
-            Err(err) => {
-                (*file.as_ptr()).error = err.as_positive();
-                0
-            }
-        }
-    }
+    unsafe { shellder::stdio::fread(ptr.cast(), size, nmemb, file) }.or_errno(0)
 }
 
 #[unsafe(no_mangle)]
@@ -215,15 +202,7 @@ unsafe extern "C" fn fwrite(
     let file = file.expect("Unexpected null arg to `fread()`");
     assert!(!ptr.is_null());
 
-    unsafe {
-        match shellder::stdio::fwrite(ptr as *const u8, size, nmemb, file) {
-            Ok(val) => val,
-            Err(err) => {
-                (*file.as_ptr()).error = err.as_positive();
-                0
-            }
-        }
-    }
+    unsafe { shellder::stdio::fwrite(ptr as *const u8, size, nmemb, file) }.or_errno(0)
 }
 
 #[unsafe(no_mangle)]
@@ -231,15 +210,9 @@ unsafe extern "C" fn fwrite(
 unsafe extern "C" fn fseek(stream: Option<NonNull<File>>, offset: c_long, whence: c_int) -> c_int {
     let stream = stream.expect("Unexpected null arg to `fread()`");
 
-    unsafe {
-        match shellder::stdio::fseek(stream, offset, whence) {
-            Ok(()) => 0,
-            Err(err) => {
-                errno::set_errno(err);
-                EOF
-            }
-        }
-    }
+    unsafe { shellder::stdio::fseek(stream, offset, whence) }
+        .map(|()| 0)
+        .or_errno(-1)
 }
 
 #[unsafe(no_mangle)]
@@ -247,15 +220,7 @@ unsafe extern "C" fn fseek(stream: Option<NonNull<File>>, offset: c_long, whence
 unsafe extern "C" fn ftell(file: Option<NonNull<File>>) -> c_long {
     let file = file.expect("Unexpected null arg to `fread()`");
 
-    unsafe {
-        match shellder::stdio::ftell(file) {
-            Ok(val) => val,
-            Err(err) => {
-                errno::set_errno(err);
-                -1
-            }
-        }
-    }
+    unsafe { shellder::stdio::ftell(file) }.or_errno(-1)
 }
 
 #[unsafe(no_mangle)]
@@ -263,13 +228,52 @@ unsafe extern "C" fn ftell(file: Option<NonNull<File>>) -> c_long {
 unsafe extern "C" fn fclose(file: Option<NonNull<File>>) -> c_int {
     let file = file.expect("Unexpected null arg to `fread()`");
 
-    unsafe {
-        match shellder::stdio::fclose(file) {
-            Ok(()) => 0,
-            Err(err) => {
-                errno::set_errno(err);
-                EOF
-            }
-        }
-    }
+    unsafe { shellder::stdio::fclose(file) }
+        .map(|()| 0)
+        .or_errno(EOF)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn fflush(stream: Option<NonNull<File>>) -> c_int {
+    unsafe { shellder::stdio::fflush(stream) }
+        .map(|()| 0)
+        .or_errno(EOF)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn remove(pathname: *const c_char) -> c_int {
+    assert!(!pathname.is_null());
+    shellder::stdio::remove(unsafe { CStr::from_ptr(pathname) })
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn renameat2(
+    olddirfd: c_int,
+    oldpath: *const c_char,
+    newdirfd: c_int,
+    newpath: *const c_char,
+    flags: RenameFlags,
+) -> c_int {
+    assert!(!oldpath.is_null() && !newpath.is_null());
+    let (oldpath, newpath) = unsafe { (CStr::from_ptr(oldpath), CStr::from_ptr(newpath)) };
+    shellder::unistd::renameat2(olddirfd, oldpath, newdirfd, newpath, flags)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn renameat(
+    olddirfd: c_int,
+    oldpath: *const c_char,
+    newdirfd: c_int,
+    newpath: *const c_char,
+) -> c_int {
+    unsafe { renameat2(olddirfd, oldpath, newdirfd, newpath, RenameFlags::empty()) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn rename(oldpath: *const c_char, newpath: *const c_char) -> c_int {
+    unsafe { renameat(AT_FDCWD, oldpath, AT_FDCWD, newpath) }
 }
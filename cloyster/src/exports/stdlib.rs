@@ -1,4 +1,17 @@
-use core::ffi::{CStr, c_char, c_double, c_int, c_long, c_longlong};
+use super::c_result::CResult;
+use crate::errno::set_errno;
+use core::{
+    ffi::{
+        CStr, c_char, c_double, c_float, c_int, c_long, c_longlong, c_uint, c_ulong, c_ulonglong,
+        c_ushort, c_void,
+    },
+    ptr::{self, NonNull},
+};
+use shellder::{
+    Errno,
+    stdlib::{Conversion, div_t, imaxdiv_t, intmax_t, ldiv_t, lldiv_t},
+    types::PATH_MAX,
+};
 
 #[must_use]
 #[unsafe(no_mangle)]
@@ -6,30 +19,314 @@ unsafe extern "C" fn abs(x: c_int) -> c_int {
     x.abs()
 }
 
+#[must_use]
+#[unsafe(no_mangle)]
+extern "C" fn labs(x: c_long) -> c_long {
+    x.abs()
+}
+
+#[must_use]
+#[unsafe(no_mangle)]
+extern "C" fn llabs(x: c_longlong) -> c_longlong {
+    x.abs()
+}
+
+#[must_use]
+#[unsafe(no_mangle)]
+extern "C" fn imaxabs(x: intmax_t) -> intmax_t {
+    x.abs()
+}
+
+#[must_use]
+#[unsafe(no_mangle)]
+extern "C" fn div(numer: c_int, denom: c_int) -> div_t {
+    shellder::stdlib::div(numer, denom)
+}
+
+#[must_use]
+#[unsafe(no_mangle)]
+extern "C" fn ldiv(numer: c_long, denom: c_long) -> ldiv_t {
+    shellder::stdlib::ldiv(numer, denom)
+}
+
+#[must_use]
+#[unsafe(no_mangle)]
+extern "C" fn lldiv(numer: c_longlong, denom: c_longlong) -> lldiv_t {
+    shellder::stdlib::lldiv(numer, denom)
+}
+
+#[must_use]
+#[unsafe(no_mangle)]
+extern "C" fn imaxdiv(numer: intmax_t, denom: intmax_t) -> imaxdiv_t {
+    shellder::stdlib::imaxdiv(numer, denom)
+}
+
 #[must_use]
 #[unsafe(no_mangle)]
 unsafe extern "C" fn atoi(nptr: *const c_char) -> c_int {
     let nptr = unsafe { CStr::from_ptr(nptr) };
-    shellder::stdlib::atoi(nptr).unwrap_or(0)
+    shellder::stdlib::atoi(nptr)
 }
 
 #[must_use]
 #[unsafe(no_mangle)]
 unsafe extern "C" fn atol(nptr: *const c_char) -> c_long {
     let nptr = unsafe { CStr::from_ptr(nptr) };
-    shellder::stdlib::atol(nptr).unwrap_or(0)
+    shellder::stdlib::atol(nptr)
 }
 
 #[must_use]
 #[unsafe(no_mangle)]
 unsafe extern "C" fn atoll(nptr: *const c_char) -> c_longlong {
     let nptr = unsafe { CStr::from_ptr(nptr) };
-    shellder::stdlib::atoll(nptr).unwrap_or(0)
+    shellder::stdlib::atoll(nptr)
 }
 
 #[must_use]
 #[unsafe(no_mangle)]
 unsafe extern "C" fn atof(nptr: *const c_char) -> c_double {
     let nptr = unsafe { CStr::from_ptr(nptr) };
-    shellder::stdlib::atof(nptr).unwrap_or(0.0)
+    shellder::stdlib::atof(nptr)
+}
+
+// Report where a conversion stopped through `endptr`, and any error through errno. Like glibc,
+// `endptr` is left alone if the base was invalid
+unsafe fn finish_conversion<T>(
+    nptr: *const c_char,
+    endptr: Option<NonNull<*mut c_char>>,
+    conversion: Conversion<T>,
+) -> T {
+    if let Some(err) = conversion.error {
+        set_errno(err);
+    }
+    if let Some(endptr) = endptr.filter(|_| conversion.error != Some(Errno::EINVAL)) {
+        unsafe { *endptr.as_ptr() = nptr.add(conversion.consumed) as *mut c_char };
+    }
+    conversion.value
+}
+
+macro_rules! integer_conversion {
+    ($name:ident -> $type:ty) => {
+        #[unsafe(no_mangle)]
+        unsafe extern "C" fn $name(
+            nptr: *const c_char,
+            endptr: Option<NonNull<*mut c_char>>,
+            base: c_int,
+        ) -> $type {
+            assert!(!nptr.is_null());
+            let conversion = shellder::stdlib::$name(unsafe { CStr::from_ptr(nptr) }, base);
+            unsafe { finish_conversion(nptr, endptr, conversion) }
+        }
+    };
+}
+
+integer_conversion!(strtol -> c_long);
+integer_conversion!(strtoll -> c_longlong);
+integer_conversion!(strtoul -> c_ulong);
+integer_conversion!(strtoull -> c_ulonglong);
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn strtod(nptr: *const c_char, endptr: Option<NonNull<*mut c_char>>) -> c_double {
+    assert!(!nptr.is_null());
+    let conversion = shellder::stdlib::strtod(unsafe { CStr::from_ptr(nptr) });
+    unsafe { finish_conversion(nptr, endptr, conversion) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn strtof(nptr: *const c_char, endptr: Option<NonNull<*mut c_char>>) -> c_float {
+    assert!(!nptr.is_null());
+    let conversion = shellder::stdlib::strtof(unsafe { CStr::from_ptr(nptr) });
+    unsafe { finish_conversion(nptr, endptr, conversion) }
+}
+
+#[must_use]
+#[unsafe(no_mangle)]
+unsafe extern "C" fn getenv(name: *const c_char) -> *mut c_char {
+    assert!(!name.is_null());
+    let name = unsafe { CStr::from_ptr(name) };
+    shellder::stdlib::getenv(name)
+        .map(|v| v.as_ptr() as *mut c_char)
+        .unwrap_or(ptr::null_mut())
+}
+
+type Comparator = unsafe extern "C" fn(*const c_void, *const c_void) -> c_int;
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn qsort(base: *mut c_void, nmemb: usize, size: usize, compar: Comparator) {
+    unsafe { shellder::stdlib::qsort(base, nmemb, size, |a, b| compar(a, b).cmp(&0)) }
+}
+
+// GNU argument order, where `arg` comes last
+#[unsafe(no_mangle)]
+unsafe extern "C" fn qsort_r(
+    base: *mut c_void,
+    nmemb: usize,
+    size: usize,
+    compar: unsafe extern "C" fn(*const c_void, *const c_void, *mut c_void) -> c_int,
+    arg: *mut c_void,
+) {
+    unsafe { shellder::stdlib::qsort(base, nmemb, size, |a, b| compar(a, b, arg).cmp(&0)) }
+}
+
+#[must_use]
+#[unsafe(no_mangle)]
+unsafe extern "C" fn bsearch(
+    key: *const c_void,
+    base: *const c_void,
+    nmemb: usize,
+    size: usize,
+    compar: Comparator,
+) -> *mut c_void {
+    unsafe { shellder::stdlib::bsearch(key, base, nmemb, size, |a, b| compar(a, b).cmp(&0)) }
+        .map_or(ptr::null_mut(), |found| found as *mut c_void)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn rand() -> c_int {
+    shellder::stdlib::rand()
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn srand(seed: c_uint) {
+    shellder::stdlib::srand(seed);
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn rand_r(seed: Option<NonNull<c_uint>>) -> c_int {
+    let seed = seed.expect("Unexpected null arg to `rand_r()`");
+    shellder::stdlib::rand_r(unsafe { &mut *seed.as_ptr() })
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn random() -> c_long {
+    shellder::stdlib::random()
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn srandom(seed: c_uint) {
+    shellder::stdlib::srandom(seed);
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn initstate(
+    seed: c_uint,
+    state: Option<NonNull<c_char>>,
+    n: usize,
+) -> *mut c_char {
+    let state = state.expect("Unexpected null arg to `initstate()`");
+    unsafe { shellder::stdlib::initstate(seed, state, n) }
+        .map(NonNull::as_ptr)
+        .or_errno(ptr::null_mut())
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn setstate(state: Option<NonNull<c_char>>) -> *mut c_char {
+    let state = state.expect("Unexpected null arg to `setstate()`");
+    unsafe { shellder::stdlib::setstate(state) }
+        .map(NonNull::as_ptr)
+        .or_errno(ptr::null_mut())
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn drand48() -> c_double {
+    shellder::stdlib::drand48()
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn erand48(xsubi: Option<NonNull<[c_ushort; 3]>>) -> c_double {
+    let xsubi = xsubi.expect("Unexpected null arg to `erand48()`");
+    shellder::stdlib::erand48(unsafe { &mut *xsubi.as_ptr() })
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn lrand48() -> c_long {
+    shellder::stdlib::lrand48()
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn nrand48(xsubi: Option<NonNull<[c_ushort; 3]>>) -> c_long {
+    let xsubi = xsubi.expect("Unexpected null arg to `nrand48()`");
+    shellder::stdlib::nrand48(unsafe { &mut *xsubi.as_ptr() })
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn mrand48() -> c_long {
+    shellder::stdlib::mrand48()
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn jrand48(xsubi: Option<NonNull<[c_ushort; 3]>>) -> c_long {
+    let xsubi = xsubi.expect("Unexpected null arg to `jrand48()`");
+    shellder::stdlib::jrand48(unsafe { &mut *xsubi.as_ptr() })
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn srand48(seed: c_long) {
+    shellder::stdlib::srand48(seed);
+}
+
+// `seed48()` returns the previous state in a static buffer
+static mut SEED48_PREVIOUS: [c_ushort; 3] = [0; 3];
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn seed48(seed16v: Option<NonNull<[c_ushort; 3]>>) -> *mut c_ushort {
+    let seed16v = seed16v.expect("Unexpected null arg to `seed48()`");
+    let previous = shellder::stdlib::seed48(unsafe { *seed16v.as_ptr() });
+    unsafe {
+        SEED48_PREVIOUS = previous;
+        ptr::addr_of_mut!(SEED48_PREVIOUS).cast()
+    }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn lcong48(param: Option<NonNull<[c_ushort; 7]>>) {
+    let param = param.expect("Unexpected null arg to `lcong48()`");
+    shellder::stdlib::lcong48(unsafe { *param.as_ptr() });
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn arc4random() -> u32 {
+    shellder::stdlib::arc4random()
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn arc4random_buf(buf: *mut c_void, nbytes: usize) {
+    assert!(!buf.is_null() || nbytes == 0);
+    if nbytes > 0 {
+        shellder::stdlib::arc4random_buf(unsafe {
+            core::slice::from_raw_parts_mut(buf as *mut u8, nbytes)
+        });
+    }
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn arc4random_uniform(upper_bound: u32) -> u32 {
+    shellder::stdlib::arc4random_uniform(upper_bound)
+}
+
+// With a null `resolved_path`, allocates the result. Otherwise, `resolved_path` must be at least
+// `PATH_MAX` bytes long
+#[unsafe(no_mangle)]
+unsafe extern "C" fn realpath(path: *const c_char, resolved_path: *mut c_char) -> *mut c_char {
+    if path.is_null() {
+        set_errno(Errno::EINVAL);
+        return ptr::null_mut();
+    }
+    let path = unsafe { CStr::from_ptr(path) };
+    if !resolved_path.is_null() {
+        let resolved = unsafe { &mut *resolved_path.cast::<[u8; PATH_MAX]>() };
+        return shellder::stdlib::realpath(path, resolved)
+            .map(|_| resolved_path)
+            .or_errno(ptr::null_mut());
+    }
+
+    let mut resolved = [0; PATH_MAX];
+    shellder::stdlib::realpath(path, &mut resolved)
+        .and_then(|resolved| {
+            let resolved = resolved.to_bytes_with_nul();
+            let buf = shellder::malloc::malloc(resolved.len())?.cast::<u8>();
+            unsafe { ptr::copy_nonoverlapping(resolved.as_ptr(), buf.as_ptr(), resolved.len()) };
+            Ok(buf.cast::<c_char>().as_ptr())
+        })
+        .or_errno(ptr::null_mut())
 }
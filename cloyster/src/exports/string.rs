@@ -2,6 +2,7 @@ use core::{
     cmp::Ordering,
     ffi::{CStr, c_char, c_int},
     ptr::{self, NonNull},
+    slice,
 };
 
 #[unsafe(no_mangle)]
@@ -149,6 +150,58 @@ unsafe extern "C" fn strcat(dst: *mut c_char, src: *const c_char) -> *mut c_char
     unsafe { shellder::string::strcat(dst, src).as_ptr() }
 }
 
+// Holds descriptions of unknown errors for `strerror()`
+// TODO: this should be thread local
+static mut STRERROR_BUF: [u8; 32] = [0; 32];
+
+#[unsafe(no_mangle)]
+extern "C" fn strerror(errnum: c_int) -> *mut c_char {
+    let buf = unsafe { &mut *ptr::addr_of_mut!(STRERROR_BUF) };
+    shellder::string::strerror_r_gnu(errnum, buf).as_ptr() as *mut c_char
+}
+
+// GNU-specific variant
+#[unsafe(no_mangle)]
+unsafe extern "C" fn strerror_r(errnum: c_int, buf: *mut c_char, buflen: usize) -> *mut c_char {
+    let buf = if buf.is_null() {
+        &mut []
+    } else {
+        unsafe { slice::from_raw_parts_mut(buf as *mut u8, buflen) }
+    };
+    shellder::string::strerror_r_gnu(errnum, buf).as_ptr() as *mut c_char
+}
+
+// XSI-compliant variant
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __xpg_strerror_r(errnum: c_int, buf: *mut c_char, buflen: usize) -> c_int {
+    let buf = if buf.is_null() {
+        &mut []
+    } else {
+        unsafe { slice::from_raw_parts_mut(buf as *mut u8, buflen) }
+    };
+    match shellder::string::strerror_r(errnum, buf) {
+        Ok(()) => 0,
+        Err(err) => err.as_positive(),
+    }
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn strerrorname_np(errnum: c_int) -> *const c_char {
+    shellder::string::strerrorname_np(errnum)
+        .map(CStr::as_ptr)
+        .unwrap_or(ptr::null())
+}
+
+// Holds descriptions of real-time and unknown signals for `strsignal()`
+// TODO: this should be thread local
+static mut STRSIGNAL_BUF: [u8; 32] = [0; 32];
+
+#[unsafe(no_mangle)]
+extern "C" fn strsignal(sig: c_int) -> *mut c_char {
+    let buf = unsafe { &mut *ptr::addr_of_mut!(STRSIGNAL_BUF) };
+    shellder::string::strsignal(sig, buf).as_ptr() as *mut c_char
+}
+
 #[unsafe(no_mangle)]
 extern "C" fn toupper(c: c_int) -> c_int {
     shellder::string::toupper(c)
@@ -1,32 +1,20 @@
-use crate::errno::set_errno;
+use super::c_result::CResult;
 use core::{
     ffi::{CStr, c_char, c_int, c_void},
     ptr::{self, NonNull},
 };
-use shellder::types::*;
+use shellder::{Errno, types::*};
 
 #[unsafe(no_mangle)]
 unsafe extern "C" fn write(fd: c_int, buf: *const c_void, count: usize) -> c_int {
     assert!(!buf.is_null());
-    match unsafe { shellder::unistd::write(fd, buf, count) } {
-        Err(errno) => {
-            set_errno(errno);
-            -1
-        }
-        Ok(val) => val,
-    }
+    unsafe { shellder::unistd::write(fd, buf, count) }.or_errno(-1)
 }
 
 #[unsafe(no_mangle)]
 unsafe extern "C" fn read(fd: c_int, buf: *mut c_void, count: usize) -> c_int {
     assert!(!buf.is_null());
-    match unsafe { shellder::unistd::read(fd, buf, count) } {
-        Err(errno) => {
-            set_errno(errno);
-            -1
-        }
-        Ok(val) => val,
-    }
+    unsafe { shellder::unistd::read(fd, buf, count) }.or_errno(-1)
 }
 
 #[unsafe(no_mangle)]
@@ -43,70 +31,38 @@ unsafe extern "C" fn mmap(
     fd: c_int,
     off_t: u64,
 ) -> *mut c_void {
-    match unsafe { shellder::unistd::mmap(addr, length, prot, flags, fd, off_t) } {
-        Err(errno) => {
-            set_errno(errno);
-            ptr::null_mut()
-        }
-        Ok(val) => val.as_ptr(),
-    }
+    unsafe { shellder::unistd::mmap(addr, length, prot, flags, fd, off_t) }
+        .map(|val| val.as_ptr())
+        .or_errno(ptr::null_mut())
 }
 
 #[unsafe(no_mangle)]
 unsafe extern "C" fn munmap(addr: Option<NonNull<c_void>>, length: usize) -> c_int {
     let addr = addr.expect("Cannot unmap null address");
-    match unsafe { shellder::unistd::munmap(addr, length) } {
-        Err(errno) => {
-            set_errno(errno);
-            -1
-        }
-        Ok(val) => val,
-    }
+    unsafe { shellder::unistd::munmap(addr, length) }.or_errno(-1)
 }
 
 #[unsafe(no_mangle)]
 unsafe extern "C" fn sbrk(size: isize) -> *mut c_void {
-    match unsafe { shellder::unistd::sbrk(size) } {
-        Err(errno) => {
-            set_errno(errno);
-            ptr::null_mut()
-        }
-        Ok(val) => val.wrapping_byte_offset(-size) as *mut c_void,
-    }
+    unsafe { shellder::unistd::sbrk(size) }
+        .map(|val| val.wrapping_byte_offset(-size) as *mut c_void)
+        .or_errno(ptr::null_mut())
 }
 
 #[unsafe(no_mangle)]
 unsafe extern "C" fn open(pathname: *const c_char, flags: OpenFlags, mode: ModeFlags) -> c_int {
     assert!(!pathname.is_null());
-    match unsafe { shellder::unistd::open(CStr::from_ptr(pathname), flags, mode) } {
-        Err(errno) => {
-            set_errno(errno);
-            -1
-        }
-        Ok(val) => val,
-    }
+    unsafe { shellder::unistd::open(CStr::from_ptr(pathname), flags, mode) }.or_errno(-1)
 }
 
 #[unsafe(no_mangle)]
 extern "C" fn close(fd: c_int) -> c_int {
-    match shellder::unistd::close(fd) {
-        Err(errno) => {
-            set_errno(errno);
-            -1
-        }
-        Ok(_val) => 0,
-    }
+    shellder::unistd::close(fd).map(|_| 0).or_errno(-1)
 }
 
 #[unsafe(no_mangle)]
-extern "C" fn lseek(fd: c_int, offset: off_t, whence: c_int) -> c_int {
-    match shellder::unistd::lseek(fd, offset, whence) {
-        Err(errno) => {
-            set_errno(errno);
-            -1
-        }
-        Ok(val) => val,
-    }
+extern "C" fn lseek(fd: c_int, offset: off_t, whence: c_int) -> off_t {
+    shellder::unistd::lseek(fd, offset, whence).or_errno(-1)
 }
 
 #[unsafe(no_mangle)]
@@ -114,35 +70,360 @@ extern "C" fn time(time: *mut time_t) -> time_t {
     if !time.is_null() {
         unimplemented!("Cloyster only currently accepts the argument to time() to be NULL");
     }
-    match shellder::unistd::time() {
-        Err(errno) => {
-            set_errno(errno);
-            -1
-        }
-        Ok(val) => val,
-    }
+    shellder::unistd::time().or_errno(-1)
 }
 
 #[unsafe(no_mangle)]
 extern "C" fn clock_gettime(id: clockid_t, tp: Option<NonNull<TimeSpec>>) -> c_int {
     let tp = tp.expect("Unexpected null arg to `clock_gettime()`");
-    match shellder::unistd::clock_gettime(id, tp) {
-        Err(errno) => {
-            set_errno(errno);
-            -1
-        }
-        Ok(val) => val,
-    }
+    shellder::unistd::clock_gettime(id, tp).or_errno(-1)
 }
 
 #[unsafe(no_mangle)]
 extern "C" fn nanosleep(req: *const TimeSpec, rem: Option<NonNull<TimeSpec>>) -> c_int {
     assert!(!req.is_null());
-    match shellder::unistd::nanosleep(req, rem) {
-        Err(errno) => {
-            set_errno(errno);
-            -1
+    shellder::unistd::nanosleep(req, rem).or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn getrandom(buf: *mut c_void, buflen: usize, flags: GetRandomFlags) -> isize {
+    assert!(!buf.is_null());
+    unsafe { shellder::unistd::getrandom(buf, buflen, flags) }
+        .and_then(|count| Ok(count.try_into()?))
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn getentropy(buffer: *mut c_void, length: usize) -> c_int {
+    assert!(!buffer.is_null());
+    let buffer = unsafe { core::slice::from_raw_parts_mut(buffer as *mut u8, length) };
+    shellder::unistd::getentropy(buffer).map(|_| 0).or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn getpid() -> pid_t {
+    shellder::unistd::getpid()
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn pipe2(pipefd: Option<NonNull<[c_int; 2]>>, flags: OpenFlags) -> c_int {
+    let mut pipefd = pipefd.expect("Unexpected null arg to `pipe2()`");
+    shellder::unistd::pipe2(flags)
+        .map(|fds| {
+            unsafe { *pipefd.as_mut() = fds };
+            0
+        })
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn pipe(pipefd: Option<NonNull<[c_int; 2]>>) -> c_int {
+    unsafe { pipe2(pipefd, OpenFlags::empty()) }
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn dup(oldfd: c_int) -> c_int {
+    shellder::unistd::dup(oldfd).or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn dup2(oldfd: c_int, newfd: c_int) -> c_int {
+    shellder::unistd::dup2(oldfd, newfd).or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn dup3(oldfd: c_int, newfd: c_int, flags: OpenFlags) -> c_int {
+    shellder::unistd::dup3(oldfd, newfd, flags).or_errno(-1)
+}
+
+// The type of the optional third argument depends on `cmd`
+#[unsafe(no_mangle)]
+unsafe extern "C" fn fcntl(fd: c_int, cmd: c_int, mut args: ...) -> c_int {
+    let arg = match cmd {
+        F_GETFD | F_GETFL => 0,
+        F_DUPFD | F_DUPFD_CLOEXEC | F_SETFD | F_SETFL => (unsafe { args.arg::<c_int>() }) as usize,
+        F_GETLK | F_SETLK | F_SETLKW | F_OFD_GETLK | F_OFD_SETLK | F_OFD_SETLKW => {
+            let lock = unsafe { args.arg::<*mut Flock>() };
+            assert!(!lock.is_null());
+            lock as usize
         }
-        Ok(val) => val,
+        _ => unsafe { args.arg::<usize>() },
+    };
+    shellder::unistd::fcntl(fd, cmd, arg).or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn flock(fd: c_int, operation: FlockFlags) -> c_int {
+    shellder::unistd::flock(fd, operation)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn lockf(fd: c_int, cmd: c_int, len: off_t) -> c_int {
+    shellder::unistd::lockf(fd, cmd, len)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn pread(fd: c_int, buf: *mut c_void, count: usize, offset: off_t) -> isize {
+    unsafe { shellder::unistd::pread(fd, buf, count, offset) }
+        .and_then(|count| Ok(count.try_into()?))
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn pwrite(fd: c_int, buf: *const c_void, count: usize, offset: off_t) -> isize {
+    unsafe { shellder::unistd::pwrite(fd, buf, count, offset) }
+        .and_then(|count| Ok(count.try_into()?))
+        .or_errno(-1)
+}
+
+// View `iovcnt` buffers from `iov` as a slice
+unsafe fn io_vecs<'a>(iov: *const IoVec, iovcnt: c_int) -> Result<&'a [IoVec], Errno> {
+    let len = usize::try_from(iovcnt).map_err(|_| Errno::EINVAL)?;
+    if len == 0 {
+        return Ok(&[]);
     }
+    assert!(!iov.is_null());
+    Ok(unsafe { core::slice::from_raw_parts(iov, len) })
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn readv(fd: c_int, iov: *const IoVec, iovcnt: c_int) -> isize {
+    unsafe { io_vecs(iov, iovcnt) }
+        .and_then(|iov| unsafe { shellder::unistd::readv(fd, iov) })
+        .and_then(|count| Ok(count.try_into()?))
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn writev(fd: c_int, iov: *const IoVec, iovcnt: c_int) -> isize {
+    unsafe { io_vecs(iov, iovcnt) }
+        .and_then(|iov| unsafe { shellder::unistd::writev(fd, iov) })
+        .and_then(|count| Ok(count.try_into()?))
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn preadv(fd: c_int, iov: *const IoVec, iovcnt: c_int, offset: off_t) -> isize {
+    unsafe { io_vecs(iov, iovcnt) }
+        .and_then(|iov| unsafe { shellder::unistd::preadv(fd, iov, offset) })
+        .and_then(|count| Ok(count.try_into()?))
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn pwritev(fd: c_int, iov: *const IoVec, iovcnt: c_int, offset: off_t) -> isize {
+    unsafe { io_vecs(iov, iovcnt) }
+        .and_then(|iov| unsafe { shellder::unistd::pwritev(fd, iov, offset) })
+        .and_then(|count| Ok(count.try_into()?))
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn preadv2(
+    fd: c_int,
+    iov: *const IoVec,
+    iovcnt: c_int,
+    offset: off_t,
+    flags: RwfFlags,
+) -> isize {
+    unsafe { io_vecs(iov, iovcnt) }
+        .and_then(|iov| unsafe { shellder::unistd::preadv2(fd, iov, offset, flags) })
+        .and_then(|count| Ok(count.try_into()?))
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn pwritev2(
+    fd: c_int,
+    iov: *const IoVec,
+    iovcnt: c_int,
+    offset: off_t,
+    flags: RwfFlags,
+) -> isize {
+    unsafe { io_vecs(iov, iovcnt) }
+        .and_then(|iov| unsafe { shellder::unistd::pwritev2(fd, iov, offset, flags) })
+        .and_then(|count| Ok(count.try_into()?))
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn unlink(pathname: *const c_char) -> c_int {
+    assert!(!pathname.is_null());
+    shellder::unistd::unlink(unsafe { CStr::from_ptr(pathname) })
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn unlinkat(dirfd: c_int, pathname: *const c_char, flags: AtFlags) -> c_int {
+    assert!(!pathname.is_null());
+    shellder::unistd::unlinkat(dirfd, unsafe { CStr::from_ptr(pathname) }, flags)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn rmdir(pathname: *const c_char) -> c_int {
+    assert!(!pathname.is_null());
+    shellder::unistd::rmdir(unsafe { CStr::from_ptr(pathname) })
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn link(oldpath: *const c_char, newpath: *const c_char) -> c_int {
+    assert!(!oldpath.is_null() && !newpath.is_null());
+    let (oldpath, newpath) = unsafe { (CStr::from_ptr(oldpath), CStr::from_ptr(newpath)) };
+    shellder::unistd::link(oldpath, newpath)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn linkat(
+    olddirfd: c_int,
+    oldpath: *const c_char,
+    newdirfd: c_int,
+    newpath: *const c_char,
+    flags: AtFlags,
+) -> c_int {
+    assert!(!oldpath.is_null() && !newpath.is_null());
+    let (oldpath, newpath) = unsafe { (CStr::from_ptr(oldpath), CStr::from_ptr(newpath)) };
+    shellder::unistd::linkat(olddirfd, oldpath, newdirfd, newpath, flags)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn symlink(target: *const c_char, linkpath: *const c_char) -> c_int {
+    assert!(!target.is_null() && !linkpath.is_null());
+    let (target, linkpath) = unsafe { (CStr::from_ptr(target), CStr::from_ptr(linkpath)) };
+    shellder::unistd::symlink(target, linkpath)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn symlinkat(
+    target: *const c_char,
+    newdirfd: c_int,
+    linkpath: *const c_char,
+) -> c_int {
+    assert!(!target.is_null() && !linkpath.is_null());
+    let (target, linkpath) = unsafe { (CStr::from_ptr(target), CStr::from_ptr(linkpath)) };
+    shellder::unistd::symlinkat(target, newdirfd, linkpath)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+// Doesn't null-terminate `buf`
+#[unsafe(no_mangle)]
+unsafe extern "C" fn readlinkat(
+    dirfd: c_int,
+    pathname: *const c_char,
+    buf: *mut c_char,
+    bufsiz: usize,
+) -> isize {
+    assert!(!pathname.is_null() && !buf.is_null());
+    let pathname = unsafe { CStr::from_ptr(pathname) };
+    let buf = unsafe { core::slice::from_raw_parts_mut(buf.cast::<u8>(), bufsiz) };
+    shellder::unistd::readlinkat(dirfd, pathname, buf)
+        .and_then(|count| Ok(count.try_into()?))
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn readlink(pathname: *const c_char, buf: *mut c_char, bufsiz: usize) -> isize {
+    unsafe { readlinkat(AT_FDCWD, pathname, buf, bufsiz) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn chown(pathname: *const c_char, owner: uid_t, group: gid_t) -> c_int {
+    assert!(!pathname.is_null());
+    shellder::unistd::chown(unsafe { CStr::from_ptr(pathname) }, owner, group)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fchown(fd: c_int, owner: uid_t, group: gid_t) -> c_int {
+    shellder::unistd::fchown(fd, owner, group)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn fchownat(
+    dirfd: c_int,
+    pathname: *const c_char,
+    owner: uid_t,
+    group: gid_t,
+    flags: AtFlags,
+) -> c_int {
+    assert!(!pathname.is_null());
+    let pathname = unsafe { CStr::from_ptr(pathname) };
+    shellder::unistd::fchownat(dirfd, pathname, owner, group, flags)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn truncate(pathname: *const c_char, length: off_t) -> c_int {
+    assert!(!pathname.is_null());
+    shellder::unistd::truncate(unsafe { CStr::from_ptr(pathname) }, length)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn ftruncate(fd: c_int, length: off_t) -> c_int {
+    shellder::unistd::ftruncate(fd, length)
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+// With a null `buf`, allocates one `size` bytes long, or as long as needed if `size` is 0
+#[unsafe(no_mangle)]
+unsafe extern "C" fn getcwd(buf: *mut c_char, size: usize) -> *mut c_char {
+    if buf.is_null() {
+        let mut path = [0; PATH_MAX];
+        return shellder::unistd::getcwd(&mut path)
+            .and_then(|path| {
+                let path = path.to_bytes_with_nul();
+                let size = match size {
+                    0 => path.len(),
+                    size if size < path.len() => return Err(Errno::ERANGE),
+                    size => size,
+                };
+                let buf = shellder::malloc::malloc(size)?.cast::<u8>();
+                unsafe { ptr::copy_nonoverlapping(path.as_ptr(), buf.as_ptr(), path.len()) };
+                Ok(buf.cast::<c_char>().as_ptr())
+            })
+            .or_errno(ptr::null_mut());
+    }
+    if size == 0 {
+        crate::errno::set_errno(Errno::EINVAL);
+        return ptr::null_mut();
+    }
+    let path = unsafe { core::slice::from_raw_parts_mut(buf.cast::<u8>(), size) };
+    shellder::unistd::getcwd(path)
+        .map(|_| buf)
+        .or_errno(ptr::null_mut())
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn chdir(path: *const c_char) -> c_int {
+    assert!(!path.is_null());
+    shellder::unistd::chdir(unsafe { CStr::from_ptr(path) })
+        .map(|()| 0)
+        .or_errno(-1)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fchdir(fd: c_int) -> c_int {
+    shellder::unistd::fchdir(fd).map(|()| 0).or_errno(-1)
 }
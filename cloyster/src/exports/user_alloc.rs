@@ -0,0 +1,24 @@
+//! The `__cloyster_user_*` functions for test binaries, forwarding to the C library they link
+use core::ffi::c_void;
+
+unsafe extern "C" {
+    fn aligned_alloc(align: usize, size: usize) -> *mut c_void;
+    fn free(ptr: *mut c_void);
+    fn malloc_usable_size(ptr: *mut c_void) -> usize;
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __cloyster_user_alloc(size: usize, align: usize) -> *mut c_void {
+    // The size has to be a multiple of the alignment
+    unsafe { aligned_alloc(align, size.next_multiple_of(align)) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __cloyster_user_free(ptr: *mut c_void) {
+    unsafe { free(ptr) }
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn __cloyster_user_size(ptr: *mut c_void) -> usize {
+    unsafe { malloc_usable_size(ptr) }
+}
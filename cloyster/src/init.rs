@@ -1,4 +1,4 @@
-use crate::tls;
+use crate::{stack_protector, tls};
 use core::ffi::{c_char, c_int};
 
 unsafe extern "C" {
@@ -10,10 +10,21 @@ unsafe extern "C" fn __cloyster_start(argc: c_int, argv: *const *const c_char) {
     crate::logging::Logger::init();
     crate::globals::init();
 
+    // The environment follows argv's terminating NULL
+    let envp = unsafe { argv.add(argc as usize + 1) };
     unsafe {
-        let fs = tls::thread_local_init().unwrap();
+        shellder::stdlib::set_environ(envp);
+    }
+
+    #[cfg(feature = "malloc_trace")]
+    shellder::malloc::trace::init_from_env();
+
+    unsafe {
+        // The TLS holds the canary, so it stays mapped for atexit handlers and is only
+        // released with the rest of the process
+        tls::thread_local_init().unwrap();
+        stack_protector::init(envp);
         let rv = main(argc, argv);
-        tls::thread_local_uninit(fs).unwrap();
         crate::exports::exit::exit(rv);
     }
 }
@@ -18,6 +18,9 @@ mod exports;
 mod globals;
 mod init;
 mod logging;
+#[cfg(not(test))]
+mod setjmp;
+mod stack_protector;
 mod tls;
 
 #[cfg(not(test))]
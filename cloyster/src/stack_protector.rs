@@ -0,0 +1,67 @@
+//! Stack smashing protection, for code compiled with `-fstack-protector`
+use core::{ffi::c_char, fmt::Write};
+
+// Auxiliary vector entry types
+const AT_NULL: usize = 0;
+// Address of 16 random bytes from the kernel
+const AT_RANDOM: usize = 25;
+
+/// Canary checked by code compiled with `-fstack-protector` on RISC-V
+#[cfg(target_arch = "riscv64")]
+#[unsafe(no_mangle)]
+#[allow(non_upper_case_globals)]
+static mut __stack_chk_guard: usize = 0;
+
+// Find the kernel's random bytes in the auxiliary vector, which follows the environment's
+// terminating NULL
+unsafe fn at_random(envp: *const *const c_char) -> Option<[u8; size_of::<usize>()]> {
+    unsafe {
+        let mut entry = envp;
+        while !(*entry).is_null() {
+            entry = entry.add(1);
+        }
+        let mut auxv = entry.add(1) as *const usize;
+        loop {
+            match *auxv {
+                AT_NULL => return None,
+                AT_RANDOM => return Some((*auxv.add(1) as *const [u8; size_of::<usize>()]).read()),
+                _ => auxv = auxv.add(2),
+            }
+        }
+    }
+}
+
+// Pick a canary and store it where the compiler expects it. Must be called after the thread
+// pointer is set up, and before any protected code runs
+pub(crate) unsafe fn init(envp: *const *const c_char) {
+    let mut canary = unsafe { at_random(envp) }.unwrap_or_else(|| {
+        let mut bytes = [0; size_of::<usize>()];
+        shellder::unistd::getentropy(&mut bytes).expect("Could not get entropy for the canary");
+        bytes
+    });
+    // A zero byte stops string functions from reading or overwriting the canary
+    canary[0] = 0;
+    let canary = usize::from_ne_bytes(canary);
+
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        crate::tls::set_stack_guard(canary);
+    }
+    #[cfg(target_arch = "riscv64")]
+    unsafe {
+        __stack_chk_guard = canary;
+    }
+}
+
+// Called by protected code when a function's canary was overwritten
+//
+// C signature:
+// `void __stack_chk_fail(void);`
+#[unsafe(no_mangle)]
+extern "C" fn __stack_chk_fail() -> ! {
+    let _ = writeln!(
+        shellder::stdio::Descriptor::stderr(),
+        "*** stack smashing detected ***: terminated"
+    );
+    shellder::stdlib::abort();
+}
@@ -1,4 +1,6 @@
 //! TLS(Thread Local Storage setup
+#[cfg(target_arch = "x86_64")]
+use core::ffi::c_int;
 use core::{
     ffi::c_void,
     ptr::{self, NonNull},
@@ -15,6 +17,31 @@ const STATIC_TLS_SIZE: usize = 0x2000;
 #[unsafe(link_section = ".gnu.linkonce.td.tdata_end")]
 static mut TDATA_END: () = ();
 
+// Thread control block the thread pointer points at, laid out like glibc's `tcbhead_t`. Code
+// compiled with `-fstack-protector` reads the canary from `%fs:0x28`
+#[cfg(target_arch = "x86_64")]
+#[repr(C, align(64))]
+struct ThreadControlBlock {
+    // The ABI requires `%fs:0` to hold the thread pointer itself
+    tcb: *mut ThreadControlBlock,
+    dtv: usize,
+    this: *mut ThreadControlBlock,
+    multiple_threads: c_int,
+    gscope_flag: c_int,
+    sysinfo: usize,
+    stack_guard: usize,
+    pointer_guard: usize,
+}
+
+#[cfg(target_arch = "x86_64")]
+const _: () = assert!(core::mem::offset_of!(ThreadControlBlock, stack_guard) == 0x28);
+
+// RISC-V keeps its canary in `__stack_chk_guard` instead, and its TLS starts at the thread pointer
+#[cfg(target_arch = "x86_64")]
+const TCB_SIZE: usize = size_of::<ThreadControlBlock>();
+#[cfg(target_arch = "riscv64")]
+const TCB_SIZE: usize = 0;
+
 unsafe fn set_thread_pointer(addr: *const c_void) -> Result<(), Errno> {
     #[cfg(target_arch = "x86_64")]
     unsafe {
@@ -44,7 +71,14 @@ pub(crate) unsafe fn thread_local_init() -> Result<NonNull<c_void>, Errno> {
             0,
         )?
     };
-    let addr = map_addr.as_ptr().wrapping_add(STATIC_TLS_SIZE);
+    let addr = map_addr.as_ptr().wrapping_add(STATIC_TLS_SIZE - TCB_SIZE);
+
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        let tcb = addr as *mut ThreadControlBlock;
+        (*tcb).tcb = tcb;
+        (*tcb).this = tcb;
+    }
 
     unsafe {
         set_thread_pointer(addr)?;
@@ -63,9 +97,14 @@ pub(crate) unsafe fn thread_local_init() -> Result<NonNull<c_void>, Errno> {
     Ok(map_addr)
 }
 
-pub(crate) unsafe fn thread_local_uninit(ptr: NonNull<c_void>) -> Result<(), Errno> {
+// Set the canary checked by code compiled with `-fstack-protector`
+#[cfg(target_arch = "x86_64")]
+pub(crate) unsafe fn set_stack_guard(canary: usize) {
     unsafe {
-        shellder::unistd::munmap(ptr, STATIC_TLS_SIZE)?;
+        core::arch::asm!(
+            "mov qword ptr fs:[{offset}], {canary}",
+            offset = const core::mem::offset_of!(ThreadControlBlock, stack_guard),
+            canary = in(reg) canary,
+        );
     }
-    Ok(())
 }
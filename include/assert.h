@@ -1,7 +1,7 @@
 #ifndef __CLOYSTER_INC_ASSERT_H
 #define __CLOYSTER_INC_ASSERT_H
 
-void __assert_fail(const char* assertion, const char* file, unsigned int line,
+[[noreturn]] void __assert_fail(const char* assertion, const char* file, unsigned int line,
                    const char* function);
 
 #define assert(expression)                                                                         \
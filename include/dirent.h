@@ -0,0 +1,43 @@
+#ifndef __CLOYSTER_INC_DIRENT_H
+#define __CLOYSTER_INC_DIRENT_H
+
+#include <sys/types.h>
+
+// We can leave the actual definition to Rust code
+typedef struct DIR DIR;
+
+// Types of directory entries
+#define DT_UNKNOWN 0
+#define DT_FIFO 1
+#define DT_CHR 2
+#define DT_DIR 4
+#define DT_BLK 6
+#define DT_REG 8
+#define DT_LNK 10
+#define DT_SOCK 12
+
+struct dirent {
+    ino_t d_ino;
+    off_t d_off;
+    unsigned short d_reclen;
+    unsigned char d_type;
+    char d_name[256];
+};
+
+DIR* opendir(const char* name);
+DIR* fdopendir(int fd);
+struct dirent* readdir(DIR* dirp);
+int readdir_r(DIR* restrict dirp, struct dirent* restrict entry, struct dirent** restrict result);
+void rewinddir(DIR* dirp);
+void seekdir(DIR* dirp, long loc);
+long telldir(DIR* dirp);
+int dirfd(DIR* dirp);
+int closedir(DIR* dirp);
+
+// List a directory
+int scandir(const char* restrict dirp, struct dirent*** restrict namelist,
+            int (*filter)(const struct dirent*),
+            int (*compar)(const struct dirent**, const struct dirent**));
+int alphasort(const struct dirent** a, const struct dirent** b);
+
+#endif
@@ -0,0 +1,33 @@
+#ifndef __CLOYSTER_INC_FTW_H
+#define __CLOYSTER_INC_FTW_H
+
+#include <sys/stat.h>
+
+// Types of files passed to the callback
+#define FTW_F 0
+#define FTW_D 1
+#define FTW_DNR 2
+#define FTW_NS 3
+#define FTW_SL 4
+#define FTW_DP 5
+#define FTW_SLN 6
+
+// Flags for nftw()
+#define FTW_PHYS 1
+#define FTW_MOUNT 2
+#define FTW_CHDIR 4
+#define FTW_DEPTH 8
+
+struct FTW {
+    int base;
+    int level;
+};
+
+// Walk a file tree
+int ftw(const char* dirpath, int (*fn)(const char* fpath, const struct stat* sb, int typeflag),
+        int nopenfd);
+int nftw(const char* dirpath,
+         int (*fn)(const char* fpath, const struct stat* sb, int typeflag, struct FTW* ftwbuf),
+         int nopenfd, int flags);
+
+#endif
@@ -0,0 +1,15 @@
+#ifndef __CLOYSTER_INC_INTTYPES_H
+#define __CLOYSTER_INC_INTTYPES_H
+
+#include <stdint.h>
+
+typedef struct {
+    intmax_t quot;
+    intmax_t rem;
+} imaxdiv_t;
+
+// Greatest-width integer arithmetic
+intmax_t imaxabs(intmax_t j);
+imaxdiv_t imaxdiv(intmax_t numer, intmax_t denom);
+
+#endif
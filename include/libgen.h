@@ -0,0 +1,9 @@
+#ifndef __CLOYSTER_INC_LIBGEN_H
+#define __CLOYSTER_INC_LIBGEN_H
+
+// Both may modify `path`
+char* dirname(char* path);
+char* __xpg_basename(char* path);
+#define basename __xpg_basename
+
+#endif
@@ -0,0 +1,33 @@
+#ifndef __CLOYSTER_INC_SETJMP_H
+#define __CLOYSTER_INC_SETJMP_H
+
+#include <__cloyster_compat.h>
+
+// Same layout as glibc
+typedef struct __jmp_buf_tag {
+#if defined(__x86_64__)
+    long __jmpbuf[8];
+#elif defined(__riscv) && __riscv_xlen == 64
+    long __jmpbuf[14];
+    double __fpregs[12];
+#else
+#error "Unsupported architecture"
+#endif
+    int __mask_was_saved;
+    unsigned long __saved_mask[16];
+} jmp_buf[1];
+
+typedef jmp_buf sigjmp_buf;
+
+// Save the calling environment. Like glibc, setjmp() doesn't save the signal mask
+int _setjmp(jmp_buf env);
+int __sigsetjmp(sigjmp_buf env, int savemask);
+#define setjmp(env) _setjmp(env)
+#define sigsetjmp(env, savemask) __sigsetjmp(env, savemask)
+
+// Jump back to a saved environment, restoring the signal mask if it was saved
+[[noreturn]] void longjmp(jmp_buf env, int val);
+[[noreturn]] void _longjmp(jmp_buf env, int val);
+[[noreturn]] void siglongjmp(sigjmp_buf env, int val);
+
+#endif
@@ -0,0 +1,150 @@
+#ifndef __CLOYSTER_INC_SIGNAL_H
+#define __CLOYSTER_INC_SIGNAL_H
+
+#include <__cloyster_compat.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <sys/types.h>
+
+typedef int sig_atomic_t;
+
+// Same layout as glibc, of which the kernel only uses the first 64 bits
+typedef struct {
+    unsigned long __val[16];
+} sigset_t;
+
+union sigval {
+    int sival_int;
+    void* sival_ptr;
+};
+
+typedef struct {
+    int si_signo;
+    int si_errno;
+    int si_code;
+    union {
+        int __pad[28];
+        struct {
+            pid_t si_pid;
+            unsigned int si_uid;
+            union {
+                int si_status;
+                union sigval si_value;
+            };
+        };
+        void* si_addr;
+    };
+} siginfo_t;
+
+typedef void (*sighandler_t)(int);
+
+struct sigaction {
+    union {
+        sighandler_t sa_handler;
+        void (*sa_sigaction)(int, siginfo_t*, void*);
+    };
+    sigset_t sa_mask;
+    int sa_flags;
+    void (*sa_restorer)(void);
+};
+
+// Alternate signal stack
+typedef struct {
+    void* ss_sp;
+    int ss_flags;
+    size_t ss_size;
+} stack_t;
+
+// Signal numbers
+#define SIGHUP 1
+#define SIGINT 2
+#define SIGQUIT 3
+#define SIGILL 4
+#define SIGTRAP 5
+#define SIGABRT 6
+#define SIGBUS 7
+#define SIGFPE 8
+#define SIGKILL 9
+#define SIGUSR1 10
+#define SIGSEGV 11
+#define SIGUSR2 12
+#define SIGPIPE 13
+#define SIGALRM 14
+#define SIGTERM 15
+#define SIGSTKFLT 16
+#define SIGCHLD 17
+#define SIGCONT 18
+#define SIGSTOP 19
+#define SIGTSTP 20
+#define SIGTTIN 21
+#define SIGTTOU 22
+#define SIGURG 23
+#define SIGXCPU 24
+#define SIGXFSZ 25
+#define SIGVTALRM 26
+#define SIGPROF 27
+#define SIGWINCH 28
+#define SIGIO 29
+#define SIGPWR 30
+#define SIGSYS 31
+#define SIGRTMIN (__libc_current_sigrtmin())
+#define SIGRTMAX (__libc_current_sigrtmax())
+#define NSIG 65
+
+// Special handlers
+#define SIG_DFL ((sighandler_t)0)
+#define SIG_IGN ((sighandler_t)1)
+#define SIG_ERR ((sighandler_t)-1)
+
+// Flags for `sa_flags`
+#define SA_NOCLDSTOP 0x00000001
+#define SA_NOCLDWAIT 0x00000002
+#define SA_SIGINFO 0x00000004
+#define SA_RESTORER 0x04000000
+#define SA_ONSTACK 0x08000000
+#define SA_RESTART 0x10000000
+#define SA_NODEFER 0x40000000
+#define SA_RESETHAND 0x80000000
+
+// Flags for `ss_flags`
+#define SS_ONSTACK 1
+#define SS_DISABLE 2
+#define MINSIGSTKSZ 2048
+#define SIGSTKSZ 8192
+
+// `how` argument to sigprocmask()
+#define SIG_BLOCK 0
+#define SIG_UNBLOCK 1
+#define SIG_SETMASK 2
+
+// Change signal actions
+int sigaction(int sig, const struct sigaction* restrict act, struct sigaction* restrict oldact);
+sighandler_t signal(int sig, sighandler_t handler);
+
+// Run handlers on an alternate stack
+int sigaltstack(const stack_t* restrict ss, stack_t* restrict old_ss);
+
+// Send signals
+int raise(int sig);
+int kill(pid_t pid, int sig);
+
+// Block and wait for signals
+int sigprocmask(int how, const sigset_t* restrict set, sigset_t* restrict oldset);
+int pthread_sigmask(int how, const sigset_t* restrict set, sigset_t* restrict oldset);
+int sigsuspend(const sigset_t* mask);
+int sigpending(sigset_t* set);
+
+// Manipulate signal sets
+int sigemptyset(sigset_t* set);
+int sigfillset(sigset_t* set);
+int sigaddset(sigset_t* set, int signum);
+int sigdelset(sigset_t* set, int signum);
+int sigismember(const sigset_t* set, int signum);
+#ifdef _GNU_SOURCE
+int sigisemptyset(const sigset_t* set);
+#endif
+
+int __libc_current_sigrtmin(void);
+int __libc_current_sigrtmax(void);
+
+#endif
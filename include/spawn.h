@@ -0,0 +1,63 @@
+#ifndef __CLOYSTER_INC_SPAWN_H
+#define __CLOYSTER_INC_SPAWN_H
+
+#include <signal.h>
+#include <sys/types.h>
+
+// Flags for posix_spawnattr_setflags()
+#define POSIX_SPAWN_SETPGROUP 0x02
+#define POSIX_SPAWN_SETSIGDEF 0x04
+#define POSIX_SPAWN_SETSIGMASK 0x08
+#define POSIX_SPAWN_USEVFORK 0x40
+#define POSIX_SPAWN_SETSID 0x80
+
+// Laid out like glibc's
+typedef struct {
+    short __flags;
+    pid_t __pgrp;
+    sigset_t __sd;
+    sigset_t __ss;
+    int __sched_priority;
+    int __policy;
+    int __pad[16];
+} posix_spawnattr_t;
+
+typedef struct {
+    int __allocated;
+    int __used;
+    void* __actions;
+    int __pad[16];
+} posix_spawn_file_actions_t;
+
+// Start a program without copying the caller
+int posix_spawn(pid_t* pid, const char* path, const posix_spawn_file_actions_t* file_actions,
+    const posix_spawnattr_t* attr, char* const argv[], char* const envp[]);
+int posix_spawnp(pid_t* pid, const char* file, const posix_spawn_file_actions_t* file_actions,
+    const posix_spawnattr_t* attr, char* const argv[], char* const envp[]);
+
+// Things for the child to do before it execs
+int posix_spawn_file_actions_init(posix_spawn_file_actions_t* file_actions);
+int posix_spawn_file_actions_destroy(posix_spawn_file_actions_t* file_actions);
+int posix_spawn_file_actions_addopen(posix_spawn_file_actions_t* file_actions, int fd,
+    const char* path, int oflag, mode_t mode);
+int posix_spawn_file_actions_addclose(posix_spawn_file_actions_t* file_actions, int fd);
+int posix_spawn_file_actions_adddup2(posix_spawn_file_actions_t* file_actions, int fd, int newfd);
+int posix_spawn_file_actions_addchdir(posix_spawn_file_actions_t* file_actions, const char* path);
+int posix_spawn_file_actions_addfchdir(posix_spawn_file_actions_t* file_actions, int fd);
+int posix_spawn_file_actions_addchdir_np(
+    posix_spawn_file_actions_t* file_actions, const char* path);
+int posix_spawn_file_actions_addfchdir_np(posix_spawn_file_actions_t* file_actions, int fd);
+
+// Attributes of the child
+int posix_spawnattr_init(posix_spawnattr_t* attr);
+int posix_spawnattr_destroy(posix_spawnattr_t* attr);
+int posix_spawnattr_getflags(const posix_spawnattr_t* attr, short* flags);
+int posix_spawnattr_setflags(posix_spawnattr_t* attr, short flags);
+int posix_spawnattr_getpgroup(const posix_spawnattr_t* attr, pid_t* pgroup);
+int posix_spawnattr_setpgroup(posix_spawnattr_t* attr, pid_t pgroup);
+int posix_spawnattr_getsigdefault(const posix_spawnattr_t* attr, sigset_t* sigdefault);
+int posix_spawnattr_setsigdefault(posix_spawnattr_t* attr, const sigset_t* sigdefault);
+int posix_spawnattr_getsigmask(const posix_spawnattr_t* attr, sigset_t* sigmask);
+int posix_spawnattr_setsigmask(posix_spawnattr_t* attr, const sigset_t* sigmask);
+
+#endif
@@ -0,0 +1,209 @@
+#ifndef __CLOYSTER_INC_STDBIT_H
+#define __CLOYSTER_INC_STDBIT_H
+
+#include <__cloyster_compat.h>
+
+#define __STDC_VERSION_STDBIT_H__ 202311L
+
+// Byte order
+#define __STDC_ENDIAN_LITTLE__ 1234
+#define __STDC_ENDIAN_BIG__ 4321
+#define __STDC_ENDIAN_NATIVE__ __STDC_ENDIAN_LITTLE__
+
+// Count consecutive 0 bits from the most significant bit
+unsigned int stdc_leading_zeros_uc(unsigned char value);
+unsigned int stdc_leading_zeros_us(unsigned short value);
+unsigned int stdc_leading_zeros_ui(unsigned int value);
+unsigned int stdc_leading_zeros_ul(unsigned long value);
+unsigned int stdc_leading_zeros_ull(unsigned long long value);
+#define stdc_leading_zeros(value) \
+    _Generic((value), \
+        unsigned char: stdc_leading_zeros_uc, \
+        unsigned short: stdc_leading_zeros_us, \
+        unsigned int: stdc_leading_zeros_ui, \
+        unsigned long: stdc_leading_zeros_ul, \
+        unsigned long long: stdc_leading_zeros_ull)((value))
+
+// Count consecutive 1 bits from the most significant bit
+unsigned int stdc_leading_ones_uc(unsigned char value);
+unsigned int stdc_leading_ones_us(unsigned short value);
+unsigned int stdc_leading_ones_ui(unsigned int value);
+unsigned int stdc_leading_ones_ul(unsigned long value);
+unsigned int stdc_leading_ones_ull(unsigned long long value);
+#define stdc_leading_ones(value) \
+    _Generic((value), \
+        unsigned char: stdc_leading_ones_uc, \
+        unsigned short: stdc_leading_ones_us, \
+        unsigned int: stdc_leading_ones_ui, \
+        unsigned long: stdc_leading_ones_ul, \
+        unsigned long long: stdc_leading_ones_ull)((value))
+
+// Count consecutive 0 bits from the least significant bit
+unsigned int stdc_trailing_zeros_uc(unsigned char value);
+unsigned int stdc_trailing_zeros_us(unsigned short value);
+unsigned int stdc_trailing_zeros_ui(unsigned int value);
+unsigned int stdc_trailing_zeros_ul(unsigned long value);
+unsigned int stdc_trailing_zeros_ull(unsigned long long value);
+#define stdc_trailing_zeros(value) \
+    _Generic((value), \
+        unsigned char: stdc_trailing_zeros_uc, \
+        unsigned short: stdc_trailing_zeros_us, \
+        unsigned int: stdc_trailing_zeros_ui, \
+        unsigned long: stdc_trailing_zeros_ul, \
+        unsigned long long: stdc_trailing_zeros_ull)((value))
+
+// Count consecutive 1 bits from the least significant bit
+unsigned int stdc_trailing_ones_uc(unsigned char value);
+unsigned int stdc_trailing_ones_us(unsigned short value);
+unsigned int stdc_trailing_ones_ui(unsigned int value);
+unsigned int stdc_trailing_ones_ul(unsigned long value);
+unsigned int stdc_trailing_ones_ull(unsigned long long value);
+#define stdc_trailing_ones(value) \
+    _Generic((value), \
+        unsigned char: stdc_trailing_ones_uc, \
+        unsigned short: stdc_trailing_ones_us, \
+        unsigned int: stdc_trailing_ones_ui, \
+        unsigned long: stdc_trailing_ones_ul, \
+        unsigned long long: stdc_trailing_ones_ull)((value))
+
+// One-based index of the most significant 0 bit, or 0 if there isn't one
+unsigned int stdc_first_leading_zero_uc(unsigned char value);
+unsigned int stdc_first_leading_zero_us(unsigned short value);
+unsigned int stdc_first_leading_zero_ui(unsigned int value);
+unsigned int stdc_first_leading_zero_ul(unsigned long value);
+unsigned int stdc_first_leading_zero_ull(unsigned long long value);
+#define stdc_first_leading_zero(value) \
+    _Generic((value), \
+        unsigned char: stdc_first_leading_zero_uc, \
+        unsigned short: stdc_first_leading_zero_us, \
+        unsigned int: stdc_first_leading_zero_ui, \
+        unsigned long: stdc_first_leading_zero_ul, \
+        unsigned long long: stdc_first_leading_zero_ull)((value))
+
+// One-based index of the most significant 1 bit, or 0 if there isn't one
+unsigned int stdc_first_leading_one_uc(unsigned char value);
+unsigned int stdc_first_leading_one_us(unsigned short value);
+unsigned int stdc_first_leading_one_ui(unsigned int value);
+unsigned int stdc_first_leading_one_ul(unsigned long value);
+unsigned int stdc_first_leading_one_ull(unsigned long long value);
+#define stdc_first_leading_one(value) \
+    _Generic((value), \
+        unsigned char: stdc_first_leading_one_uc, \
+        unsigned short: stdc_first_leading_one_us, \
+        unsigned int: stdc_first_leading_one_ui, \
+        unsigned long: stdc_first_leading_one_ul, \
+        unsigned long long: stdc_first_leading_one_ull)((value))
+
+// One-based index of the least significant 0 bit, or 0 if there isn't one
+unsigned int stdc_first_trailing_zero_uc(unsigned char value);
+unsigned int stdc_first_trailing_zero_us(unsigned short value);
+unsigned int stdc_first_trailing_zero_ui(unsigned int value);
+unsigned int stdc_first_trailing_zero_ul(unsigned long value);
+unsigned int stdc_first_trailing_zero_ull(unsigned long long value);
+#define stdc_first_trailing_zero(value) \
+    _Generic((value), \
+        unsigned char: stdc_first_trailing_zero_uc, \
+        unsigned short: stdc_first_trailing_zero_us, \
+        unsigned int: stdc_first_trailing_zero_ui, \
+        unsigned long: stdc_first_trailing_zero_ul, \
+        unsigned long long: stdc_first_trailing_zero_ull)((value))
+
+// One-based index of the least significant 1 bit, or 0 if there isn't one
+unsigned int stdc_first_trailing_one_uc(unsigned char value);
+unsigned int stdc_first_trailing_one_us(unsigned short value);
+unsigned int stdc_first_trailing_one_ui(unsigned int value);
+unsigned int stdc_first_trailing_one_ul(unsigned long value);
+unsigned int stdc_first_trailing_one_ull(unsigned long long value);
+#define stdc_first_trailing_one(value) \
+    _Generic((value), \
+        unsigned char: stdc_first_trailing_one_uc, \
+        unsigned short: stdc_first_trailing_one_us, \
+        unsigned int: stdc_first_trailing_one_ui, \
+        unsigned long: stdc_first_trailing_one_ul, \
+        unsigned long long: stdc_first_trailing_one_ull)((value))
+
+// Count 0 bits
+unsigned int stdc_count_zeros_uc(unsigned char value);
+unsigned int stdc_count_zeros_us(unsigned short value);
+unsigned int stdc_count_zeros_ui(unsigned int value);
+unsigned int stdc_count_zeros_ul(unsigned long value);
+unsigned int stdc_count_zeros_ull(unsigned long long value);
+#define stdc_count_zeros(value) \
+    _Generic((value), \
+        unsigned char: stdc_count_zeros_uc, \
+        unsigned short: stdc_count_zeros_us, \
+        unsigned int: stdc_count_zeros_ui, \
+        unsigned long: stdc_count_zeros_ul, \
+        unsigned long long: stdc_count_zeros_ull)((value))
+
+// Count 1 bits
+unsigned int stdc_count_ones_uc(unsigned char value);
+unsigned int stdc_count_ones_us(unsigned short value);
+unsigned int stdc_count_ones_ui(unsigned int value);
+unsigned int stdc_count_ones_ul(unsigned long value);
+unsigned int stdc_count_ones_ull(unsigned long long value);
+#define stdc_count_ones(value) \
+    _Generic((value), \
+        unsigned char: stdc_count_ones_uc, \
+        unsigned short: stdc_count_ones_us, \
+        unsigned int: stdc_count_ones_ui, \
+        unsigned long: stdc_count_ones_ul, \
+        unsigned long long: stdc_count_ones_ull)((value))
+
+// Check if the value is a power of two
+bool stdc_has_single_bit_uc(unsigned char value);
+bool stdc_has_single_bit_us(unsigned short value);
+bool stdc_has_single_bit_ui(unsigned int value);
+bool stdc_has_single_bit_ul(unsigned long value);
+bool stdc_has_single_bit_ull(unsigned long long value);
+#define stdc_has_single_bit(value) \
+    _Generic((value), \
+        unsigned char: stdc_has_single_bit_uc, \
+        unsigned short: stdc_has_single_bit_us, \
+        unsigned int: stdc_has_single_bit_ui, \
+        unsigned long: stdc_has_single_bit_ul, \
+        unsigned long long: stdc_has_single_bit_ull)((value))
+
+// Number of bits needed to represent the value
+unsigned int stdc_bit_width_uc(unsigned char value);
+unsigned int stdc_bit_width_us(unsigned short value);
+unsigned int stdc_bit_width_ui(unsigned int value);
+unsigned int stdc_bit_width_ul(unsigned long value);
+unsigned int stdc_bit_width_ull(unsigned long long value);
+#define stdc_bit_width(value) \
+    _Generic((value), \
+        unsigned char: stdc_bit_width_uc, \
+        unsigned short: stdc_bit_width_us, \
+        unsigned int: stdc_bit_width_ui, \
+        unsigned long: stdc_bit_width_ul, \
+        unsigned long long: stdc_bit_width_ull)((value))
+
+// Largest power of two not greater than the value
+unsigned char stdc_bit_floor_uc(unsigned char value);
+unsigned short stdc_bit_floor_us(unsigned short value);
+unsigned int stdc_bit_floor_ui(unsigned int value);
+unsigned long stdc_bit_floor_ul(unsigned long value);
+unsigned long long stdc_bit_floor_ull(unsigned long long value);
+#define stdc_bit_floor(value) \
+    _Generic((value), \
+        unsigned char: stdc_bit_floor_uc, \
+        unsigned short: stdc_bit_floor_us, \
+        unsigned int: stdc_bit_floor_ui, \
+        unsigned long: stdc_bit_floor_ul, \
+        unsigned long long: stdc_bit_floor_ull)((value))
+
+// Smallest power of two not less than the value, or 0 if it isn't representable
+unsigned char stdc_bit_ceil_uc(unsigned char value);
+unsigned short stdc_bit_ceil_us(unsigned short value);
+unsigned int stdc_bit_ceil_ui(unsigned int value);
+unsigned long stdc_bit_ceil_ul(unsigned long value);
+unsigned long long stdc_bit_ceil_ull(unsigned long long value);
+#define stdc_bit_ceil(value) \
+    _Generic((value), \
+        unsigned char: stdc_bit_ceil_uc, \
+        unsigned short: stdc_bit_ceil_us, \
+        unsigned int: stdc_bit_ceil_ui, \
+        unsigned long: stdc_bit_ceil_ul, \
+        unsigned long long: stdc_bit_ceil_ull)((value))
+
+#endif
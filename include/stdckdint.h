@@ -0,0 +1,12 @@
+#ifndef __CLOYSTER_INC_STDCKDINT_H
+#define __CLOYSTER_INC_STDCKDINT_H
+
+#define __STDC_VERSION_STDCKDINT_H__ 202311L
+
+// Checked integer arithmetic. Each stores the wrapped result in `*R`, and evaluates to true if it
+// overflowed
+#define ckd_add(R, A, B) __builtin_add_overflow((A), (B), (R))
+#define ckd_sub(R, A, B) __builtin_sub_overflow((A), (B), (R))
+#define ckd_mul(R, A, B) __builtin_mul_overflow((A), (B), (R))
+
+#endif
@@ -2,15 +2,36 @@
 #define __CLOYSTER_INC_STDIO_H
 #include <stddef.h>
 
+#define BUFSIZ 8192
+
 // We can leave the actual definition to Rust code
 typedef struct FILE FILE;
 
 FILE* fopen(const char* restrict pathname, const char* restrict mode);
+FILE* fdopen(int fd, const char* mode);
 size_t fread(void* ptr, size_t size, size_t nmemb, FILE* restrict stream);
 size_t fwrite(const void* ptr, size_t size, size_t nmemb, FILE* restrict stream);
+int fflush(FILE* stream);
 int fclose(FILE* stream);
 
+// Pipe to or from a shell command
+FILE* popen(const char* command, const char* mode);
+int pclose(FILE* stream);
+
+// Files
+int remove(const char* pathname);
+int rename(const char* oldpath, const char* newpath);
+int renameat(int olddirfd, const char* oldpath, int newdirfd, const char* newpath);
+#ifdef _GNU_SOURCE
+#define RENAME_NOREPLACE 1
+#define RENAME_EXCHANGE 2
+#define RENAME_WHITEOUT 4
+int renameat2(int olddirfd, const char* oldpath, int newdirfd, const char* newpath,
+              unsigned int flags);
+#endif
+
 int printf(const char* restrict format, ...);
 int puts(const char* s);
+void perror(const char* s);
 
 #endif
@@ -1,10 +1,93 @@
 #ifndef __CLOYSTER_INC_STDLIB_H
 #define __CLOYSTER_INC_STDLIB_H
 
+#include <stddef.h>
+#include <stdint.h>
+
 // Normal process termination
 [[noreturn]] void exit(int status);
+int atexit(void (*function)(void));
+
+// Process termination without running `atexit()` handlers
+[[noreturn]] void quick_exit(int status);
+int at_quick_exit(void (*function)(void));
+[[noreturn]] void _Exit(int status);
 
 // Abnormal process termination
 [[noreturn]] void abort(void);
 
+// Integer arithmetic
+typedef struct {
+    int quot;
+    int rem;
+} div_t;
+typedef struct {
+    long quot;
+    long rem;
+} ldiv_t;
+typedef struct {
+    long long quot;
+    long long rem;
+} lldiv_t;
+int abs(int j);
+long labs(long j);
+long long llabs(long long j);
+div_t div(int numer, int denom);
+ldiv_t ldiv(long numer, long denom);
+lldiv_t lldiv(long long numer, long long denom);
+
+// Convert strings to numbers
+int atoi(const char* nptr);
+long atol(const char* nptr);
+long long atoll(const char* nptr);
+double atof(const char* nptr);
+long strtol(const char* restrict nptr, char** restrict endptr, int base);
+long long strtoll(const char* restrict nptr, char** restrict endptr, int base);
+unsigned long strtoul(const char* restrict nptr, char** restrict endptr, int base);
+unsigned long long strtoull(const char* restrict nptr, char** restrict endptr, int base);
+double strtod(const char* restrict nptr, char** restrict endptr);
+float strtof(const char* restrict nptr, char** restrict endptr);
+
+// Sort and search arrays
+void qsort(void* base, size_t nmemb, size_t size, int (*compar)(const void*, const void*));
+#ifdef _GNU_SOURCE
+void qsort_r(void* base, size_t nmemb, size_t size,
+             int (*compar)(const void*, const void*, void*), void* arg);
+#endif
+void* bsearch(const void* key, const void* base, size_t nmemb, size_t size,
+              int (*compar)(const void*, const void*));
+
+// Pseudo-random numbers
+#define RAND_MAX 2147483647
+int rand(void);
+void srand(unsigned int seed);
+int rand_r(unsigned int* seedp);
+long random(void);
+void srandom(unsigned int seed);
+char* initstate(unsigned int seed, char* state, size_t n);
+char* setstate(char* state);
+double drand48(void);
+double erand48(unsigned short xsubi[3]);
+long lrand48(void);
+long nrand48(unsigned short xsubi[3]);
+long mrand48(void);
+long jrand48(unsigned short xsubi[3]);
+void srand48(long seedval);
+unsigned short* seed48(unsigned short seed16v[3]);
+void lcong48(unsigned short param[7]);
+
+// Cryptographically secure random numbers
+uint32_t arc4random(void);
+void arc4random_buf(void* buf, size_t nbytes);
+uint32_t arc4random_uniform(uint32_t upper_bound);
+
+// Resolve a path to an absolute one without symbolic links
+char* realpath(const char* restrict path, char* restrict resolved_path);
+
+// Get an environment variable
+char* getenv(const char* name);
+
+// Run a shell command
+int system(const char* command);
+
 #endif
@@ -5,4 +5,21 @@
 
 int memcmp(const void* s1, const void* s2, size_t n);
 
+char* strerror(int errnum);
+#ifdef _GNU_SOURCE
+char* strerror_r(int errnum, char* buf, size_t buflen);
+const char* strerrorname_np(int errnum);
+#else
+int __xpg_strerror_r(int errnum, char* buf, size_t buflen);
+#define strerror_r __xpg_strerror_r
+#endif
+
+// Describe a signal
+char* strsignal(int sig);
+
+#if defined(_GNU_SOURCE) && !defined(basename)
+// Everything after the last slash. <libgen.h> has the POSIX version
+char* basename(const char* path);
+#endif
+
 #endif
@@ -0,0 +1,12 @@
+#ifndef __CLOYSTER_INC_SYS_FILE_H
+#define __CLOYSTER_INC_SYS_FILE_H
+
+// Operations for flock()
+#define LOCK_SH 1
+#define LOCK_EX 2
+#define LOCK_NB 4
+#define LOCK_UN 8
+
+int flock(int fd, int operation);
+
+#endif
@@ -0,0 +1,16 @@
+#ifndef __CLOYSTER_INC_SYS_RANDOM_H
+#define __CLOYSTER_INC_SYS_RANDOM_H
+
+#include <stddef.h>
+#include <sys/types.h>
+
+// Flags for getrandom()
+#define GRND_NONBLOCK 0x01
+#define GRND_RANDOM 0x02
+#define GRND_INSECURE 0x04
+
+// Get random bytes from the kernel
+ssize_t getrandom(void* buf, size_t buflen, unsigned int flags);
+int getentropy(void* buffer, size_t length);
+
+#endif
@@ -0,0 +1,23 @@
+#ifndef __CLOYSTER_INC_SYS_TYPES_H
+#define __CLOYSTER_INC_SYS_TYPES_H
+
+#include <stddef.h>
+
+typedef long ssize_t;
+typedef int pid_t;
+typedef unsigned int mode_t;
+typedef long off_t;
+typedef unsigned long dev_t;
+typedef unsigned long ino_t;
+typedef unsigned int uid_t;
+typedef unsigned int gid_t;
+typedef long blkcnt_t;
+#if defined(__x86_64__)
+typedef unsigned long nlink_t;
+typedef long blksize_t;
+#else
+typedef unsigned int nlink_t;
+typedef int blksize_t;
+#endif
+
+#endif
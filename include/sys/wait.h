@@ -0,0 +1,28 @@
+#ifndef __CLOYSTER_INC_SYS_WAIT_H
+#define __CLOYSTER_INC_SYS_WAIT_H
+
+#include <sys/types.h>
+
+// Options for waitpid() and wait4()
+#define WNOHANG 0x01
+#define WUNTRACED 0x02
+#define WCONTINUED 0x08
+
+// Decode the status reported by wait() and friends
+#define WEXITSTATUS(status) (((status) & 0xff00) >> 8)
+#define WTERMSIG(status) ((status) & 0x7f)
+#define WSTOPSIG(status) WEXITSTATUS(status)
+#define WIFEXITED(status) (WTERMSIG(status) == 0)
+#define WIFSIGNALED(status) (((signed char)(((status) & 0x7f) + 1) >> 1) > 0)
+#define WIFSTOPPED(status) (((status) & 0xff) == 0x7f)
+#define WIFCONTINUED(status) ((status) == 0xffff)
+#define WCOREDUMP(status) ((status) & 0x80)
+
+struct rusage;
+
+// Wait for a child process to change state
+pid_t wait(int* status);
+pid_t waitpid(pid_t pid, int* status, int options);
+pid_t wait4(pid_t pid, int* status, int options, struct rusage* rusage);
+
+#endif
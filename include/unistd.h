@@ -0,0 +1,72 @@
+#ifndef __CLOYSTER_INC_UNISTD_H
+#define __CLOYSTER_INC_UNISTD_H
+
+#include <stddef.h>
+#include <sys/types.h>
+
+// Process creation
+pid_t fork(void);
+pid_t vfork(void);
+int pthread_atfork(void (*prepare)(void), void (*parent)(void), void (*child)(void));
+
+// Program execution
+int execve(const char* pathname, char* const argv[], char* const envp[]);
+int execv(const char* pathname, char* const argv[]);
+int execvp(const char* file, char* const argv[]);
+int execvpe(const char* file, char* const argv[], char* const envp[]);
+int execl(const char* pathname, const char* arg, ...);
+int execlp(const char* file, const char* arg, ...);
+int execle(const char* pathname, const char* arg, ...);
+
+// Positions for lseek()
+#define SEEK_SET 0
+#define SEEK_CUR 1
+#define SEEK_END 2
+
+// Commands for lockf()
+#define F_ULOCK 0
+#define F_LOCK 1
+#define F_TLOCK 2
+#define F_TEST 3
+
+// File descriptors
+int close(int fd);
+ssize_t read(int fd, void* buf, size_t count);
+ssize_t write(int fd, const void* buf, size_t count);
+off_t lseek(int fd, off_t offset, int whence);
+ssize_t pread(int fd, void* buf, size_t count, off_t offset);
+ssize_t pwrite(int fd, const void* buf, size_t count, off_t offset);
+int pipe(int pipefd[2]);
+#ifdef _GNU_SOURCE
+int pipe2(int pipefd[2], int flags);
+int dup3(int oldfd, int newfd, int flags);
+#endif
+int dup(int oldfd);
+int dup2(int oldfd, int newfd);
+int lockf(int fd, int cmd, off_t len);
+
+// Changing the filesystem
+int unlink(const char* pathname);
+int unlinkat(int dirfd, const char* pathname, int flags);
+int rmdir(const char* pathname);
+int link(const char* oldpath, const char* newpath);
+int linkat(int olddirfd, const char* oldpath, int newdirfd, const char* newpath, int flags);
+int symlink(const char* target, const char* linkpath);
+int symlinkat(const char* target, int newdirfd, const char* linkpath);
+ssize_t readlink(const char* restrict pathname, char* restrict buf, size_t bufsiz);
+ssize_t readlinkat(int dirfd, const char* restrict pathname, char* restrict buf, size_t bufsiz);
+int chown(const char* pathname, uid_t owner, gid_t group);
+int fchown(int fd, uid_t owner, gid_t group);
+int fchownat(int dirfd, const char* pathname, uid_t owner, gid_t group, int flags);
+int truncate(const char* pathname, off_t length);
+int ftruncate(int fd, off_t length);
+
+// Working directory
+char* getcwd(char* buf, size_t size);
+int chdir(const char* path);
+int fchdir(int fd);
+
+pid_t getpid(void);
+[[noreturn]] void _exit(int status);
+
+#endif
@@ -0,0 +1,265 @@
+//! Summarise an allocation trace written by Cloyster's `malloc_trace` feature
+//!
+//! Usage: `mtrace [TRACE_FILE]`, reading from stdin if no file is given
+use std::{
+    collections::HashMap,
+    env,
+    fmt::{self, Display},
+    fs::File,
+    io::{self, BufRead, BufReader},
+    process::ExitCode,
+};
+
+/// Number of call sites to list
+const TOP_CALL_SITES: usize = 10;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Op {
+    Malloc,
+    Calloc,
+    Realloc,
+    AlignedAlloc,
+    Free,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Event {
+    op: Op,
+    ptr: usize,
+    size: usize,
+    old_ptr: usize,
+    caller: usize,
+}
+
+impl Event {
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        let op = match fields.next()? {
+            "m" => Op::Malloc,
+            "c" => Op::Calloc,
+            "r" => Op::Realloc,
+            "a" => Op::AlignedAlloc,
+            "f" => Op::Free,
+            _ => return None,
+        };
+        let hex = |field: Option<&str>| usize::from_str_radix(field?, 16).ok();
+        let ptr = hex(fields.next())?;
+        let size = fields.next()?.parse().ok()?;
+        let old_ptr = hex(fields.next())?;
+        let caller = hex(fields.next())?;
+        if fields.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            op,
+            ptr,
+            size,
+            old_ptr,
+            caller,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+struct CallSite {
+    allocations: usize,
+    bytes: usize,
+}
+
+#[derive(Debug, Default)]
+struct Summary {
+    // Live allocations, mapped to their size and call site
+    live: HashMap<usize, (usize, usize)>,
+    current: usize,
+    peak: usize,
+    allocations: usize,
+    frees: usize,
+    failures: usize,
+    call_sites: HashMap<usize, CallSite>,
+}
+
+impl Summary {
+    fn release(&mut self, ptr: usize) {
+        if let Some((size, _)) = self.live.remove(&ptr) {
+            self.current -= size;
+        }
+    }
+
+    fn add(&mut self, event: Event) {
+        if event.op == Op::Free {
+            if event.ptr != 0 {
+                self.frees += 1;
+                self.release(event.ptr);
+            }
+            return;
+        }
+
+        if event.ptr == 0 {
+            // A failed realloc() leaves the old region alone
+            self.failures += 1;
+            return;
+        }
+
+        if event.op == Op::Realloc && event.old_ptr != 0 {
+            self.release(event.old_ptr);
+        } else {
+            self.allocations += 1;
+        }
+
+        self.live.insert(event.ptr, (event.size, event.caller));
+        self.current += event.size;
+        self.peak = self.peak.max(self.current);
+
+        let site = self.call_sites.entry(event.caller).or_default();
+        site.allocations += 1;
+        site.bytes += event.size;
+    }
+
+    // Call sites sorted by bytes allocated, largest first
+    fn top_call_sites(&self) -> Vec<(usize, CallSite)> {
+        let mut sites: Vec<_> = self.call_sites.iter().map(|(k, v)| (*k, *v)).collect();
+        sites.sort_by(|a, b| b.1.bytes.cmp(&a.1.bytes).then(a.0.cmp(&b.0)));
+        sites.truncate(TOP_CALL_SITES);
+        sites
+    }
+
+    // Leaked allocations grouped by call site, largest first
+    fn leaks(&self) -> Vec<(usize, CallSite)> {
+        let mut sites: HashMap<usize, CallSite> = HashMap::new();
+        for (size, caller) in self.live.values() {
+            let site = sites.entry(*caller).or_default();
+            site.allocations += 1;
+            site.bytes += size;
+        }
+        let mut sites: Vec<_> = sites.into_iter().collect();
+        sites.sort_by(|a, b| b.1.bytes.cmp(&a.1.bytes).then(a.0.cmp(&b.0)));
+        sites
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Peak usage: {} bytes", self.peak)?;
+        writeln!(
+            f,
+            "Allocations: {}, frees: {}, failures: {}",
+            self.allocations, self.frees, self.failures
+        )?;
+
+        writeln!(f, "\nTop call sites:")?;
+        for (caller, site) in self.top_call_sites() {
+            writeln!(
+                f,
+                "  {caller:#x}: {} bytes in {} allocations",
+                site.bytes, site.allocations
+            )?;
+        }
+
+        let leaks = self.leaks();
+        if leaks.is_empty() {
+            writeln!(f, "\nNo leaks")?;
+        } else {
+            writeln!(f, "\nLeaks ({} bytes):", self.current)?;
+            for (caller, site) in leaks {
+                writeln!(
+                    f,
+                    "  {caller:#x}: {} bytes in {} allocations",
+                    site.bytes, site.allocations
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn summarise(input: impl BufRead) -> io::Result<Summary> {
+    let mut summary = Summary::default();
+    for (number, line) in input.lines().enumerate() {
+        let line = line?;
+        let event = Event::parse(&line).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {}: malformed event `{line}`", number + 1),
+            )
+        })?;
+        summary.add(event);
+    }
+    Ok(summary)
+}
+
+fn main() -> ExitCode {
+    let summary = match env::args().nth(1) {
+        Some(path) => File::open(&path).and_then(|file| summarise(BufReader::new(file))),
+        None => summarise(io::stdin().lock()),
+    };
+
+    match summary {
+        Ok(summary) => {
+            print!("{summary}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("mtrace: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACE: &str = "\
+m 1000 100 0 401000
+r 2000 200 1000 401010
+m 3000 40 0 401020
+c 4000 24 0 401030
+f 4000 0 0 401040
+f 2000 0 0 401050
+f 0 0 0 401060
+m 0 100000 0 401070
+";
+
+    #[test]
+    fn parse_event() {
+        assert_eq!(
+            Event::parse("r 2000 200 1000 401010"),
+            Some(Event {
+                op: Op::Realloc,
+                ptr: 0x2000,
+                size: 200,
+                old_ptr: 0x1000,
+                caller: 0x401010,
+            })
+        );
+        assert_eq!(Event::parse("x 2000 200 1000 401010"), None);
+        assert_eq!(Event::parse("m 2000 200 1000"), None);
+        assert_eq!(Event::parse("m 2000 200 1000 401010 5"), None);
+        assert_eq!(Event::parse("m 2000 0x200 1000 401010"), None);
+    }
+
+    #[test]
+    fn summary() {
+        let summary = summarise(TRACE.as_bytes()).unwrap();
+        assert_eq!(summary.peak, 264);
+        assert_eq!(summary.allocations, 3);
+        assert_eq!(summary.frees, 2);
+        assert_eq!(summary.failures, 1);
+        assert_eq!(
+            summary.leaks(),
+            vec![(
+                0x401020,
+                CallSite {
+                    allocations: 1,
+                    bytes: 40
+                }
+            )]
+        );
+        assert_eq!(summary.top_call_sites()[0].0, 0x401010);
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(summarise("m 1000 1 0 1\nnonsense\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! Directory streams, read in batches with `getdents64`
+use crate::{
+    errno::Errno,
+    malloc::{calloc, free},
+    types::*,
+    unistd,
+};
+use core::{
+    ffi::{CStr, c_int},
+    mem,
+    ptr::{self, NonNull},
+};
+
+// How many bytes of entries to read at once
+const BUF_SIZE: usize = 8192;
+
+// The kernel aligns entries for `Dirent`
+#[repr(C, align(8))]
+struct Buffer([u8; BUF_SIZE]);
+
+/// Directory stream
+/// Corresponds to the C `DIR` type
+pub struct Dir {
+    fd: c_int,
+    // Entries read from the kernel but not returned yet are `buf[pos..len]`
+    pos: usize,
+    len: usize,
+    // Position of the entry after the last one returned, for `telldir()`
+    filepos: off_t,
+    buf: Buffer,
+    // The last entry returned. Entries are copied here because the kernel's may be shorter
+    // than a `Dirent`
+    entry: Dirent,
+}
+
+/// Open the directory at `path`
+pub fn opendir(path: &CStr) -> Result<NonNull<Dir>, Errno> {
+    let flags = OpenFlags::O_RDONLY | OpenFlags::O_DIRECTORY | OpenFlags::O_CLOEXEC;
+    let fd = unsafe { unistd::open(path, flags, ModeFlags::empty())? };
+    fdopendir(fd).inspect_err(|_| {
+        let _ = unistd::close(fd);
+    })
+}
+
+/// Open a stream on the directory open as `fd`. The stream owns `fd` once this succeeds, and
+/// sets [FD_CLOEXEC] on it
+pub fn fdopendir(fd: c_int) -> Result<NonNull<Dir>, Errno> {
+    if unistd::fstat(fd)?.mode & S_IFMT != S_IFDIR {
+        return Err(Errno::ENOTDIR);
+    }
+    let fd_flags = unistd::fcntl(fd, F_GETFD, 0)?;
+    unistd::fcntl(fd, F_SETFD, (fd_flags | FD_CLOEXEC) as usize)?;
+
+    // Zeroed is an empty stream, other than the descriptor
+    let dir = calloc(1, mem::size_of::<Dir>())?.cast::<Dir>();
+    unsafe { (*dir.as_ptr()).fd = fd };
+    Ok(dir)
+}
+
+/// Read the next entry of a directory stream
+///
+/// # Returns
+///
+/// The entry, which is overwritten by the next call, or `None` at the end of the directory
+///
+/// # Safety
+///
+/// `dir` must have been opened with [opendir] or [fdopendir], and not closed
+pub unsafe fn readdir(dir: NonNull<Dir>) -> Result<Option<NonNull<Dirent>>, Errno> {
+    let dir = unsafe { &mut *dir.as_ptr() };
+    if dir.pos >= dir.len {
+        let len = unistd::getdents64(dir.fd, &mut dir.buf.0)?;
+        if len == 0 {
+            return Ok(None);
+        }
+        dir.pos = 0;
+        dir.len = len;
+    }
+
+    let record = &dir.buf.0[dir.pos..dir.len];
+    let reclen = usize::from(u16::from_ne_bytes([record[16], record[17]]));
+    let copied = reclen.min(mem::size_of::<Dirent>()).min(record.len());
+    unsafe {
+        ptr::copy_nonoverlapping(
+            record.as_ptr(),
+            ptr::addr_of_mut!(dir.entry).cast::<u8>(),
+            copied,
+        );
+    }
+    dir.pos += reclen;
+    dir.filepos = dir.entry.off;
+    Ok(Some(NonNull::from(&mut dir.entry)))
+}
+
+/// Get the position of a directory stream, to go back to with [seekdir]
+///
+/// # Safety
+///
+/// See [readdir]
+pub unsafe fn telldir(dir: NonNull<Dir>) -> off_t {
+    unsafe { (*dir.as_ptr()).filepos }
+}
+
+/// Go back to a position of a directory stream returned by [telldir]
+///
+/// # Safety
+///
+/// See [readdir]
+pub unsafe fn seekdir(dir: NonNull<Dir>, loc: off_t) -> Result<(), Errno> {
+    let dir = unsafe { &mut *dir.as_ptr() };
+    unistd::lseek(dir.fd, loc, SEEK_SET)?;
+    dir.pos = 0;
+    dir.len = 0;
+    dir.filepos = loc;
+    Ok(())
+}
+
+/// Go back to the start of a directory stream
+///
+/// # Safety
+///
+/// See [readdir]
+pub unsafe fn rewinddir(dir: NonNull<Dir>) -> Result<(), Errno> {
+    unsafe { seekdir(dir, 0) }
+}
+
+/// Get the file descriptor a directory stream reads from
+///
+/// # Safety
+///
+/// See [readdir]
+pub unsafe fn dirfd(dir: NonNull<Dir>) -> c_int {
+    unsafe { (*dir.as_ptr()).fd }
+}
+
+/// Close a directory stream and its file descriptor
+///
+/// # Safety
+///
+/// See [readdir]. `dir` can't be used afterwards
+pub unsafe fn closedir(dir: NonNull<Dir>) -> Result<(), Errno> {
+    let fd = unsafe { (*dir.as_ptr()).fd };
+    unsafe { free(dir.cast())? };
+    unistd::close(fd)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{ffi::CString, vec::Vec};
+
+    fn names(dir: NonNull<Dir>) -> Vec<CString> {
+        let mut names = Vec::new();
+        while let Some(entry) = unsafe { readdir(dir) }.unwrap() {
+            names.push(unsafe { entry.as_ref() }.name().to_owned());
+        }
+        names
+    }
+
+    #[test]
+    fn reads_entries() {
+        let dir = opendir(c"src").unwrap();
+        let mut found = names(dir);
+        found.sort();
+        let mut expected = std::fs::read_dir("src")
+            .unwrap()
+            .map(|entry| CString::new(entry.unwrap().file_name().into_encoded_bytes()).unwrap())
+            .chain([c".".to_owned(), c"..".to_owned()])
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(found, expected);
+
+        // Reading again from the start gives the same entries
+        unsafe { rewinddir(dir).unwrap() };
+        let mut again = names(dir);
+        again.sort();
+        assert_eq!(again, expected);
+        unsafe { closedir(dir).unwrap() };
+    }
+
+    #[test]
+    fn seeks() {
+        let dir = opendir(c"src").unwrap();
+        unsafe { readdir(dir).unwrap() };
+        let position = unsafe { telldir(dir) };
+        let rest = names(dir);
+        assert!(!rest.is_empty());
+        unsafe { seekdir(dir, position).unwrap() };
+        assert_eq!(names(dir), rest);
+        unsafe { closedir(dir).unwrap() };
+    }
+
+    #[test]
+    fn rejects_files() {
+        assert_eq!(opendir(c"Cargo.toml").err(), Some(Errno::ENOTDIR));
+        assert_eq!(opendir(c"no_such_directory").err(), Some(Errno::ENOENT));
+    }
+}
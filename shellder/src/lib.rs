@@ -1,9 +1,12 @@
 #![cfg_attr(not(test), no_std)]
 #![feature(c_variadic)]
 
+pub mod dirent;
 mod errno;
+pub mod libgen;
 pub mod malloc;
 pub mod math;
+pub mod stdbit;
 pub mod stdio;
 pub mod stdlib;
 pub mod string;
@@ -0,0 +1,137 @@
+//! Splitting paths into a directory and a final component
+use core::ffi::CStr;
+
+// Cut off `path`, which is null-terminated, at `end`
+fn terminate(path: &mut [u8], end: usize) -> &CStr {
+    path[end] = 0;
+    CStr::from_bytes_until_nul(path).expect("Path is null-terminated")
+}
+
+/// Get the directory part of `path`, which is null-terminated, without trailing slashes. `path`
+/// is cut off to make the result, unless it has no directory part and the result is "."
+///
+/// # C Signature
+///
+/// `char *dirname(char *path);`
+pub fn dirname(path: &mut [u8]) -> &CStr {
+    let len = path
+        .iter()
+        .position(|c| *c == 0)
+        .expect("Path is null-terminated");
+    if len == 0 {
+        return c".";
+    }
+    let end = len - path[..len].iter().rev().take_while(|c| **c == b'/').count();
+    let Some(slash) = path[..end].iter().rposition(|c| *c == b'/') else {
+        // All slashes is the root. Otherwise, there's no directory part
+        return if end == 0 {
+            terminate(path, root_len(path))
+        } else {
+            c"."
+        };
+    };
+    let dir_end = slash
+        - path[..slash]
+            .iter()
+            .rev()
+            .take_while(|c| **c == b'/')
+            .count();
+    if dir_end == 0 {
+        terminate(path, root_len(path))
+    } else {
+        terminate(path, dir_end)
+    }
+}
+
+// Exactly two leading slashes may mean something other than the root, so they're kept
+fn root_len(path: &[u8]) -> usize {
+    if path.starts_with(b"//") && path[2] != b'/' {
+        2
+    } else {
+        1
+    }
+}
+
+/// Get the final component of `path`, which is null-terminated, without trailing slashes.
+/// `path` is cut off to make the result, unless it's empty and the result is "."
+///
+/// # C Signature
+///
+/// `char *basename(char *path);` from `<libgen.h>`
+pub fn basename(path: &mut [u8]) -> &CStr {
+    let len = path
+        .iter()
+        .position(|c| *c == 0)
+        .expect("Path is null-terminated");
+    if len == 0 {
+        return c".";
+    }
+    let end = len - path[..len].iter().rev().take_while(|c| **c == b'/').count();
+    if end == 0 {
+        return terminate(path, 1);
+    }
+    let start = path[..end]
+        .iter()
+        .rposition(|c| *c == b'/')
+        .map_or(0, |slash| slash + 1);
+    let path = &mut path[start..];
+    terminate(path, end - start)
+}
+
+/// Get what comes after the last slash of `path`, which is empty if `path` ends in a slash
+///
+/// # C Signature
+///
+/// `char *basename(const char *path);` from `<string.h>`, with `_GNU_SOURCE`
+pub fn basename_gnu(path: &CStr) -> &CStr {
+    let bytes = path.to_bytes_with_nul();
+    let start = bytes
+        .iter()
+        .rposition(|c| *c == b'/')
+        .map_or(0, |slash| slash + 1);
+    CStr::from_bytes_with_nul(&bytes[start..]).expect("Path is null-terminated")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    fn split(path: &CStr) -> (Vec<u8>, Vec<u8>) {
+        let mut dir = path.to_bytes_with_nul().to_vec();
+        let mut base = dir.clone();
+        (
+            dirname(&mut dir).to_bytes().to_vec(),
+            basename(&mut base).to_bytes().to_vec(),
+        )
+    }
+
+    #[test]
+    fn splits_paths() {
+        assert_eq!(split(c"/usr/lib"), (b"/usr".to_vec(), b"lib".to_vec()));
+        assert_eq!(split(c"/usr/lib/"), (b"/usr".to_vec(), b"lib".to_vec()));
+        assert_eq!(split(c"/usr"), (b"/".to_vec(), b"usr".to_vec()));
+        assert_eq!(split(c"usr"), (b".".to_vec(), b"usr".to_vec()));
+        assert_eq!(split(c"a//b"), (b"a".to_vec(), b"b".to_vec()));
+        assert_eq!(split(c"//usr//lib//"), (b"//usr".to_vec(), b"lib".to_vec()));
+        assert_eq!(split(c".."), (b".".to_vec(), b"..".to_vec()));
+        assert_eq!(split(c""), (b".".to_vec(), b".".to_vec()));
+    }
+
+    #[test]
+    fn splits_roots() {
+        assert_eq!(split(c"/"), (b"/".to_vec(), b"/".to_vec()));
+        assert_eq!(split(c"//"), (b"//".to_vec(), b"/".to_vec()));
+        assert_eq!(split(c"///"), (b"/".to_vec(), b"/".to_vec()));
+        assert_eq!(split(c"//a"), (b"//".to_vec(), b"a".to_vec()));
+        assert_eq!(split(c"///a"), (b"/".to_vec(), b"a".to_vec()));
+    }
+
+    #[test]
+    fn gnu_basename() {
+        assert_eq!(basename_gnu(c"/usr/lib"), c"lib");
+        assert_eq!(basename_gnu(c"/usr/"), c"");
+        assert_eq!(basename_gnu(c"usr"), c"usr");
+        assert_eq!(basename_gnu(c""), c"");
+    }
+}
@@ -0,0 +1,183 @@
+//! A bump (arena) allocator
+//!
+//! Allocations are carved sequentially out of memory claimed from the [MemoryExtender], and
+//! individual regions are never reused. Freeing the most recent allocation rolls the arena back,
+//! and the arena is rewound entirely once every allocation has been freed. This is a good fit for
+//! short-lived tools that allocate a lot and exit.
+use super::{Backend, extender::MemoryExtender, usize_ext::UsizeExt};
+use crate::errno::Errno;
+use core::{alloc::Layout, cmp, mem, ptr::NonNull};
+
+const MIN_ALIGN: usize = 32;
+const HDR_SIZE: usize = MIN_ALIGN;
+const PAGE_SIZE: usize = 4096;
+
+#[cfg(feature = "bump_alloc")]
+pub(crate) type Allocator = BumpAllocator<super::extender::DefaultMemoryExtender>;
+
+// Stored directly before each allocation
+#[repr(C)]
+#[derive(Debug, Clone)]
+struct Header {
+    live: bool,
+    size: usize,
+    // Top of the arena before this allocation was made
+    prev_top: usize,
+}
+
+pub(crate) struct BumpAllocator<T> {
+    // Start of the region currently being bumped through
+    start: usize,
+    top: usize,
+    end: usize,
+    allocations: usize,
+    memory_extender: T,
+}
+
+#[cfg(feature = "bump_alloc")]
+impl Allocator {
+    pub(crate) fn new() -> Result<Self, Errno> {
+        Self::from_memory_extender(super::extender::DefaultMemoryExtender)
+    }
+}
+
+impl<T: MemoryExtender> BumpAllocator<T> {
+    pub(crate) fn from_memory_extender(memory_extender: T) -> Result<Self, Errno> {
+        assert!(HDR_SIZE >= mem::size_of::<Header>());
+        // Memory is claimed lazily on the first allocation
+        Ok(Self {
+            start: 0,
+            top: 0,
+            end: 0,
+            allocations: 0,
+            memory_extender,
+        })
+    }
+
+    fn claim_more(&mut self, required: usize) -> Result<(), Errno> {
+        let required = required.align_up(PAGE_SIZE);
+        let region = unsafe { self.memory_extender.sbrk(required)? }.as_ptr() as usize;
+
+        if region == self.end {
+            self.end += required;
+        } else {
+            // Not contiguous with the current region, so abandon what's left of it
+            self.start = region;
+            self.top = region;
+            self.end = region + required;
+        }
+        Ok(())
+    }
+
+    // Safety: `ptr` must have been returned by this allocator
+    unsafe fn header<'a>(ptr: NonNull<u8>) -> Result<&'a mut Header, Errno> {
+        unsafe { (ptr.as_ptr().wrapping_sub(HDR_SIZE) as *mut Header).as_mut() }
+            .ok_or(Errno::CloysterAlloc)
+    }
+}
+
+impl<T: MemoryExtender + Send> Backend for BumpAllocator<T> {
+    fn allocations(&self) -> usize {
+        self.allocations
+    }
+
+    /// Return the size of a memory allocation
+    ///
+    /// # Safety
+    /// Ptr must be a valid, previosly allocated region of memory
+    unsafe fn size_of(&mut self, ptr: NonNull<u8>) -> Result<usize, Errno> {
+        let header = unsafe { Self::header(ptr)? };
+        assert!(header.live);
+        Ok(header.size)
+    }
+
+    /// # Safety
+    /// Ptr must be a valid, previosly allocated region of memory
+    unsafe fn free(&mut self, ptr: NonNull<u8>) -> Result<(), Errno> {
+        let header = unsafe { Self::header(ptr)? };
+        assert!(header.live);
+        header.live = false;
+
+        self.allocations = self
+            .allocations
+            .checked_sub(1)
+            .expect("Freed more than allocated! This is possibly a bug in Cloyster, or you free()'d one too many times");
+
+        if self.allocations == 0 {
+            self.top = self.start;
+        } else if ptr.as_ptr() as usize + header.size == self.top {
+            self.top = header.prev_top;
+        }
+        Ok(())
+    }
+
+    fn alloc_unaligned(&mut self, size: usize) -> Result<NonNull<u8>, Errno> {
+        self.alloc(Layout::from_size_align(size, MIN_ALIGN).map_err(|_| Errno::EINVAL)?)
+    }
+
+    fn alloc(&mut self, layout: Layout) -> Result<NonNull<u8>, Errno> {
+        let requested_align = cmp::max(layout.align(), MIN_ALIGN);
+        let requested_size = layout.size();
+        if requested_size == 0 {
+            panic!("Program attempted to allocate an object of 0 bytes");
+        }
+        let requested_size = requested_size.align_up(MIN_ALIGN);
+
+        let place = |top: usize| {
+            let data = (top + HDR_SIZE).align_up(requested_align);
+            (data, data + requested_size)
+        };
+
+        let (mut data, mut new_top) = place(self.top);
+        if new_top > self.end {
+            // Enough for the allocation even if the new region isn't contiguous
+            self.claim_more(HDR_SIZE + requested_align + requested_size)?;
+            (data, new_top) = place(self.top);
+            assert!(new_top <= self.end);
+        }
+
+        let data = NonNull::new(data as *mut u8).ok_or(Errno::CloysterAlloc)?;
+        unsafe {
+            *Self::header(data)? = Header {
+                live: true,
+                size: requested_size,
+                prev_top: self.top,
+            };
+        }
+        self.top = new_top;
+        self.allocations += 1;
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::malloc::tests::MockExtender;
+
+    #[test]
+    fn rewinds_when_empty() {
+        let mut allocator =
+            BumpAllocator::from_memory_extender(MockExtender::new(PAGE_SIZE * 4)).unwrap();
+        let first = allocator.alloc_unaligned(100).unwrap();
+        let second = allocator.alloc_unaligned(100).unwrap();
+        unsafe {
+            allocator.free(first).unwrap();
+            allocator.free(second).unwrap();
+        }
+        assert_eq!(allocator.alloc_unaligned(100).unwrap(), first);
+    }
+
+    #[test]
+    fn rolls_back_last_allocation() {
+        let mut allocator =
+            BumpAllocator::from_memory_extender(MockExtender::new(PAGE_SIZE * 4)).unwrap();
+        let _first = allocator.alloc_unaligned(100).unwrap();
+        let second = allocator.alloc_unaligned(100).unwrap();
+        unsafe {
+            allocator.free(second).unwrap();
+        }
+        assert_eq!(allocator.alloc_unaligned(50).unwrap(), second);
+    }
+}
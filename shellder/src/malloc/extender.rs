@@ -0,0 +1,18 @@
+use crate::errno::Errno;
+use core::ptr::NonNull;
+
+/// Source of fresh memory for the allocator backends
+pub(crate) trait MemoryExtender {
+    unsafe fn sbrk(&mut self, increment: usize) -> Result<NonNull<u8>, Errno>;
+}
+
+#[cfg(not(feature = "user_alloc"))]
+pub(crate) struct DefaultMemoryExtender;
+
+#[cfg(not(feature = "user_alloc"))]
+impl MemoryExtender for DefaultMemoryExtender {
+    unsafe fn sbrk(&mut self, increment: usize) -> Result<NonNull<u8>, Errno> {
+        NonNull::new(unsafe { crate::unistd::sbrk(increment.try_into()?)? })
+            .ok_or(Errno::CloysterAlloc)
+    }
+}
@@ -1,4 +1,4 @@
-use super::usize_ext::UsizeExt;
+use super::{Backend, extender::MemoryExtender, usize_ext::UsizeExt};
 use crate::errno::Errno;
 use core::{
     alloc::Layout,
@@ -10,20 +10,8 @@ const MIN_ALIGN: usize = 32;
 const HDR_SIZE: usize = MIN_ALIGN;
 const PAGE_SIZE: usize = 4096;
 
-pub(crate) type Allocator = FreeListAllocator<DefaultMemoryExtender>;
-
-pub(crate) trait MemoryExtender {
-    unsafe fn sbrk(&mut self, increment: usize) -> Result<NonNull<u8>, Errno>;
-}
-
-pub(crate) struct DefaultMemoryExtender;
-
-impl MemoryExtender for DefaultMemoryExtender {
-    unsafe fn sbrk(&mut self, increment: usize) -> Result<NonNull<u8>, Errno> {
-        NonNull::new(unsafe { crate::unistd::sbrk(increment.try_into()?)? })
-            .ok_or(Errno::CloysterAlloc)
-    }
-}
+#[cfg(not(any(feature = "bump_alloc", feature = "user_alloc")))]
+pub(crate) type Allocator = FreeListAllocator<super::extender::DefaultMemoryExtender>;
 
 #[repr(C)]
 #[derive(Debug, Clone)]
@@ -45,14 +33,15 @@ pub(crate) struct FreeListAllocator<T> {
 
 unsafe impl<T: Send> Send for FreeListAllocator<T> {}
 
-impl FreeListAllocator<DefaultMemoryExtender> {
+#[cfg(not(any(feature = "bump_alloc", feature = "user_alloc")))]
+impl Allocator {
     pub(crate) fn new() -> Result<Self, Errno> {
-        Self::from_memory_extender(DefaultMemoryExtender)
+        Self::from_memory_extender(super::extender::DefaultMemoryExtender)
     }
 }
 
 impl<T: MemoryExtender> FreeListAllocator<T> {
-    fn from_memory_extender(mut memory_extender: T) -> Result<Self, Errno> {
+    pub(crate) fn from_memory_extender(mut memory_extender: T) -> Result<Self, Errno> {
         assert!(HDR_SIZE >= mem::size_of::<Node>());
         let mut head = unsafe { memory_extender.sbrk(PAGE_SIZE)?.cast() };
         unsafe {
@@ -73,12 +62,9 @@ impl<T: MemoryExtender> FreeListAllocator<T> {
         })
     }
 
-    pub(crate) fn allocations(&self) -> usize {
-        self.allocations
-    }
-
     fn claim_more(&mut self, required: usize) -> Result<NonNull<Node>, Errno> {
-        let required = required.align_up(PAGE_SIZE);
+        // The new node's header comes out of the claim too
+        let required = (required + HDR_SIZE).align_up(PAGE_SIZE);
 
         let mut node = unsafe { self.memory_extender.sbrk(required)? }.cast();
         assert_eq!(
@@ -100,12 +86,18 @@ impl<T: MemoryExtender> FreeListAllocator<T> {
 
         Ok(node)
     }
+}
+
+impl<T: MemoryExtender + Send> Backend for FreeListAllocator<T> {
+    fn allocations(&self) -> usize {
+        self.allocations
+    }
 
     /// Return the size of a memory allocation
     ///
     /// # Safety
     /// Ptr must be a valid, previosly allocated region of memory
-    pub(crate) unsafe fn size_of(&mut self, ptr: NonNull<u8>) -> Result<usize, Errno> {
+    unsafe fn size_of(&mut self, ptr: NonNull<u8>) -> Result<usize, Errno> {
         let ptr = ptr.as_ptr();
         let node = unsafe { ((ptr.wrapping_sub(HDR_SIZE)) as *const Node).as_ref() }
             .ok_or(Errno::CloysterAlloc)?;
@@ -115,7 +107,7 @@ impl<T: MemoryExtender> FreeListAllocator<T> {
 
     /// # Safety
     /// Ptr must be a valid, previosly allocated region of memory
-    pub(crate) unsafe fn free(&mut self, ptr: NonNull<u8>) -> Result<(), Errno> {
+    unsafe fn free(&mut self, ptr: NonNull<u8>) -> Result<(), Errno> {
         let ptr = ptr.as_ptr();
         let node = unsafe { ((ptr.wrapping_sub(HDR_SIZE)) as *mut Node).as_mut() }
             .ok_or(Errno::CloysterAlloc)?;
@@ -129,11 +121,11 @@ impl<T: MemoryExtender> FreeListAllocator<T> {
         Ok(())
     }
 
-    pub(crate) fn alloc_unaligned(&mut self, size: usize) -> Result<NonNull<u8>, Errno> {
+    fn alloc_unaligned(&mut self, size: usize) -> Result<NonNull<u8>, Errno> {
         self.alloc(Layout::from_size_align(size, MIN_ALIGN).map_err(|_| Errno::EINVAL)?)
     }
 
-    pub(crate) fn alloc(&mut self, layout: Layout) -> Result<NonNull<u8>, Errno> {
+    fn alloc(&mut self, layout: Layout) -> Result<NonNull<u8>, Errno> {
         let requested_align = cmp::max(layout.align(), MIN_ALIGN);
         let requested_size = layout.size();
         if requested_size == 0 {
@@ -212,76 +204,16 @@ impl<T: MemoryExtender> FreeListAllocator<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rand::Rng;
-
-    struct MockExtender {
-        _backing: Vec<u8>,
-        base: usize,
-        max: usize,
-    }
-
-    impl MockExtender {
-        fn new(capacity: usize) -> Self {
-            let mut _backing = Vec::with_capacity(capacity + MIN_ALIGN);
-            let base = (_backing.as_mut_ptr() as usize).align_up(MIN_ALIGN);
-            let max = base + capacity;
-            Self {
-                _backing,
-                base,
-                max,
-            }
-        }
-    }
-
-    impl MemoryExtender for MockExtender {
-        unsafe fn sbrk(&mut self, increment: usize) -> Result<NonNull<u8>, Errno> {
-            let base = self.base;
-            self.base += increment;
-            if self.base > self.max {
-                panic!("Out of mock memory");
-            }
-
-            Ok(NonNull::new(base as *mut u8).unwrap())
-        }
-    }
-
-    #[test]
-    fn rand_allocations() {
-        let mut rng = rand::thread_rng();
-        let mut allocator =
-            FreeListAllocator::from_memory_extender(MockExtender::new(100000)).unwrap();
-
-        for _ in 1..100 {
-            let mut allocs = Vec::new();
-
-            for i in 1..100 {
-                let area = allocator
-                    .alloc_unaligned(rng.r#gen::<usize>() % 256 + 1)
-                    .unwrap();
-                allocs.push(area);
-                assert_eq!(allocator.allocations, i);
-            }
-
-            for alloc in allocs {
-                unsafe {
-                    allocator.free(alloc).unwrap();
-                }
-            }
-        }
-
-        assert_eq!(allocator.allocations, 0);
-    }
+    use crate::malloc::tests::MockExtender;
 
     #[test]
-    fn multiple_allocations() {
+    fn few_claims() {
         let mut allocs = Vec::new();
         let mut allocator =
             FreeListAllocator::from_memory_extender(MockExtender::new(100000)).unwrap();
 
-        for i in 1..400 {
-            let area = allocator.alloc_unaligned(31).unwrap();
-            allocs.push(area);
-            assert_eq!(allocator.allocations, i);
+        for _ in 1..400 {
+            allocs.push(allocator.alloc_unaligned(31).unwrap());
         }
 
         for alloc in allocs {
@@ -290,53 +222,15 @@ mod tests {
             }
         }
 
-        assert_eq!(allocator.allocations, 0);
         assert!(allocator.total_claims < 10);
     }
 
     #[test]
-    fn allocate_more_than_a_page() {
-        let mut allocator =
-            FreeListAllocator::from_memory_extender(MockExtender::new(PAGE_SIZE * 10)).unwrap();
-        unsafe {
-            let area = allocator.alloc_unaligned(PAGE_SIZE * 5 + 3).unwrap();
-            allocator.free(area).unwrap();
-        }
-        assert_eq!(allocator.allocations, 0);
-    }
-
-    #[test]
-    fn basic() {
-        let mut allocator =
-            FreeListAllocator::from_memory_extender(MockExtender::new(10000)).unwrap();
-        for _ in 0..1000000 {
-            let area = allocator.alloc_unaligned(800).unwrap();
-            assert_eq!(allocator.allocations, 1);
-            unsafe {
-                {
-                    let area = area.as_ptr() as *mut u32;
-                    *area = 0xdeadbeef;
-                    assert_eq!(*area, 0xdeadbeef);
-                }
-                allocator.free(area).unwrap();
-            }
-        }
-        assert_eq!(allocator.allocations, 0);
-    }
-
-    #[test]
-    fn allocate_alignment() {
+    fn page_sized_allocation() {
         let mut allocator =
-            FreeListAllocator::from_memory_extender(MockExtender::new(10000)).unwrap();
-        for align in [1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024, 2048] {
-            let ptr = allocator
-                .alloc(Layout::from_size_align(45, align).unwrap())
-                .unwrap();
-            assert!((ptr.as_ptr() as usize).is_aligned_to(align));
-            unsafe {
-                allocator.free(ptr).unwrap();
-            }
-        }
-        assert_eq!(allocator.allocations, 0);
+            FreeListAllocator::from_memory_extender(MockExtender::new(100000)).unwrap();
+        let alloc = allocator.alloc_unaligned(2 * PAGE_SIZE).unwrap();
+        assert_eq!(unsafe { allocator.size_of(alloc) }, Ok(2 * PAGE_SIZE));
+        unsafe { allocator.free(alloc).unwrap() };
     }
 }
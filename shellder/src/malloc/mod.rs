@@ -1,11 +1,61 @@
+//! Memory allocation
+//!
+//! The allocator backend is chosen at compile time. The free list allocator is used by default;
+//! the `bump_alloc` feature selects an arena allocator that only reclaims memory once everything
+//! has been freed, and the `user_alloc` feature forwards to an allocator supplied at link time.
+#[cfg(any(test, feature = "bump_alloc"))]
+mod bump_impl;
+#[cfg(any(test, not(feature = "user_alloc")))]
+mod extender;
+#[cfg(any(test, not(any(feature = "bump_alloc", feature = "user_alloc"))))]
 mod free_list_impl;
+#[cfg(any(test, feature = "malloc_trace"))]
+pub mod trace;
+#[cfg(any(test, feature = "user_alloc"))]
+mod user_impl;
+#[cfg(any(test, not(feature = "user_alloc")))]
 mod usize_ext;
 
 use crate::errno::Errno;
 use core::{alloc::Layout, cell::OnceCell, ptr::NonNull};
-use free_list_impl::Allocator;
 use spin::Mutex;
 
+#[cfg(all(feature = "bump_alloc", feature = "user_alloc"))]
+compile_error!("Only one allocator backend feature may be enabled");
+
+#[cfg(feature = "bump_alloc")]
+use bump_impl::Allocator;
+#[cfg(not(any(feature = "bump_alloc", feature = "user_alloc")))]
+use free_list_impl::Allocator;
+#[cfg(feature = "user_alloc")]
+use user_impl::Allocator;
+
+/// Alignment of `max_align_t`, which `malloc()` and friends must satisfy
+const MALLOC_ALIGN: usize = 16;
+
+/// Interface implemented by every allocator backend
+pub(crate) trait Backend: Send {
+    /// Number of allocations that have not yet been freed
+    fn allocations(&self) -> usize;
+
+    /// Return the size of a memory allocation
+    ///
+    /// # Safety
+    /// Ptr must be a valid, previosly allocated region of memory
+    unsafe fn size_of(&mut self, ptr: NonNull<u8>) -> Result<usize, Errno>;
+
+    /// # Safety
+    /// Ptr must be a valid, previosly allocated region of memory
+    unsafe fn free(&mut self, ptr: NonNull<u8>) -> Result<(), Errno>;
+
+    /// Allocate `size` bytes suitably aligned for any C object
+    fn alloc_unaligned(&mut self, size: usize) -> Result<NonNull<u8>, Errno> {
+        self.alloc(Layout::from_size_align(size, MALLOC_ALIGN).map_err(|_| Errno::EINVAL)?)
+    }
+
+    fn alloc(&mut self, layout: Layout) -> Result<NonNull<u8>, Errno>;
+}
+
 static ALLOCATOR: Mutex<OnceCell<Allocator>> = Mutex::new(OnceCell::new());
 
 pub fn get_num_allocations() -> usize {
@@ -74,3 +124,208 @@ pub unsafe fn free(ptr: NonNull<u8>) -> Result<(), Errno> {
     let allocator = allocator.get_mut().expect("Bug: allocator not initialized");
     unsafe { allocator.free(ptr) }
 }
+
+/// Call `function` while holding the allocator's lock, so no other thread is part way through an
+/// allocation. Used around `fork()`, so the child doesn't inherit a lock that nothing will release
+pub fn while_locked<T>(function: impl FnOnce() -> T) -> T {
+    let _allocator = ALLOCATOR.lock();
+    function()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{
+        Backend, bump_impl::BumpAllocator, extender::MemoryExtender,
+        free_list_impl::FreeListAllocator, user_impl::Registry, user_impl::UserAllocator,
+        usize_ext::UsizeExt,
+    };
+    use crate::errno::Errno;
+    use core::{alloc::Layout, ptr::NonNull};
+    use rand::Rng;
+
+    const MIN_ALIGN: usize = 32;
+    const PAGE_SIZE: usize = 4096;
+
+    pub(crate) struct MockExtender {
+        _backing: Vec<u8>,
+        base: usize,
+        max: usize,
+    }
+
+    impl MockExtender {
+        pub(crate) fn new(capacity: usize) -> Self {
+            let mut _backing = Vec::with_capacity(capacity + MIN_ALIGN);
+            let base = (_backing.as_mut_ptr() as usize).align_up(MIN_ALIGN);
+            let max = base + capacity;
+            Self {
+                _backing,
+                base,
+                max,
+            }
+        }
+    }
+
+    impl MemoryExtender for MockExtender {
+        unsafe fn sbrk(&mut self, increment: usize) -> Result<NonNull<u8>, Errno> {
+            let base = self.base;
+            self.base += increment;
+            if self.base > self.max {
+                panic!("Out of mock memory");
+            }
+
+            Ok(NonNull::new(base as *mut u8).unwrap())
+        }
+    }
+
+    // Stands in for an allocator registered at link time
+    struct MockRegistry<B>(B);
+
+    impl<B: Backend> Registry for MockRegistry<B> {
+        unsafe fn alloc(&mut self, size: usize, align: usize) -> *mut u8 {
+            self.0
+                .alloc(Layout::from_size_align(size, align).unwrap())
+                .unwrap()
+                .as_ptr()
+        }
+
+        unsafe fn free(&mut self, ptr: NonNull<u8>) {
+            unsafe { self.0.free(ptr).unwrap() }
+        }
+
+        unsafe fn size_of(&mut self, ptr: NonNull<u8>) -> usize {
+            unsafe { self.0.size_of(ptr).unwrap() }
+        }
+    }
+
+    fn free_list(extender: MockExtender) -> impl Backend {
+        FreeListAllocator::from_memory_extender(extender).unwrap()
+    }
+
+    fn bump(extender: MockExtender) -> impl Backend {
+        BumpAllocator::from_memory_extender(extender).unwrap()
+    }
+
+    fn user(extender: MockExtender) -> impl Backend {
+        UserAllocator::from_registry(MockRegistry(free_list(extender)))
+    }
+
+    fn rand_allocations(mut allocator: impl Backend) {
+        let mut rng = rand::thread_rng();
+
+        for _ in 1..100 {
+            let mut allocs = Vec::new();
+
+            for i in 1..100 {
+                let area = allocator
+                    .alloc_unaligned(rng.r#gen::<usize>() % 256 + 1)
+                    .unwrap();
+                allocs.push(area);
+                assert_eq!(allocator.allocations(), i);
+            }
+
+            for alloc in allocs {
+                unsafe {
+                    allocator.free(alloc).unwrap();
+                }
+            }
+        }
+
+        assert_eq!(allocator.allocations(), 0);
+    }
+
+    fn multiple_allocations(mut allocator: impl Backend) {
+        let mut allocs = Vec::new();
+
+        for i in 1..400 {
+            let area = allocator.alloc_unaligned(31).unwrap();
+            allocs.push(area);
+            assert_eq!(allocator.allocations(), i);
+        }
+
+        for alloc in allocs {
+            unsafe {
+                allocator.free(alloc).unwrap();
+            }
+        }
+
+        assert_eq!(allocator.allocations(), 0);
+    }
+
+    fn allocate_more_than_a_page(mut allocator: impl Backend) {
+        unsafe {
+            let area = allocator.alloc_unaligned(PAGE_SIZE * 5 + 3).unwrap();
+            assert!(allocator.size_of(area).unwrap() >= PAGE_SIZE * 5 + 3);
+            allocator.free(area).unwrap();
+        }
+        assert_eq!(allocator.allocations(), 0);
+    }
+
+    fn basic(mut allocator: impl Backend) {
+        for _ in 0..1000000 {
+            let area = allocator.alloc_unaligned(800).unwrap();
+            assert_eq!(allocator.allocations(), 1);
+            unsafe {
+                {
+                    let area = area.as_ptr() as *mut u32;
+                    *area = 0xdeadbeef;
+                    assert_eq!(*area, 0xdeadbeef);
+                }
+                allocator.free(area).unwrap();
+            }
+        }
+        assert_eq!(allocator.allocations(), 0);
+    }
+
+    fn allocate_alignment(mut allocator: impl Backend) {
+        for align in [1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024, 2048] {
+            let ptr = allocator
+                .alloc(Layout::from_size_align(45, align).unwrap())
+                .unwrap();
+            assert!((ptr.as_ptr() as usize).is_aligned_to(align));
+            unsafe {
+                allocator.free(ptr).unwrap();
+            }
+        }
+        assert_eq!(allocator.allocations(), 0);
+    }
+
+    // Run every test against each backend
+    macro_rules! backend_tests {
+        ($($backend:ident),*) => {
+            $(
+                mod $backend {
+                    use super::*;
+
+                    #[test]
+                    fn rand_allocations() {
+                        super::rand_allocations(super::$backend(MockExtender::new(100000)));
+                    }
+
+                    #[test]
+                    fn multiple_allocations() {
+                        super::multiple_allocations(super::$backend(MockExtender::new(100000)));
+                    }
+
+                    #[test]
+                    fn allocate_more_than_a_page() {
+                        super::allocate_more_than_a_page(super::$backend(MockExtender::new(
+                            PAGE_SIZE * 10,
+                        )));
+                    }
+
+                    #[test]
+                    fn basic() {
+                        super::basic(super::$backend(MockExtender::new(10000)));
+                    }
+
+                    #[test]
+                    fn allocate_alignment() {
+                        super::allocate_alignment(super::$backend(MockExtender::new(10000)));
+                    }
+                }
+            )*
+        };
+    }
+
+    backend_tests!(free_list, bump, user);
+}
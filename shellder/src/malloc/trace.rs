@@ -0,0 +1,173 @@
+//! Allocation tracing
+//!
+//! When enabled with [init], every allocation event is written to a file descriptor as a single
+//! line of the form
+//!
+//! ```text
+//! <op> <ptr> <size> <old_ptr> <caller>
+//! ```
+//!
+//! where `op` is one of `m` (malloc), `c` (calloc), `r` (realloc), `a` (aligned_alloc), or `f`
+//! (free), `size` is in decimal, and `ptr`, `old_ptr` and `caller` are in hexadecimal. Fields that
+//! don't apply to an event are 0. A failed allocation has a `ptr` of 0.
+use core::{
+    ffi::{CStr, c_int, c_void},
+    fmt::Write,
+    ptr::NonNull,
+    sync::atomic::{AtomicI32, Ordering},
+};
+
+/// Environment variable holding the file descriptor to trace to
+pub const TRACE_ENV_VAR: &CStr = c"CLOYSTER_MALLOC_TRACE";
+
+static TRACE_FD: AtomicI32 = AtomicI32::new(-1);
+
+/// Kind of allocation event
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Op {
+    Malloc,
+    Calloc,
+    Realloc,
+    AlignedAlloc,
+    Free,
+}
+
+impl Op {
+    fn as_char(self) -> char {
+        match self {
+            Self::Malloc => 'm',
+            Self::Calloc => 'c',
+            Self::Realloc => 'r',
+            Self::AlignedAlloc => 'a',
+            Self::Free => 'f',
+        }
+    }
+}
+
+/// A single allocation event
+#[derive(Copy, Clone, Debug)]
+pub struct Event {
+    pub op: Op,
+    /// Region returned by the allocation, or the region being freed
+    pub ptr: Option<NonNull<u8>>,
+    /// Requested size in bytes
+    pub size: usize,
+    /// Region passed to `realloc()`
+    pub old_ptr: Option<NonNull<u8>>,
+    /// Return address of the function call
+    pub caller: usize,
+}
+
+/// Start tracing allocation events to `fd`
+pub fn init(fd: c_int) {
+    TRACE_FD.store(fd, Ordering::Relaxed);
+}
+
+/// Start tracing if [TRACE_ENV_VAR] names a file descriptor
+pub fn init_from_env() {
+    if let Some(fd) = crate::stdlib::getenv(TRACE_ENV_VAR)
+        .map(|var| (var.to_bytes().len(), crate::stdlib::strtol(var, 10)))
+        .filter(|(len, fd)| *len > 0 && fd.consumed == *len && fd.error.is_none())
+        .and_then(|(_, fd)| c_int::try_from(fd.value).ok())
+    {
+        init(fd);
+    }
+}
+
+// Fixed-size line buffer, because we can't allocate while tracing allocations
+struct LineBuffer {
+    buf: [u8; 96],
+    len: usize,
+}
+
+impl Write for LineBuffer {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.len + s.len();
+        self.buf
+            .get_mut(self.len..end)
+            .ok_or(core::fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+fn format(event: &Event, line: &mut LineBuffer) -> core::fmt::Result {
+    let addr = |ptr: Option<NonNull<u8>>| ptr.map(|ptr| ptr.as_ptr() as usize).unwrap_or(0);
+    writeln!(
+        line,
+        "{} {:x} {} {:x} {:x}",
+        event.op.as_char(),
+        addr(event.ptr),
+        event.size,
+        addr(event.old_ptr),
+        event.caller
+    )
+}
+
+/// Record an allocation event, if tracing is enabled
+pub fn record(event: Event) {
+    let fd = TRACE_FD.load(Ordering::Relaxed);
+    if fd < 0 {
+        return;
+    }
+
+    let mut line = LineBuffer {
+        buf: [0; 96],
+        len: 0,
+    };
+    if format(&event, &mut line).is_ok() {
+        // A single write per event, so lines from different threads don't interleave
+        // Tracing is best-effort, so errors are ignored
+        let _ = unsafe { crate::unistd::write(fd, line.buf.as_ptr() as *const c_void, line.len) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formatted(event: Event) -> String {
+        let mut line = LineBuffer {
+            buf: [0; 96],
+            len: 0,
+        };
+        format(&event, &mut line).unwrap();
+        String::from_utf8(line.buf[..line.len].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn format_events() {
+        let ptr = NonNull::new(0x1000 as *mut u8);
+        assert_eq!(
+            formatted(Event {
+                op: Op::Malloc,
+                ptr,
+                size: 100,
+                old_ptr: None,
+                caller: 0x401abc,
+            }),
+            "m 1000 100 0 401abc\n"
+        );
+        assert_eq!(
+            formatted(Event {
+                op: Op::Realloc,
+                ptr: NonNull::new(0x2000 as *mut u8),
+                size: 20,
+                old_ptr: ptr,
+                caller: 0x10,
+            }),
+            "r 2000 20 1000 10\n"
+        );
+        assert_eq!(
+            formatted(Event {
+                op: Op::Free,
+                ptr,
+                size: 0,
+                old_ptr: None,
+                caller: usize::MAX,
+            }),
+            format!("f 1000 0 0 {:x}\n", usize::MAX)
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Allocator backend that forwards to an allocator registered at link time
+//!
+//! With the `user_alloc` feature, the program (or a library linked alongside Cloyster) must
+//! define the following functions:
+//!
+//! * `void *__cloyster_user_alloc(size_t size, size_t align);` - returns NULL on failure
+//! * `void __cloyster_user_free(void *ptr);`
+//! * `size_t __cloyster_user_size(void *ptr);` - usable size of a live allocation
+use super::Backend;
+use crate::errno::Errno;
+use core::{alloc::Layout, ptr::NonNull};
+
+#[cfg(feature = "user_alloc")]
+pub(crate) type Allocator = UserAllocator<LinkedRegistry>;
+
+/// The functions making up a registered allocator
+pub(crate) trait Registry {
+    unsafe fn alloc(&mut self, size: usize, align: usize) -> *mut u8;
+    unsafe fn free(&mut self, ptr: NonNull<u8>);
+    unsafe fn size_of(&mut self, ptr: NonNull<u8>) -> usize;
+}
+
+#[cfg(feature = "user_alloc")]
+mod linked {
+    use core::{ffi::c_void, ptr::NonNull};
+
+    unsafe extern "C" {
+        fn __cloyster_user_alloc(size: usize, align: usize) -> *mut c_void;
+        fn __cloyster_user_free(ptr: *mut c_void);
+        fn __cloyster_user_size(ptr: *mut c_void) -> usize;
+    }
+
+    /// Registry resolved from the `__cloyster_user_*` symbols
+    pub(crate) struct LinkedRegistry;
+
+    impl super::Registry for LinkedRegistry {
+        unsafe fn alloc(&mut self, size: usize, align: usize) -> *mut u8 {
+            unsafe { __cloyster_user_alloc(size, align).cast() }
+        }
+
+        unsafe fn free(&mut self, ptr: NonNull<u8>) {
+            unsafe { __cloyster_user_free(ptr.cast().as_ptr()) }
+        }
+
+        unsafe fn size_of(&mut self, ptr: NonNull<u8>) -> usize {
+            unsafe { __cloyster_user_size(ptr.cast().as_ptr()) }
+        }
+    }
+}
+#[cfg(feature = "user_alloc")]
+pub(crate) use linked::LinkedRegistry;
+
+// Nothing registers an allocator in test binaries, so forward to the C library's, which they link
+#[cfg(all(feature = "user_alloc", test))]
+mod test_registration {
+    use core::ffi::c_void;
+
+    unsafe extern "C" {
+        fn aligned_alloc(align: usize, size: usize) -> *mut c_void;
+        fn free(ptr: *mut c_void);
+        fn malloc_usable_size(ptr: *mut c_void) -> usize;
+    }
+
+    #[unsafe(no_mangle)]
+    unsafe extern "C" fn __cloyster_user_alloc(size: usize, align: usize) -> *mut c_void {
+        // The size has to be a multiple of the alignment
+        unsafe { aligned_alloc(align, size.next_multiple_of(align)) }
+    }
+
+    #[unsafe(no_mangle)]
+    unsafe extern "C" fn __cloyster_user_free(ptr: *mut c_void) {
+        unsafe { free(ptr) }
+    }
+
+    #[unsafe(no_mangle)]
+    unsafe extern "C" fn __cloyster_user_size(ptr: *mut c_void) -> usize {
+        unsafe { malloc_usable_size(ptr) }
+    }
+}
+
+pub(crate) struct UserAllocator<R> {
+    registry: R,
+    allocations: usize,
+}
+
+#[cfg(feature = "user_alloc")]
+impl Allocator {
+    pub(crate) fn new() -> Result<Self, Errno> {
+        Ok(Self::from_registry(LinkedRegistry))
+    }
+}
+
+impl<R: Registry> UserAllocator<R> {
+    pub(crate) fn from_registry(registry: R) -> Self {
+        Self {
+            registry,
+            allocations: 0,
+        }
+    }
+}
+
+impl<R: Registry + Send> Backend for UserAllocator<R> {
+    fn allocations(&self) -> usize {
+        self.allocations
+    }
+
+    unsafe fn size_of(&mut self, ptr: NonNull<u8>) -> Result<usize, Errno> {
+        Ok(unsafe { self.registry.size_of(ptr) })
+    }
+
+    unsafe fn free(&mut self, ptr: NonNull<u8>) -> Result<(), Errno> {
+        unsafe { self.registry.free(ptr) };
+        self.allocations = self
+            .allocations
+            .checked_sub(1)
+            .expect("Freed more than allocated! This is possibly a bug in Cloyster, or you free()'d one too many times");
+        Ok(())
+    }
+
+    fn alloc(&mut self, layout: Layout) -> Result<NonNull<u8>, Errno> {
+        let ptr = NonNull::new(unsafe { self.registry.alloc(layout.size(), layout.align()) })
+            .ok_or(Errno::CloysterAlloc)?;
+        self.allocations += 1;
+        Ok(ptr)
+    }
+}
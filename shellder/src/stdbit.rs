@@ -0,0 +1,211 @@
+//! C23 bit and byte utilities
+use core::ffi::c_uint;
+
+/// Unsigned integer types the `stdc_*` bit functions are defined for
+pub trait StdBit: Copy + Sized {
+    /// Number of consecutive 0 bits, starting from the most significant bit
+    fn leading_zeros(self) -> c_uint;
+    /// Number of consecutive 1 bits, starting from the most significant bit
+    fn leading_ones(self) -> c_uint;
+    /// Number of consecutive 0 bits, starting from the least significant bit
+    fn trailing_zeros(self) -> c_uint;
+    /// Number of consecutive 1 bits, starting from the least significant bit
+    fn trailing_ones(self) -> c_uint;
+    /// Number of 0 bits
+    fn count_zeros(self) -> c_uint;
+    /// Number of 1 bits
+    fn count_ones(self) -> c_uint;
+    /// Whether exactly one bit is set
+    fn has_single_bit(self) -> bool;
+    /// Number of bits needed to represent the value
+    fn bit_width(self) -> c_uint;
+    /// Largest power of two not greater than the value, or 0 if the value is 0
+    fn bit_floor(self) -> Self;
+    /// Smallest power of two not less than the value, or 0 if that isn't representable
+    fn bit_ceil(self) -> Self;
+    /// Type width in bits
+    fn width() -> c_uint;
+}
+
+macro_rules! impl_std_bit {
+    ($($type:ty),*) => {$(
+        impl StdBit for $type {
+            fn leading_zeros(self) -> c_uint {
+                <$type>::leading_zeros(self)
+            }
+
+            fn leading_ones(self) -> c_uint {
+                <$type>::leading_ones(self)
+            }
+
+            fn trailing_zeros(self) -> c_uint {
+                <$type>::trailing_zeros(self)
+            }
+
+            fn trailing_ones(self) -> c_uint {
+                <$type>::trailing_ones(self)
+            }
+
+            fn count_zeros(self) -> c_uint {
+                <$type>::count_zeros(self)
+            }
+
+            fn count_ones(self) -> c_uint {
+                <$type>::count_ones(self)
+            }
+
+            fn has_single_bit(self) -> bool {
+                self.is_power_of_two()
+            }
+
+            fn bit_width(self) -> c_uint {
+                <$type>::BITS - <$type>::leading_zeros(self)
+            }
+
+            fn bit_floor(self) -> Self {
+                if self == 0 {
+                    0
+                } else {
+                    1 << (StdBit::bit_width(self) - 1)
+                }
+            }
+
+            fn bit_ceil(self) -> Self {
+                self.checked_next_power_of_two().unwrap_or(0)
+            }
+
+            fn width() -> c_uint {
+                <$type>::BITS
+            }
+        }
+    )*};
+}
+
+impl_std_bit!(u8, u16, u32, u64);
+
+/// Number of consecutive 0 bits, starting from the most significant bit
+pub fn leading_zeros<T: StdBit>(value: T) -> c_uint {
+    value.leading_zeros()
+}
+
+/// Number of consecutive 1 bits, starting from the most significant bit
+pub fn leading_ones<T: StdBit>(value: T) -> c_uint {
+    value.leading_ones()
+}
+
+/// Number of consecutive 0 bits, starting from the least significant bit
+pub fn trailing_zeros<T: StdBit>(value: T) -> c_uint {
+    value.trailing_zeros()
+}
+
+/// Number of consecutive 1 bits, starting from the least significant bit
+pub fn trailing_ones<T: StdBit>(value: T) -> c_uint {
+    value.trailing_ones()
+}
+
+/// One-based position of the most significant 0 bit, counting from the most significant end, or 0
+/// if there isn't one
+pub fn first_leading_zero<T: StdBit>(value: T) -> c_uint {
+    match value.leading_ones() {
+        ones if ones == T::width() => 0,
+        ones => ones + 1,
+    }
+}
+
+/// One-based position of the most significant 1 bit, counting from the most significant end, or 0
+/// if there isn't one
+pub fn first_leading_one<T: StdBit>(value: T) -> c_uint {
+    match value.leading_zeros() {
+        zeros if zeros == T::width() => 0,
+        zeros => zeros + 1,
+    }
+}
+
+/// One-based position of the least significant 0 bit, or 0 if there isn't one
+pub fn first_trailing_zero<T: StdBit>(value: T) -> c_uint {
+    match value.trailing_ones() {
+        ones if ones == T::width() => 0,
+        ones => ones + 1,
+    }
+}
+
+/// One-based position of the least significant 1 bit, or 0 if there isn't one
+pub fn first_trailing_one<T: StdBit>(value: T) -> c_uint {
+    match value.trailing_zeros() {
+        zeros if zeros == T::width() => 0,
+        zeros => zeros + 1,
+    }
+}
+
+/// Number of 0 bits
+pub fn count_zeros<T: StdBit>(value: T) -> c_uint {
+    value.count_zeros()
+}
+
+/// Number of 1 bits
+pub fn count_ones<T: StdBit>(value: T) -> c_uint {
+    value.count_ones()
+}
+
+/// Whether exactly one bit is set
+pub fn has_single_bit<T: StdBit>(value: T) -> bool {
+    value.has_single_bit()
+}
+
+/// Number of bits needed to represent `value`, or 0 if `value` is 0
+pub fn bit_width<T: StdBit>(value: T) -> c_uint {
+    value.bit_width()
+}
+
+/// Largest power of two not greater than `value`, or 0 if `value` is 0
+pub fn bit_floor<T: StdBit>(value: T) -> T {
+    value.bit_floor()
+}
+
+/// Smallest power of two not less than `value`, or 0 if that isn't representable
+pub fn bit_ceil<T: StdBit>(value: T) -> T {
+    value.bit_ceil()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts() {
+        assert_eq!(leading_zeros(1u8), 7);
+        assert_eq!(leading_zeros(0u16), 16);
+        assert_eq!(leading_ones(0xf0u8), 4);
+        assert_eq!(trailing_zeros(0u32), 32);
+        assert_eq!(trailing_ones(0b0111u64), 3);
+        assert_eq!(count_zeros(0xf0f0u16), 8);
+        assert_eq!(count_ones(u64::MAX), 64);
+    }
+
+    #[test]
+    fn positions() {
+        assert_eq!(first_leading_zero(0xffu8), 0);
+        assert_eq!(first_leading_zero(0xefu8), 4);
+        assert_eq!(first_leading_one(0u32), 0);
+        assert_eq!(first_leading_one(1u32), 32);
+        assert_eq!(first_trailing_zero(u16::MAX), 0);
+        assert_eq!(first_trailing_zero(0b1011u16), 3);
+        assert_eq!(first_trailing_one(0u64), 0);
+        assert_eq!(first_trailing_one(0b1000u64), 4);
+    }
+
+    #[test]
+    fn powers_of_two() {
+        assert!(!has_single_bit(0u8));
+        assert!(has_single_bit(0x80u8));
+        assert!(!has_single_bit(6u32));
+        assert_eq!(bit_width(0u32), 0);
+        assert_eq!(bit_width(5u32), 3);
+        assert_eq!(bit_floor(0u16), 0);
+        assert_eq!(bit_floor(100u16), 64);
+        assert_eq!(bit_ceil(0u8), 1);
+        assert_eq!(bit_ceil(100u8), 128);
+        assert_eq!(bit_ceil(129u8), 0);
+        assert_eq!(bit_ceil(1u64 << 63), 1 << 63);
+    }
+}
@@ -1,9 +1,15 @@
-use super::printf::Cout;
-use crate::errno::Errno;
+use super::{printf::Cout, write_all};
+use crate::{
+    errno::Errno,
+    malloc::{free, malloc},
+    unistd::types::{IoVec, SEEK_CUR, off_t},
+};
 use core::{
-    ffi::{c_char, c_int, c_void},
-    fmt, ptr,
+    ffi::{c_char, c_int},
+    fmt,
+    ptr::{self, NonNull},
 };
+use spin::Mutex;
 
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug)]
@@ -19,7 +25,7 @@ impl Descriptor {
     }
 
     pub const fn stderr() -> Self {
-        Self(1)
+        Self(2)
     }
 }
 
@@ -30,11 +36,7 @@ impl Cout for Descriptor {
     }
 
     fn put_cstr(&mut self, s: &[u8]) -> Result<(), Errno> {
-        let len = s.len();
-        unsafe {
-            crate::unistd::write(self.0, ptr::from_ref(s) as *const c_void, len)?;
-        }
-        Ok(())
+        unsafe { write_all(self.0, &mut [IoVec::from(s)]) }
     }
 }
 
@@ -52,6 +54,19 @@ pub struct File {
     pub error: c_int,
     pub eof: bool,
     pub offset: u64,
+    /// Whether output is held in a buffer until it fills up or the stream is flushed. Streams
+    /// only opened for reading write straight through, so the error shows up right away
+    pub buffer_writes: bool,
+    /// Size of the buffer used for reads and writes, or 0 if the stream is unbuffered
+    pub buf_size: usize,
+    // Holds either output waiting to be written or input not yet read, allocated on first use
+    buf: Option<NonNull<u8>>,
+    pending: usize,
+    // Unread input is `buf[read_pos..read_len]`
+    read_pos: usize,
+    read_len: usize,
+    // Next stream in `OPEN_STREAMS`
+    next: Option<NonNull<File>>,
 }
 
 impl File {
@@ -61,6 +76,13 @@ impl File {
             eof: false,
             error: 0,
             offset: 0,
+            buffer_writes: false,
+            buf_size: 0,
+            buf: None,
+            pending: 0,
+            read_pos: 0,
+            read_len: 0,
+            next: None,
         }
     }
 
@@ -75,4 +97,203 @@ impl File {
     pub const fn stderr() -> Self {
         Self::from_desc(Descriptor::stderr())
     }
+
+    /// Set the error indicator if `res` is an error
+    pub(crate) fn track<T>(&mut self, res: Result<T, Errno>) -> Result<T, Errno> {
+        if let Err(err) = res {
+            self.error = err.to_posix().as_positive();
+        }
+        res
+    }
+
+    /// Write all of `data`, or add it to the buffer if it fits. Otherwise, the buffered output
+    /// and `data` are written together, in one syscall if the file takes it all
+    pub(crate) fn write(&mut self, data: &[u8]) -> Result<(), Errno> {
+        self.drop_input();
+        if !self.buffer_writes {
+            return unsafe { write_all(self.fd.0, &mut [IoVec::from(data)]) };
+        }
+        if self.pending + data.len() <= self.buf_size {
+            let buf = self.buffer()?;
+            unsafe {
+                ptr::copy_nonoverlapping(data.as_ptr(), buf.as_ptr().add(self.pending), data.len());
+            }
+            self.pending += data.len();
+            return Ok(());
+        }
+        // The buffered output is dropped even if it can't be written, like glibc
+        let pending = self.take_pending();
+        unsafe { write_all(self.fd.0, &mut [pending, IoVec::from(data)]) }
+    }
+
+    /// Read into `data` until it's full or the end of the file, setting the end-of-file indicator
+    /// if it's reached. Small reads fill the buffer, so later ones don't need a syscall
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes read. An error after some bytes were read sets the error indicator,
+    /// and those bytes are still returned
+    pub(crate) fn read(&mut self, data: &mut [u8]) -> Result<usize, Errno> {
+        let res = self.flush();
+        self.track(res)?;
+        let mut count = 0;
+        while count < data.len() {
+            let rest = &mut data[count..];
+            if self.read_pos == self.read_len {
+                // Too big to be worth copying through the buffer
+                let direct = rest.len() >= self.buf_size;
+                let res = if direct {
+                    unsafe { crate::unistd::read(self.fd.0, rest.as_mut_ptr().cast(), rest.len()) }
+                } else {
+                    self.fill()
+                };
+                match self.track(res) {
+                    Ok(0) => {
+                        self.eof = true;
+                        break;
+                    }
+                    Ok(val) if direct => {
+                        count += usize::try_from(val)?;
+                        continue;
+                    }
+                    Ok(_) => {}
+                    Err(_) if count > 0 => break,
+                    Err(err) => return Err(err),
+                }
+            }
+            let len = rest.len().min(self.read_len - self.read_pos);
+            let buf = self.buffer()?;
+            unsafe {
+                ptr::copy_nonoverlapping(buf.as_ptr().add(self.read_pos), rest.as_mut_ptr(), len);
+            }
+            self.read_pos += len;
+            count += len;
+        }
+        Ok(count)
+    }
+
+    // Refill the (empty) buffer with input
+    fn fill(&mut self) -> Result<c_int, Errno> {
+        let buf = self.buffer()?;
+        let val = unsafe { crate::unistd::read(self.fd.0, buf.as_ptr().cast(), self.buf_size)? };
+        self.read_pos = 0;
+        self.read_len = usize::try_from(val)?;
+        Ok(val)
+    }
+
+    /// Throw away input that was buffered but not read, moving the file offset back to where
+    /// the reader is
+    pub(crate) fn drop_input(&mut self) {
+        let unread = self.read_len - self.read_pos;
+        if unread > 0 {
+            // Pipes can't seek, and their input is lost either way
+            let _ = crate::unistd::lseek(self.fd.0, -(unread as off_t), SEEK_CUR);
+        }
+        self.read_pos = 0;
+        self.read_len = 0;
+    }
+
+    // The buffer, allocated with `buf_size` bytes on first use
+    fn buffer(&mut self) -> Result<NonNull<u8>, Errno> {
+        match self.buf {
+            Some(buf) => Ok(buf),
+            None => Ok(*self.buf.insert(malloc(self.buf_size)?)),
+        }
+    }
+
+    /// Write out the buffered output
+    pub(crate) fn flush(&mut self) -> Result<(), Errno> {
+        let pending = self.take_pending();
+        unsafe { write_all(self.fd.0, &mut [pending]) }
+    }
+
+    // Empty the buffer, returning what was in it. It stays valid until `release()`
+    fn take_pending(&mut self) -> IoVec {
+        let pending = IoVec {
+            base: self.buf.map_or(ptr::null_mut(), |buf| buf.as_ptr().cast()),
+            len: self.pending,
+        };
+        self.pending = 0;
+        pending
+    }
+
+    /// Free the buffer, dropping any output still in it
+    pub(crate) fn release(&mut self) -> Result<(), Errno> {
+        self.pending = 0;
+        self.read_pos = 0;
+        self.read_len = 0;
+        match self.buf.take() {
+            Some(buf) => unsafe { free(buf) },
+            None => Ok(()),
+        }
+    }
+}
+
+impl Cout for File {
+    fn put_char(&mut self, c: c_char) -> Result<(), Errno> {
+        self.write(&[c as u8])
+    }
+
+    fn put_cstr(&mut self, s: &[u8]) -> Result<(), Errno> {
+        self.write(s)
+    }
+}
+
+// Streams from `fopen()` and `fdopen()` that haven't been closed, linked through `File::next`, so
+// they can all be flushed
+struct OpenStreams(Option<NonNull<File>>);
+
+// Safety: the streams are only reached through the lock
+unsafe impl Send for OpenStreams {}
+
+static OPEN_STREAMS: Mutex<OpenStreams> = Mutex::new(OpenStreams(None));
+
+/// Add `file` to the streams flushed by [flush_all]
+///
+/// # Safety
+///
+/// `file` must be valid until it's passed to [forget]
+pub(crate) unsafe fn remember(mut file: NonNull<File>) {
+    let mut streams = OPEN_STREAMS.lock();
+    unsafe { file.as_mut() }.next = streams.0;
+    streams.0 = Some(file);
+}
+
+/// Take `file` out of the streams flushed by [flush_all]
+///
+/// # Safety
+///
+/// Every stream passed to [remember] must still be valid
+pub(crate) unsafe fn forget(file: NonNull<File>) {
+    let mut streams = OPEN_STREAMS.lock();
+    let mut link = &mut streams.0;
+    while let Some(mut stream) = *link {
+        if stream == file {
+            *link = unsafe { stream.as_ref() }.next;
+            return;
+        }
+        link = unsafe { &mut stream.as_mut().next };
+    }
+}
+
+/// Flush every open stream
+///
+/// # Returns
+///
+/// The first error, after trying every stream
+///
+/// # Safety
+///
+/// Every stream passed to [remember] must still be valid
+pub(crate) unsafe fn flush_all() -> Result<(), Errno> {
+    let streams = OPEN_STREAMS.lock();
+    let mut result = Ok(());
+    let mut next = streams.0;
+    while let Some(mut stream) = next {
+        let stream = unsafe { stream.as_mut() };
+        let flushed = stream.flush();
+        result = result.and(stream.track(flushed));
+        next = stream.next;
+    }
+    result
 }
@@ -1,17 +1,48 @@
 use crate::{
     errno::Errno,
     malloc::{free, malloc},
+    unistd::types::IoVec,
 };
 use core::{
     ffi::{CStr, VaListImpl, c_char, c_int, c_long, c_void},
     mem,
     ptr::{self, NonNull},
+    slice,
 };
 mod file;
 pub use file::{Descriptor, File};
 mod printf;
 use printf::printf_impl;
 
+/// Returned by character functions on end of file or error
+pub const EOF: c_int = -1;
+
+/// Size of a stream's buffer
+pub const BUFSIZ: usize = 8192;
+
+/// Write all of the buffers in `iov` to `fd` in order, looping on short writes. `iov` is used up
+/// along the way
+///
+/// # Safety
+///
+/// Each buffer must be valid for reads of its length
+pub(crate) unsafe fn write_all(fd: c_int, mut iov: &mut [IoVec]) -> Result<(), Errno> {
+    loop {
+        let skip = iov.iter().take_while(|buf| buf.len == 0).count();
+        iov = &mut mem::take(&mut iov)[skip..];
+        if iov.is_empty() {
+            return Ok(());
+        }
+        let mut written = unsafe { crate::unistd::writev(fd, iov)? };
+        for buf in iov.iter_mut() {
+            let count = written.min(buf.len);
+            buf.base = buf.base.wrapping_byte_add(count);
+            buf.len -= count;
+            written -= count;
+        }
+    }
+}
+
 /// Output string with terminating newline
 ///
 /// # C Signature
@@ -22,20 +53,18 @@ use printf::printf_impl;
 ///
 /// A non-negative number on success, or EOF on error
 pub fn puts(s: &CStr) -> Result<c_int, Errno> {
-    let length = crate::string::strlen(s);
-
-    let res = unsafe { crate::unistd::write(1, s.as_ptr() as *const c_void, length)? };
+    // One write if possible, so the line isn't split up by other writers
+    let mut iov = [IoVec::from(s.to_bytes()), IoVec::from(&b"\n"[..])];
+    unsafe { write_all(1, &mut iov)? };
 
-    putchar(b'\n'.into())?;
-
-    Ok(res)
+    Ok((s.count_bytes() + 1).try_into()?)
 }
 
-/// Output string to stream with terminating newline
+/// Output string to stream, without a terminating newline
 ///
 /// # C Signature
 ///
-/// `int fputs(const char *s);`
+/// `int fputs(const char *s, FILE *stream);`
 ///
 /// # Returns
 ///
@@ -45,11 +74,8 @@ pub fn puts(s: &CStr) -> Result<c_int, Errno> {
 ///
 /// `stream` must be a pointer to a File
 pub unsafe fn fputs(s: &CStr, stream: NonNull<File>) -> Result<c_int, Errno> {
-    let res = unsafe { fwrite(s.as_ptr() as *const u8, crate::string::strlen(s), 1, stream)? };
-    unsafe {
-        fputc(b'\n' as c_int, stream)?;
-    }
-    Ok(c_int::try_from(res)? + 1)
+    let res = unsafe { fwrite(s.as_ptr() as *const u8, 1, crate::string::strlen(s), stream)? };
+    Ok(c_int::try_from(res)?)
 }
 
 /// Clear error indicator and eof indicator
@@ -77,19 +103,36 @@ pub unsafe fn feof(stream: NonNull<File>) -> bool {
     unsafe { (*stream).eof }
 }
 
-/// Return error indicator
+/// Return the error that set the error indicator, or `None` if it isn't set
 ///
 ///
 /// # Safety
 ///
 /// `stream` must be a valid pointer to a File
-pub unsafe fn ferror(stream: NonNull<File>) -> Errno {
+pub unsafe fn ferror(stream: NonNull<File>) -> Option<Errno> {
     let stream = stream.as_ptr();
-    unsafe { Errno::from_int((*stream).error) }
+    let error = unsafe { (*stream).error };
+    (error != 0).then(|| Errno::from_int(error))
 }
 
-/// Output single extended-ASCII character. Note that while this function accepts an integer, it
-/// will panic with any input greater than 0xFF.
+/// Print a description of error number `errnum` to stderr, prefixed by `s` and a colon if `s` is
+/// non-empty
+///
+/// # C Signature
+///
+/// `void perror(const char *s);`
+pub fn perror(s: Option<&CStr>, errnum: c_int) -> Result<(), Errno> {
+    let mut buf = [0; 64];
+    let message = crate::string::strerror_r_gnu(errnum, &mut buf);
+    let (prefix, separator): (&[u8], &[u8]) = match s.filter(|s| !s.is_empty()) {
+        Some(s) => (s.to_bytes(), b": "),
+        None => (b"", b""),
+    };
+    let mut iov = [prefix, separator, message.to_bytes(), b"\n"].map(IoVec::from);
+    unsafe { write_all(Descriptor::stderr().0, &mut iov) }
+}
+
+/// Output single extended-ASCII character. `c` is converted to an unsigned char
 ///
 /// # C Signature
 ///
@@ -99,9 +142,7 @@ pub unsafe fn ferror(stream: NonNull<File>) -> Errno {
 ///
 /// The character written as an unsigned char cast to int, or EOF on error
 pub fn putchar(c: c_int) -> Result<c_int, Errno> {
-    let c: c_char = c
-        .try_into()
-        .expect("Argument to `putchar` must be a valid C character");
+    let c = c as u8;
 
     unsafe {
         crate::unistd::write(1, ptr::from_ref(&c) as *const c_void, 1)?;
@@ -110,8 +151,7 @@ pub fn putchar(c: c_int) -> Result<c_int, Errno> {
     Ok(c.into())
 }
 
-/// Output single extended-ASCII character. Note that while this function accepts an integer, it
-/// will panic with any input greater than 0xFF.
+/// Output single extended-ASCII character. `c` is converted to an unsigned char
 ///
 /// # C Signature
 ///
@@ -125,46 +165,47 @@ pub fn putchar(c: c_int) -> Result<c_int, Errno> {
 ///
 /// `stream` must be a valid stream opened with [fopen]
 pub unsafe fn fputc(c: c_int, stream: NonNull<File>) -> Result<c_int, Errno> {
-    let c: c_char = c
-        .try_into()
-        .expect("Argument to `fputc` must be a valid C character");
-
-    let stream = stream.as_ptr();
-    let fd = unsafe { (*stream).fd };
+    let c = c as u8;
 
-    unsafe {
-        crate::unistd::write(fd.0, ptr::from_ref(&c) as *const c_void, 1)?;
-    }
+    let stream = unsafe { &mut *stream.as_ptr() };
+    let res = stream.write(&[c]);
+    stream.track(res)?;
+    stream.offset += 1;
 
     Ok(c.into())
 }
 
 /// Get one C character from stdin
+///
+/// # Returns
+///
+/// The character read as an unsigned char cast to int, or [EOF] on end of file
 pub fn getchar() -> Result<c_int, Errno> {
-    let mut c: c_char = 0;
+    let mut c: u8 = 0;
 
-    unsafe {
-        crate::unistd::read(0, ptr::from_mut(&mut c) as *mut c_void, 1)?;
-    }
+    let count = unsafe { crate::unistd::read(0, ptr::from_mut(&mut c) as *mut c_void, 1)? };
 
-    Ok(c.into())
+    Ok(if count == 0 { EOF } else { c.into() })
 }
 
-/// Get one C character from file stream
+/// Get one C character from file stream, setting the end-of-file indicator on end of file
+///
+/// # Returns
+///
+/// The character read as an unsigned char cast to int, or [EOF] on end of file
 ///
 /// # Safety
 ///
 /// Same as [fread]
 pub unsafe fn getc(stream: NonNull<File>) -> Result<c_int, Errno> {
-    let stream = stream.as_ptr();
-    let fd = unsafe { (*stream).fd };
+    let stream = unsafe { &mut *stream.as_ptr() };
+    let mut c: u8 = 0;
 
-    let mut c: c_char = 0;
-
-    unsafe {
-        crate::unistd::read(fd.0, ptr::from_mut(&mut c) as *mut c_void, 1)?;
+    let res = stream.read(slice::from_mut(&mut c));
+    if stream.track(res)? == 0 {
+        return Ok(EOF);
     }
-
+    stream.offset += 1;
     Ok(c.into())
 }
 
@@ -172,7 +213,7 @@ pub unsafe fn getc(stream: NonNull<File>) -> Result<c_int, Errno> {
 ///
 /// # Returns
 ///
-/// The number of characters outputed, or a negative number on error
+/// The number of characters outputed
 ///
 /// # Safety
 ///
@@ -191,10 +232,17 @@ pub unsafe fn printf(fmt: &CStr, args: VaListImpl) -> Result<c_int, Errno> {
 ///
 /// Additionally, `stream` must not overlap with `fmt` or any argument
 pub unsafe fn fprintf(stream: NonNull<File>, fmt: &CStr, args: VaListImpl) -> Result<c_int, Errno> {
-    unsafe { printf_impl((*stream.as_ptr()).fd, fmt, args) }
+    let stream = unsafe { &mut *stream.as_ptr() };
+    let res = unsafe { printf_impl(&mut *stream, fmt, args) };
+    stream.track(res)
 }
 
-/// Like [printf()] but writes to a string
+/// Like [printf()] but writes to a string, truncating the output to fit in `n` bytes including
+/// the terminating null byte
+///
+/// # Returns
+///
+/// The number of characters that would have been written had `n` been large enough
 ///
 /// # Safety
 ///
@@ -212,6 +260,18 @@ pub unsafe fn snprintf(
     unsafe { printf_impl(writer, fmt, args) }
 }
 
+/// Remove the file or empty directory `pathname`
+///
+/// # C Signature
+///
+/// `int remove(const char *pathname);`
+pub fn remove(pathname: &CStr) -> Result<(), Errno> {
+    match crate::unistd::unlink(pathname) {
+        Err(Errno::EISDIR) => crate::unistd::rmdir(pathname),
+        result => result,
+    }
+}
+
 /// Open a file
 pub fn fopen(pathname: &CStr, mode: &CStr) -> Result<NonNull<File>, Errno> {
     use crate::unistd::types::{ModeFlags, OpenFlags};
@@ -233,13 +293,54 @@ pub fn fopen(pathname: &CStr, mode: &CStr) -> Result<NonNull<File>, Errno> {
     let file_ptr = malloc(mem::size_of::<File>())?.cast();
 
     unsafe {
-        *(file_ptr.as_ptr()) = File::from_desc(Descriptor(fd));
+        let mut file = File::from_desc(Descriptor(fd));
+        file.buf_size = buffer_size(fd);
+        *(file_ptr.as_ptr()) = file;
+        file::remember(file_ptr);
+    }
+
+    Ok(file_ptr)
+}
+
+/// Open a stream on the file descriptor `fd`, whose access mode should match `mode`
+///
+/// # Returns
+///
+/// `EINVAL` if `mode` doesn't start with `r`, `w` or `a`
+pub fn fdopen(fd: c_int, mode: &CStr) -> Result<NonNull<File>, Errno> {
+    let mode = mode.to_bytes();
+    if !matches!(mode.first(), Some(b'r' | b'w' | b'a')) {
+        return Err(Errno::EINVAL);
+    }
+
+    let file_ptr = malloc(mem::size_of::<File>())?.cast();
+
+    unsafe {
+        let mut file = File::from_desc(Descriptor(fd));
+        file.buffer_writes = mode[0] != b'r' || mode.contains(&b'+');
+        file.buf_size = buffer_size(fd);
+        *(file_ptr.as_ptr()) = file;
+        file::remember(file_ptr);
     }
 
     Ok(file_ptr)
 }
 
-/// Read a file into `ptr`
+// The file's preferred I/O size, or BUFSIZ if it doesn't have one
+fn buffer_size(fd: c_int) -> usize {
+    crate::unistd::fstat(fd)
+        .ok()
+        .and_then(|stat| usize::try_from(stat.blksize).ok())
+        .filter(|size| *size > 0)
+        .unwrap_or(BUFSIZ)
+}
+
+/// Read up to `nmemb` items of `size` bytes from a file into `ptr`, setting the end-of-file
+/// indicator on end of file and the error indicator on error
+///
+/// # Returns
+///
+/// The number of whole items read
 ///
 /// # Safety
 ///
@@ -252,23 +353,25 @@ pub unsafe fn fread(
     nmemb: usize,
     file: NonNull<File>,
 ) -> Result<usize, Errno> {
-    let file = file.as_ptr();
-
-    let fd = unsafe { (*file).fd };
-
-    let count = size.checked_mul(nmemb).ok_or(Errno::CloysterOverflow)?;
+    let file = unsafe { &mut *file.as_ptr() };
 
-    let val = unsafe { crate::unistd::read(fd.0, ptr.as_ptr() as *mut c_void, count)? };
-    let val = u64::try_from(val)?;
-
-    unsafe {
-        (*file).offset += val;
+    let count = file.track(size.checked_mul(nmemb).ok_or(Errno::CloysterOverflow))?;
+    if count == 0 {
+        return Ok(0);
     }
+    let res = file.read(unsafe { slice::from_raw_parts_mut(ptr.as_ptr(), count) });
+    let val = file.track(res)?;
+
+    file.offset += u64::try_from(val)?;
 
-    Ok(val.try_into()?)
+    Ok(val / size)
 }
 
-/// Write to file from `ptr`
+/// Write `nmemb` items of `size` bytes from `ptr` to a file, setting the error indicator on error
+///
+/// # Returns
+///
+/// `nmemb`, once everything has been written or buffered
 ///
 /// # Safety
 ///
@@ -281,20 +384,19 @@ pub unsafe fn fwrite(
     nmemb: usize,
     file: NonNull<File>,
 ) -> Result<usize, Errno> {
-    let file = file.as_ptr();
-
-    let fd = unsafe { (*file).fd };
+    let file = unsafe { &mut *file.as_ptr() };
 
-    let count = size.checked_mul(nmemb).ok_or(Errno::CloysterOverflow)?;
+    let count = file.track(size.checked_mul(nmemb).ok_or(Errno::CloysterOverflow))?;
+    if count == 0 {
+        return Ok(0);
+    }
 
-    let val = unsafe { crate::unistd::write(fd.0, ptr as *mut c_void, count)? };
-    let val = u64::try_from(val)?;
+    let res = file.write(unsafe { slice::from_raw_parts(ptr, count) });
+    file.track(res)?;
 
-    unsafe {
-        (*file).offset += val;
-    }
+    file.offset += u64::try_from(count)?;
 
-    Ok(val.try_into()?)
+    Ok(nmemb)
 }
 
 /// Reposition a stream
@@ -305,6 +407,10 @@ pub unsafe fn fwrite(
 pub unsafe fn fseek(stream: NonNull<File>, offset: c_long, whence: c_int) -> Result<(), Errno> {
     let stream = stream.as_ptr();
     let fd = unsafe { (*stream).fd };
+    unsafe {
+        (*stream).flush()?;
+        (*stream).drop_input();
+    }
 
     let val = crate::unistd::lseek(fd.0, offset.try_into()?, whence)?;
 
@@ -331,9 +437,178 @@ pub unsafe fn ftell(file: NonNull<File>) -> Result<c_long, Errno> {
 /// `file` must have been previously allocated with [fopen]
 pub unsafe fn fclose(file: NonNull<File>) -> Result<(), Errno> {
     unsafe {
-        crate::unistd::close((*file.as_ptr()).fd.0)?;
+        file::forget(file);
+        let stream = &mut *file.as_ptr();
+        // The stream is closed even if its output can't be written
+        let flushed = stream.flush();
+        let released = stream.release();
+        let closed = crate::unistd::close(stream.fd.0);
         free(file.cast())?;
+        flushed.and(released).and(closed.map(|_| ()))
     }
+}
 
-    Ok(())
+/// Write out the output buffered for `stream`, or for every open stream if it's `None`
+///
+/// # C Signature
+///
+/// `int fflush(FILE *stream);`
+///
+/// # Safety
+///
+/// `stream` must have been previously allocated with [fopen] or [fdopen], or be one of the
+/// standard streams
+pub unsafe fn fflush(stream: Option<NonNull<File>>) -> Result<(), Errno> {
+    match stream {
+        Some(stream) => {
+            let stream = unsafe { &mut *stream.as_ptr() };
+            let res = stream.flush();
+            stream.track(res)
+        }
+        None => unsafe { file::flush_all() },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unistd::types::{ModeFlags, OpenFlags, SEEK_CUR, off_t};
+
+    fn open_manifest() -> File {
+        let fd = unsafe {
+            crate::unistd::open(c"Cargo.toml", OpenFlags::O_RDONLY, ModeFlags::default()).unwrap()
+        };
+        File::from_desc(Descriptor(fd))
+    }
+
+    #[test]
+    fn read_to_eof() {
+        let mut file = open_manifest();
+        let stream = NonNull::from(&mut file);
+        unsafe {
+            let mut buf = [0u8; 7];
+            assert_eq!(fread(NonNull::from(&mut buf).cast(), 3, 2, stream), Ok(2));
+            assert_eq!(&buf[..6], b"[packa");
+
+            while getc(stream).unwrap() != EOF {}
+            assert!(feof(stream));
+            assert_eq!(ferror(stream), None);
+            assert_eq!(fread(NonNull::from(&mut buf).cast(), 1, 7, stream), Ok(0));
+
+            clearerr(stream);
+            assert!(!feof(stream));
+            crate::unistd::close(file.fd.0).unwrap();
+        }
+    }
+
+    #[test]
+    fn remove_files_and_directories() {
+        let dir = std::format!("/tmp/shellder_remove_test_{}", std::process::id());
+        let dir = std::ffi::CString::new(dir).unwrap();
+        let file = std::ffi::CString::new(std::format!("{}/file", dir.to_str().unwrap())).unwrap();
+        crate::unistd::mkdir(&dir, ModeFlags::default()).unwrap();
+        let fd = unsafe {
+            crate::unistd::open(
+                &file,
+                OpenFlags::O_WRONLY | OpenFlags::O_CREAT,
+                ModeFlags::default(),
+            )
+            .unwrap()
+        };
+        crate::unistd::close(fd).unwrap();
+
+        assert_eq!(remove(&dir), Err(Errno::ENOTEMPTY));
+        remove(&file).unwrap();
+        remove(&dir).unwrap();
+        assert_eq!(remove(&dir), Err(Errno::ENOENT));
+    }
+
+    #[test]
+    fn write_to_read_only() {
+        let mut file = open_manifest();
+        let stream = NonNull::from(&mut file);
+        unsafe {
+            assert_eq!(fwrite(b"x".as_ptr(), 1, 1, stream), Err(Errno::EBADF));
+            assert_eq!(ferror(stream), Some(Errno::EBADF));
+            clearerr(stream);
+            assert_eq!(ferror(stream), None);
+
+            assert_eq!(fputc(0xff, stream), Err(Errno::EBADF));
+            assert_eq!(ferror(stream), Some(Errno::EBADF));
+            crate::unistd::close(file.fd.0).unwrap();
+        }
+    }
+
+    #[test]
+    fn buffers_writes() {
+        let [read_end, write_end] = crate::unistd::pipe2(OpenFlags::O_NONBLOCK).unwrap();
+        let stream = fdopen(write_end, c"w").unwrap();
+        let mut buf = [0u8; 16];
+        let read = |buf: &mut [u8]| unsafe {
+            crate::unistd::read(read_end, buf.as_mut_ptr().cast(), buf.len())
+        };
+        unsafe {
+            assert_eq!(fwrite(b"abc".as_ptr(), 1, 3, stream), Ok(3));
+            assert_eq!(fputc(b'd'.into(), stream), Ok(b'd'.into()));
+            assert_eq!(read(&mut buf), Err(Errno::EAGAIN));
+            fflush(Some(stream)).unwrap();
+            assert_eq!(read(&mut buf), Ok(4));
+            assert_eq!(&buf[..4], b"abcd");
+
+            // Too big for the buffer, so it's written with what's already there
+            let big = [b'x'; BUFSIZ];
+            fputs(c"ab", stream).unwrap();
+            assert_eq!(fwrite(big.as_ptr(), 1, BUFSIZ, stream), Ok(BUFSIZ));
+            assert_eq!(read(&mut buf), Ok(16));
+            assert_eq!(&buf[..4], b"abxx");
+            fclose(stream).unwrap();
+            crate::unistd::close(read_end).unwrap();
+        }
+    }
+
+    #[test]
+    fn fopen_uses_block_size() {
+        let file = fopen(c"Cargo.toml", c"r").unwrap();
+        unsafe {
+            let stat = crate::unistd::fstat(file.as_ref().fd.0).unwrap();
+            assert_eq!(
+                file.as_ref().buf_size,
+                usize::try_from(stat.blksize).unwrap()
+            );
+            fclose(file).unwrap();
+        }
+
+        let [read_end, write_end] = crate::unistd::pipe2(OpenFlags::empty()).unwrap();
+        let stream = fdopen(write_end, c"w").unwrap();
+        unsafe {
+            let stat = crate::unistd::fstat(write_end).unwrap();
+            assert_eq!(
+                stream.as_ref().buf_size,
+                usize::try_from(stat.blksize).unwrap()
+            );
+            fclose(stream).unwrap();
+        }
+        crate::unistd::close(read_end).unwrap();
+    }
+
+    #[test]
+    fn buffers_reads() {
+        let file = fopen(c"Cargo.toml", c"r").unwrap();
+        unsafe {
+            let fd = file.as_ref().fd.0;
+            let size = crate::unistd::fstat(fd).unwrap().size;
+            assert_eq!(getc(file), Ok(b'['.into()));
+            // The manifest fits in the buffer, so it was read all at once
+            assert_eq!(crate::unistd::lseek(fd, 0, SEEK_CUR), Ok(size as off_t));
+            assert_eq!(ftell(file), Ok(1));
+
+            // Writing would drop the unread input and move the offset back to the reader
+            (*file.as_ptr()).drop_input();
+            assert_eq!(crate::unistd::lseek(fd, 0, SEEK_CUR), Ok(1));
+            let mut buf = [0u8; 7];
+            assert_eq!(fread(NonNull::from(&mut buf).cast(), 1, 7, file), Ok(7));
+            assert_eq!(&buf, b"package");
+            fclose(file).unwrap();
+        }
+    }
 }
@@ -70,6 +70,9 @@ pub(crate) struct CStringWriter {
 
 impl CStringWriter {
     pub(crate) unsafe fn new(ptr: NonNull<c_char>, size: usize) -> Self {
+        if size > 0 {
+            unsafe { *ptr.as_ptr() = 0 };
+        }
         Self {
             ptr: ptr.as_ptr(),
             index: 0,
@@ -79,6 +82,7 @@ impl CStringWriter {
 }
 
 impl Cout for CStringWriter {
+    // Characters that don't fit are silently dropped, like snprintf() requires
     fn put_char(&mut self, c: c_char) -> Result<(), Errno> {
         if self.index < self.size.saturating_sub(1) {
             unsafe {
@@ -86,21 +90,22 @@ impl Cout for CStringWriter {
                 *(self.ptr.wrapping_byte_add(self.index + 1)) = 0;
             }
             self.index += 1;
-            Ok(())
-        } else {
-            Err(Errno::CloysterBufferOverflow)
         }
+        Ok(())
     }
 }
 
 struct CountingCout<T: Cout> {
     inner: T,
     count: usize,
+    // `fmt::Error` carries no information, so keep the underlying error around
+    error: Option<Errno>,
 }
 
 impl<T: Cout> fmt::Write for CountingCout<T> {
     fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
-        if self.put_cstr(s.as_bytes()).is_err() {
+        if let Err(err) = self.put_cstr(s.as_bytes()) {
+            self.error = Some(err);
             Err(fmt::Error)
         } else {
             Ok(())
@@ -124,7 +129,11 @@ impl<T: Cout> Cout for CountingCout<T> {
 
 impl<T: Cout> From<T> for CountingCout<T> {
     fn from(c: T) -> Self {
-        Self { inner: c, count: 0 }
+        Self {
+            inner: c,
+            count: 0,
+            error: None,
+        }
     }
 }
 
@@ -198,7 +207,8 @@ pub(crate) unsafe fn printf_impl(
             cout.put_cstr(&fmt[last..idx])?;
 
             // Safe IFF previous safety guarantees hold up
-            let changed = unsafe { parse_placeholder(&mut cout, &fmt[idx..], &mut args)? };
+            let changed = unsafe { parse_placeholder(&mut cout, &fmt[idx..], &mut args) }
+                .map_err(|err| cout.error.take().unwrap_or(err))?;
 
             // Parsing the placeholder moves us over some amount of chars
             assert!(changed > 0);
@@ -212,7 +222,7 @@ pub(crate) unsafe fn printf_impl(
 
     cout.put_cstr(&fmt[last..len])?;
 
-    Ok(cout.count.try_into().unwrap())
+    Ok(cout.count.try_into()?)
 }
 
 #[cfg(test)]
@@ -264,6 +274,14 @@ mod tests {
         }
     }
 
+    struct ClosedCout;
+
+    impl Cout for ClosedCout {
+        fn put_char(&mut self, _c: c_char) -> Result<(), Errno> {
+            Err(Errno::EBADF)
+        }
+    }
+
     fn check(res: &str, fmt: &CStr, va: impl Into<MockVaList>) {
         let mut string = String::new();
         let length = unsafe { printf_impl(&mut string, fmt, va.into()).unwrap() };
@@ -290,4 +308,33 @@ mod tests {
             MockVaList::new().with_str(c"hello").with(41),
         );
     }
+
+    #[test]
+    fn truncated() {
+        let mut buf = [0x7f as c_char; 4];
+        let writer = unsafe { CStringWriter::new(NonNull::from(&mut buf).cast(), buf.len()) };
+        let length =
+            unsafe { printf_impl(writer, c"[%d]", MockVaList::new().with(12345)).unwrap() };
+        assert_eq!(length, 7);
+        assert_eq!(unsafe { CStr::from_ptr(buf.as_ptr()) }, c"[12");
+
+        let writer = unsafe { CStringWriter::new(NonNull::from(&mut buf).cast(), 0) };
+        assert_eq!(
+            unsafe { printf_impl(writer, c"abc", MockVaList::new()) },
+            Ok(3)
+        );
+        assert_eq!(unsafe { CStr::from_ptr(buf.as_ptr()) }, c"[12");
+    }
+
+    #[test]
+    fn output_error() {
+        assert_eq!(
+            unsafe { printf_impl(ClosedCout, c"x", MockVaList::new()) },
+            Err(Errno::EBADF)
+        );
+        assert_eq!(
+            unsafe { printf_impl(ClosedCout, c"%d", MockVaList::new().with(5)) },
+            Err(Errno::EBADF)
+        );
+    }
 }
@@ -0,0 +1,267 @@
+//! Cryptographically secure random numbers, from a ChaCha20 keystream seeded by the kernel
+use crate::{
+    types::pid_t,
+    unistd::{getentropy, getpid},
+};
+use spin::Mutex;
+
+// Key and nonce, in bytes
+const SEED_LEN: usize = 32 + 12;
+const BLOCK_LEN: usize = 64;
+// Keystream is generated this many bytes at a time
+const BUFFER_LEN: usize = 16 * BLOCK_LEN;
+// Mix in fresh entropy from the kernel after handing out this many bytes
+const RESEED_INTERVAL: usize = 1600000;
+
+// "expand 32-byte k"
+const CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+// The ChaCha20 block function, as laid out in RFC 7539 (32-bit counter, 96-bit nonce)
+fn chacha20_block(key: &[u8; 32], counter: u32, nonce: &[u8; 12], out: &mut [u8; BLOCK_LEN]) {
+    let word = |bytes: &[u8]| u32::from_le_bytes(bytes.try_into().unwrap());
+
+    let mut input = [0; 16];
+    input[..4].copy_from_slice(&CONSTANTS);
+    for (i, chunk) in key.chunks_exact(4).enumerate() {
+        input[4 + i] = word(chunk);
+    }
+    input[12] = counter;
+    for (i, chunk) in nonce.chunks_exact(4).enumerate() {
+        input[13 + i] = word(chunk);
+    }
+
+    let mut state = input;
+    for _ in 0..10 {
+        quarter_round(&mut state, 0, 4, 8, 12);
+        quarter_round(&mut state, 1, 5, 9, 13);
+        quarter_round(&mut state, 2, 6, 10, 14);
+        quarter_round(&mut state, 3, 7, 11, 15);
+        quarter_round(&mut state, 0, 5, 10, 15);
+        quarter_round(&mut state, 1, 6, 11, 12);
+        quarter_round(&mut state, 2, 7, 8, 13);
+        quarter_round(&mut state, 3, 4, 9, 14);
+    }
+
+    for (i, chunk) in out.chunks_exact_mut(4).enumerate() {
+        chunk.copy_from_slice(&state[i].wrapping_add(input[i]).to_le_bytes());
+    }
+}
+
+// Fast key erasure: every refill of the buffer starts with the key and nonce for the next refill,
+// which are wiped as soon as they're read, so output that's already been handed out can't be
+// reconstructed from the state
+struct Arc4Random {
+    key: [u8; 32],
+    nonce: [u8; 12],
+    buffer: [u8; BUFFER_LEN],
+    // Unused keystream is at the end of the buffer
+    available: usize,
+    until_reseed: usize,
+    // Process the state was seeded in, so a forked child doesn't repeat its parent's output
+    pid: pid_t,
+}
+
+impl Arc4Random {
+    fn new() -> Self {
+        let mut state = Self {
+            key: [0; 32],
+            nonce: [0; 12],
+            buffer: [0; BUFFER_LEN],
+            available: 0,
+            until_reseed: 0,
+            pid: 0,
+        };
+        state.reseed();
+        state
+    }
+
+    fn entropy() -> [u8; SEED_LEN] {
+        let mut seed = [0; SEED_LEN];
+        getentropy(&mut seed).expect("Could not get entropy from the kernel");
+        seed
+    }
+
+    fn reseed(&mut self) {
+        self.rekey(Some(&Self::entropy()));
+        // The rest of the buffer came from the old key, which may be all zeroes, so it's thrown
+        // away. The next refill uses the key made from the entropy
+        self.buffer.fill(0);
+        self.available = 0;
+        self.until_reseed = RESEED_INTERVAL;
+        self.pid = getpid();
+    }
+
+    // Refill the buffer, and take the next key and nonce from the start of it, optionally mixed
+    // with fresh entropy
+    fn rekey(&mut self, entropy: Option<&[u8; SEED_LEN]>) {
+        for (counter, block) in self.buffer.chunks_exact_mut(BLOCK_LEN).enumerate() {
+            chacha20_block(
+                &self.key,
+                counter as u32,
+                &self.nonce,
+                block.try_into().unwrap(),
+            );
+        }
+        if let Some(entropy) = entropy {
+            for (byte, extra) in self.buffer.iter_mut().zip(entropy) {
+                *byte ^= extra;
+            }
+        }
+
+        self.key.copy_from_slice(&self.buffer[..32]);
+        self.nonce.copy_from_slice(&self.buffer[32..SEED_LEN]);
+        self.buffer[..SEED_LEN].fill(0);
+        self.available = BUFFER_LEN - SEED_LEN;
+    }
+
+    fn fill(&mut self, mut out: &mut [u8]) {
+        if self.pid != getpid() {
+            *self = Self::new();
+        }
+        if self.until_reseed <= out.len() {
+            self.reseed();
+        } else {
+            self.until_reseed -= out.len();
+        }
+
+        while !out.is_empty() {
+            if self.available == 0 {
+                self.rekey(None);
+            }
+            let count = out.len().min(self.available);
+            let start = BUFFER_LEN - self.available;
+            let keystream = &mut self.buffer[start..start + count];
+            out[..count].copy_from_slice(keystream);
+            keystream.fill(0);
+            self.available -= count;
+            out = &mut out[count..];
+        }
+    }
+}
+
+static ARC4RANDOM: Mutex<Option<Arc4Random>> = Mutex::new(None);
+
+/// Fill `buf` with cryptographically secure random bytes
+///
+/// The generator is seeded from the kernel on first use, periodically reseeded, and reseeded from
+/// scratch in the child after a `fork()`
+///
+/// # C Signature
+///
+/// `void arc4random_buf(void *buf, size_t nbytes);`
+///
+/// # Panics
+///
+/// If the kernel can't provide entropy
+pub fn arc4random_buf(buf: &mut [u8]) {
+    ARC4RANDOM
+        .lock()
+        .get_or_insert_with(Arc4Random::new)
+        .fill(buf);
+}
+
+/// Get a cryptographically secure random 32-bit number
+///
+/// # C Signature
+///
+/// `uint32_t arc4random(void);`
+pub fn arc4random() -> u32 {
+    let mut bytes = [0; 4];
+    arc4random_buf(&mut bytes);
+    u32::from_ne_bytes(bytes)
+}
+
+/// Get a cryptographically secure random number less than `upper_bound`, without modulo bias
+///
+/// # C Signature
+///
+/// `uint32_t arc4random_uniform(uint32_t upper_bound);`
+///
+/// # Returns
+///
+/// 0 if `upper_bound` is less than 2
+pub fn arc4random_uniform(upper_bound: u32) -> u32 {
+    if upper_bound < 2 {
+        return 0;
+    }
+    // Values below `min` would make the low residues more likely, so reject them
+    let min = upper_bound.wrapping_neg() % upper_bound;
+    loop {
+        let value = arc4random();
+        if value >= min {
+            return value % upper_bound;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chacha20_test_vector() {
+        // RFC 7539, section 2.3.2
+        let key = core::array::from_fn(|i| i as u8);
+        let nonce = [0, 0, 0, 0x09, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+        let mut block = [0; BLOCK_LEN];
+        chacha20_block(&key, 1, &nonce, &mut block);
+        let expected = [
+            0x10, 0xf1, 0xe7, 0xe4, 0xd1, 0x3b, 0x59, 0x15, 0x50, 0x0f, 0xdd, 0x1f, 0xa3, 0x20,
+            0x71, 0xc4, 0xc7, 0xd1, 0xf4, 0xc7, 0x33, 0xc0, 0x68, 0x03, 0x04, 0x22, 0xaa, 0x9a,
+            0xc3, 0xd4, 0x6c, 0x4e, 0xd2, 0x82, 0x64, 0x46, 0x07, 0x9f, 0xaa, 0x09, 0x14, 0xc2,
+            0xd7, 0x05, 0xd9, 0x8b, 0x02, 0xa2, 0xb5, 0x12, 0x9c, 0xd1, 0xde, 0x16, 0x4e, 0xb9,
+            0xcb, 0xd0, 0x83, 0xe8, 0xa2, 0x50, 0x3c, 0x4e,
+        ];
+        assert_eq!(block, expected);
+    }
+
+    #[test]
+    fn buffer_is_consumed_and_erased() {
+        let mut state = Arc4Random::new();
+        let mut first = [0; 100];
+        let mut second = [0; BUFFER_LEN];
+        state.fill(&mut first);
+        state.fill(&mut second);
+        assert_ne!(first, [0; 100]);
+        assert_ne!(first[..], second[..100]);
+        // Everything handed out has been wiped
+        let available = state.available;
+        assert!(
+            state.buffer[..BUFFER_LEN - available]
+                .iter()
+                .all(|b| *b == 0)
+        );
+    }
+
+    #[test]
+    fn generators_differ() {
+        let mut first = [0; 32];
+        let mut second = [0; 32];
+        Arc4Random::new().fill(&mut first);
+        Arc4Random::new().fill(&mut second);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn uniform() {
+        assert_eq!(arc4random_uniform(0), 0);
+        assert_eq!(arc4random_uniform(1), 0);
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            seen[arc4random_uniform(6) as usize] = true;
+        }
+        assert!(seen.iter().all(|seen| *seen));
+        assert!((0..1000).all(|_| arc4random_uniform(0x80000001) < 0x80000001));
+    }
+}
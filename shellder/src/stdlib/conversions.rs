@@ -1,29 +1,557 @@
 use crate::errno::Errno;
 use core::{
-    ffi::{CStr, c_double, c_int, c_long, c_longlong},
+    ffi::{CStr, c_double, c_float, c_int, c_long, c_longlong, c_ulong, c_ulonglong},
     str::FromStr,
 };
 
+/// Outcome of a `strto*` conversion
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Conversion<T> {
+    /// The converted value, clamped to the range of `T` on overflow
+    pub value: T,
+    /// Number of bytes consumed. 0 if no conversion could be performed
+    pub consumed: usize,
+    /// `ERANGE` if the value was out of range, or `EINVAL` if the base was invalid
+    pub error: Option<Errno>,
+}
+
+impl<T: Default> Conversion<T> {
+    fn none(error: Option<Errno>) -> Self {
+        Self {
+            value: T::default(),
+            consumed: 0,
+            error,
+        }
+    }
+}
+
+// Whitespace as defined by `isspace()` in the C locale
+fn skip_space(s: &[u8]) -> usize {
+    s.iter()
+        .position(|c| !matches!(c, b' ' | b'\t' | b'\n' | b'\x0b' | b'\x0c' | b'\r'))
+        .unwrap_or(s.len())
+}
+
+// Optional sign, returning whether the number is negative and the length of the sign
+fn sign(s: &[u8]) -> (bool, usize) {
+    match s.first() {
+        Some(b'-') => (true, 1),
+        Some(b'+') => (false, 1),
+        _ => (false, 0),
+    }
+}
+
+fn digit(c: Option<&u8>, base: u32) -> Option<u32> {
+    (*c? as char).to_digit(base)
+}
+
+fn has_hex_prefix(s: &[u8]) -> bool {
+    s.len() > 2 && s[0] == b'0' && s[1] | 0x20 == b'x' && digit(s.get(2), 16).is_some()
+}
+
+// Magnitude of an integer, saturating just beyond `u64::MAX`
+struct Integer {
+    negative: bool,
+    magnitude: u128,
+    consumed: usize,
+}
+
+fn parse_integer(s: &[u8], base: c_int) -> Result<Option<Integer>, Errno> {
+    if base == 1 || !(0..=36).contains(&base) {
+        return Err(Errno::EINVAL);
+    }
+
+    let mut idx = skip_space(s);
+    let (negative, len) = sign(&s[idx..]);
+    idx += len;
+
+    let mut base = base as u32;
+    if (base == 0 || base == 16) && has_hex_prefix(&s[idx..]) {
+        base = 16;
+        idx += 2;
+    } else if base == 0 {
+        base = if s.get(idx) == Some(&b'0') { 8 } else { 10 };
+    }
+
+    let start = idx;
+    let mut magnitude: u128 = 0;
+    while let Some(d) = digit(s.get(idx), base) {
+        magnitude = (magnitude * u128::from(base) + u128::from(d)).min(u128::from(u64::MAX) + 1);
+        idx += 1;
+    }
+
+    Ok((idx > start).then_some(Integer {
+        negative,
+        magnitude,
+        consumed: idx,
+    }))
+}
+
+macro_rules! signed_conversion {
+    ($(#[$meta:meta])* $name:ident -> $type:ty) => {
+        $(#[$meta])*
+        pub fn $name(nptr: &CStr, base: c_int) -> Conversion<$type> {
+            let int = match parse_integer(nptr.to_bytes(), base) {
+                Ok(Some(int)) => int,
+                Ok(None) => return Conversion::none(None),
+                Err(err) => return Conversion::none(Some(err)),
+            };
+            let value = if int.negative {
+                -(int.magnitude as i128)
+            } else {
+                int.magnitude as i128
+            };
+            let (value, error) = match <$type>::try_from(value) {
+                Ok(value) => (value, None),
+                Err(_) if int.negative => (<$type>::MIN, Some(Errno::ERANGE)),
+                Err(_) => (<$type>::MAX, Some(Errno::ERANGE)),
+            };
+            Conversion {
+                value,
+                consumed: int.consumed,
+                error,
+            }
+        }
+    };
+}
+
+macro_rules! unsigned_conversion {
+    ($(#[$meta:meta])* $name:ident -> $type:ty) => {
+        $(#[$meta])*
+        pub fn $name(nptr: &CStr, base: c_int) -> Conversion<$type> {
+            let int = match parse_integer(nptr.to_bytes(), base) {
+                Ok(Some(int)) => int,
+                Ok(None) => return Conversion::none(None),
+                Err(err) => return Conversion::none(Some(err)),
+            };
+            let (value, error) = match <$type>::try_from(int.magnitude) {
+                // C negates the unsigned value
+                Ok(value) if int.negative => (value.wrapping_neg(), None),
+                Ok(value) => (value, None),
+                Err(_) => (<$type>::MAX, Some(Errno::ERANGE)),
+            };
+            Conversion {
+                value,
+                consumed: int.consumed,
+                error,
+            }
+        }
+    };
+}
+
+signed_conversion!(
+    /// Convert the initial part of a string to a long, in `base` 2 to 36, or 0 to detect the base
+    /// from a `0x` (hexadecimal) or `0` (octal) prefix
+    strtol -> c_long
+);
+signed_conversion!(
+    /// Convert the initial part of a string to a long long. See [strtol]
+    strtoll -> c_longlong
+);
+unsigned_conversion!(
+    /// Convert the initial part of a string to an unsigned long. See [strtol]
+    strtoul -> c_ulong
+);
+unsigned_conversion!(
+    /// Convert the initial part of a string to an unsigned long long. See [strtol]
+    strtoull -> c_ulonglong
+);
+
+// Binary floating point format
+trait Float: FromStr + Default + Copy {
+    const MANTISSA_BITS: u32;
+    const MAX_EXP: i64;
+    const INFINITY: Self;
+    const NAN: Self;
+    fn from_bits64(bits: u64) -> Self;
+    fn negate(self) -> Self;
+    fn is_out_of_range(self, nonzero: bool) -> bool;
+}
+
+impl Float for c_double {
+    const MANTISSA_BITS: u32 = 52;
+    const MAX_EXP: i64 = 1023;
+    const INFINITY: Self = Self::INFINITY;
+    const NAN: Self = Self::NAN;
+    fn from_bits64(bits: u64) -> Self {
+        Self::from_bits(bits)
+    }
+    fn negate(self) -> Self {
+        -self
+    }
+    fn is_out_of_range(self, nonzero: bool) -> bool {
+        self.is_infinite() || self.is_subnormal() || (nonzero && self == 0.0)
+    }
+}
+
+impl Float for c_float {
+    const MANTISSA_BITS: u32 = 23;
+    const MAX_EXP: i64 = 127;
+    const INFINITY: Self = Self::INFINITY;
+    const NAN: Self = Self::NAN;
+    fn from_bits64(bits: u64) -> Self {
+        Self::from_bits(bits as u32)
+    }
+    fn negate(self) -> Self {
+        -self
+    }
+    fn is_out_of_range(self, nonzero: bool) -> bool {
+        self.is_infinite() || self.is_subnormal() || (nonzero && self == 0.0)
+    }
+}
+
+// Round `mantissa * 2^exp` to the nearest `F`, ties to even. `sticky` is set if any nonzero bits
+// below `mantissa` were discarded. Returns the value and whether it's out of range
+fn compose<F: Float>(mantissa: u64, exp: i64, sticky: bool) -> (F, bool) {
+    if mantissa == 0 {
+        return (F::default(), false);
+    }
+
+    // Normalize, so the value is 1.xxx * 2^exp
+    let shift = mantissa.leading_zeros();
+    let mantissa = mantissa << shift;
+    let exp = exp + 63 - i64::from(shift);
+
+    let min_exp = 1 - F::MAX_EXP;
+    if exp > F::MAX_EXP {
+        return (F::INFINITY, true);
+    }
+
+    // Subnormals keep fewer bits
+    let tiny = exp < min_exp;
+    let keep = i64::from(F::MANTISSA_BITS) + 1 - if tiny { min_exp - exp } else { 0 };
+    let drop = 64 - keep;
+    if drop > 64 {
+        return (F::default(), true);
+    }
+
+    let (mut kept, rest) = if drop == 64 {
+        (0, mantissa)
+    } else {
+        (mantissa >> drop, mantissa << (64 - drop))
+    };
+    const HALF: u64 = 1 << 63;
+    if rest > HALF || (rest == HALF && (sticky || kept & 1 == 1)) {
+        kept += 1;
+    }
+    let inexact = rest != 0 || sticky;
+
+    // The implicit leading bit of `kept` carries into the exponent field, so rounding up to the
+    // next power of two (or from the largest subnormal to the smallest normal) just works
+    let bits = if tiny {
+        kept
+    } else {
+        (((exp + F::MAX_EXP - 1) as u64) << F::MANTISSA_BITS) + kept
+    };
+    let value = F::from_bits64(bits);
+    let overflow = bits >> F::MANTISSA_BITS >= (2 * F::MAX_EXP + 1) as u64;
+    (value, overflow || (tiny && inexact))
+}
+
+// Parse the digits of a hexadecimal float after the `0x` prefix, returning the value, whether it's
+// out of range, and the number of bytes consumed
+fn parse_hex_float<F: Float>(s: &[u8]) -> Option<(F, bool, usize)> {
+    let mut idx = 0;
+    let mut mantissa: u64 = 0;
+    let mut exp: i64 = 0;
+    let mut sticky = false;
+    let mut seen_digit = false;
+    let mut seen_point = false;
+
+    loop {
+        match s.get(idx) {
+            Some(b'.') if !seen_point => seen_point = true,
+            c => {
+                let Some(d) = digit(c, 16) else {
+                    break;
+                };
+                seen_digit = true;
+                if mantissa >> 60 == 0 {
+                    mantissa = mantissa << 4 | u64::from(d);
+                    if seen_point {
+                        exp -= 4;
+                    }
+                } else {
+                    sticky |= d != 0;
+                    if !seen_point {
+                        exp += 4;
+                    }
+                }
+            }
+        }
+        idx += 1;
+    }
+    if !seen_digit {
+        return None;
+    }
+
+    if let Some((exp_value, len)) = parse_exponent(&s[idx..], b'p') {
+        exp += exp_value;
+        idx += len;
+    }
+
+    let (value, out_of_range) = compose::<F>(mantissa, exp, sticky);
+    Some((value, out_of_range, idx))
+}
+
+// Parse an exponent introduced by `marker`, saturating far beyond any representable exponent
+fn parse_exponent(s: &[u8], marker: u8) -> Option<(i64, usize)> {
+    if s.first()? | 0x20 != marker {
+        return None;
+    }
+    let (negative, sign_len) = sign(&s[1..]);
+    let start = 1 + sign_len;
+    let len = s[start..].iter().take_while(|c| c.is_ascii_digit()).count();
+    if len == 0 {
+        return None;
+    }
+    let value = s[start..start + len]
+        .iter()
+        .fold(0i64, |acc, c| (acc * 10 + i64::from(c - b'0')).min(1 << 32));
+    Some((if negative { -value } else { value }, start + len))
+}
+
+// Length of a decimal float, and whether any of its significant digits are nonzero
+fn scan_decimal_float(s: &[u8]) -> Option<(usize, bool)> {
+    let int_len = s.iter().take_while(|c| c.is_ascii_digit()).count();
+    let mut len = int_len;
+    let mut digits = int_len;
+    if s.get(len) == Some(&b'.') {
+        let frac_len = s[len + 1..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .count();
+        digits += frac_len;
+        len += 1 + frac_len;
+    }
+    if digits == 0 {
+        return None;
+    }
+    let nonzero = s[..len].iter().any(|c| matches!(c, b'1'..=b'9'));
+
+    if let Some((_, exp_len)) = parse_exponent(&s[len..], b'e') {
+        len += exp_len;
+    }
+    Some((len, nonzero))
+}
+
+fn starts_with_ignore_case(s: &[u8], prefix: &[u8]) -> bool {
+    s.len() >= prefix.len() && s[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+fn strtof_impl<F: Float>(nptr: &CStr) -> Conversion<F> {
+    let s = nptr.to_bytes();
+    let mut idx = skip_space(s);
+    let (negative, len) = sign(&s[idx..]);
+    idx += len;
+    let s = &s[idx..];
+
+    let (value, out_of_range, len) = if starts_with_ignore_case(s, b"infinity") {
+        (F::INFINITY, false, 8)
+    } else if starts_with_ignore_case(s, b"inf") {
+        (F::INFINITY, false, 3)
+    } else if starts_with_ignore_case(s, b"nan") {
+        // An optional n-char-sequence is allowed in parentheses
+        let len = s[3..]
+            .strip_prefix(b"(")
+            .and_then(|rest| {
+                let chars = rest
+                    .iter()
+                    .take_while(|c| c.is_ascii_alphanumeric() || **c == b'_')
+                    .count();
+                (rest.get(chars) == Some(&b')')).then_some(chars + 2)
+            })
+            .unwrap_or(0);
+        (F::NAN, false, 3 + len)
+    } else if let Some((value, out_of_range, len)) = starts_with_ignore_case(s, b"0x")
+        .then(|| parse_hex_float::<F>(&s[2..]))
+        .flatten()
+    {
+        (value, out_of_range, len + 2)
+    } else if let Some((len, nonzero)) = scan_decimal_float(s) {
+        // Core's parser is correctly rounded, and the scanned slice is valid ASCII
+        let Some(value) = core::str::from_utf8(&s[..len])
+            .ok()
+            .and_then(|s| F::from_str(s).ok())
+        else {
+            return Conversion::none(None);
+        };
+        (value, value.is_out_of_range(nonzero), len)
+    } else {
+        return Conversion::none(None);
+    };
+
+    Conversion {
+        value: if negative { value.negate() } else { value },
+        consumed: idx + len,
+        error: out_of_range.then_some(Errno::ERANGE),
+    }
+}
+
+/// Convert the initial part of a string to a double. Accepts decimal and hexadecimal floats,
+/// `inf`, `infinity`, and `nan`, ignoring case
+pub fn strtod(nptr: &CStr) -> Conversion<c_double> {
+    strtof_impl(nptr)
+}
+
+/// Convert the initial part of a string to a float. See [strtod]
+pub fn strtof(nptr: &CStr) -> Conversion<c_float> {
+    strtof_impl(nptr)
+}
+
 /// Convert string to integer
-pub fn atoi(nptr: &CStr) -> Result<c_int, Errno> {
-    let nptr = nptr.to_str().map_err(|_| Errno::CloysterUnicodeError)?;
-    Ok(FromStr::from_str(nptr)?)
+pub fn atoi(nptr: &CStr) -> c_int {
+    strtol(nptr, 10).value as c_int
 }
 
 /// Convert string to long
-pub fn atol(nptr: &CStr) -> Result<c_long, Errno> {
-    let nptr = nptr.to_str().map_err(|_| Errno::CloysterUnicodeError)?;
-    Ok(FromStr::from_str(nptr)?)
+pub fn atol(nptr: &CStr) -> c_long {
+    strtol(nptr, 10).value
 }
 
 /// Convert string to long long
-pub fn atoll(nptr: &CStr) -> Result<c_longlong, Errno> {
-    let nptr = nptr.to_str().map_err(|_| Errno::CloysterUnicodeError)?;
-    Ok(FromStr::from_str(nptr)?)
+pub fn atoll(nptr: &CStr) -> c_longlong {
+    strtoll(nptr, 10).value
 }
 
 /// Convert string to double
-pub fn atof(nptr: &CStr) -> Result<c_double, Errno> {
-    let nptr = nptr.to_str().map_err(|_| Errno::CloysterUnicodeError)?;
-    Ok(FromStr::from_str(nptr)?)
+pub fn atof(nptr: &CStr) -> c_double {
+    strtod(nptr).value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed<T>(conversion: Conversion<T>) -> (T, usize, Option<Errno>) {
+        (conversion.value, conversion.consumed, conversion.error)
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(parsed(strtol(c"  \t-42abc", 10)), (-42, 6, None));
+        assert_eq!(parsed(strtol(c"+17", 0)), (17, 3, None));
+        assert_eq!(parsed(strtol(c"0x1fg", 0)), (0x1f, 4, None));
+        assert_eq!(parsed(strtol(c"0x1f", 16)), (0x1f, 4, None));
+        assert_eq!(parsed(strtol(c"0xg", 16)), (0, 1, None));
+        assert_eq!(parsed(strtol(c"0755", 0)), (0o755, 4, None));
+        assert_eq!(parsed(strtol(c"0789", 0)), (0o7, 2, None));
+        assert_eq!(parsed(strtol(c"zz", 36)), (36 * 36 - 1, 2, None));
+        assert_eq!(parsed(strtol(c"1012", 2)), (5, 3, None));
+        assert_eq!(parsed(strtol(c"", 10)), (0, 0, None));
+        assert_eq!(parsed(strtol(c"  -", 10)), (0, 0, None));
+        assert_eq!(parsed(strtol(c"12", 1)), (0, 0, Some(Errno::EINVAL)));
+        assert_eq!(parsed(strtol(c"12", 37)), (0, 0, Some(Errno::EINVAL)));
+    }
+
+    #[test]
+    fn integer_overflow() {
+        assert_eq!(
+            parsed(strtol(c"9223372036854775807", 10)),
+            (i64::MAX, 19, None)
+        );
+        assert_eq!(
+            parsed(strtol(c"9223372036854775808", 10)),
+            (i64::MAX, 19, Some(Errno::ERANGE))
+        );
+        assert_eq!(
+            parsed(strtol(c"-9223372036854775808", 10)),
+            (i64::MIN, 20, None)
+        );
+        assert_eq!(
+            parsed(strtoll(c"-99999999999999999999999999", 10)),
+            (i64::MIN, 27, Some(Errno::ERANGE))
+        );
+        assert_eq!(
+            parsed(strtoul(c"18446744073709551615", 10)),
+            (u64::MAX, 20, None)
+        );
+        assert_eq!(
+            parsed(strtoull(c"18446744073709551616", 10)),
+            (u64::MAX, 20, Some(Errno::ERANGE))
+        );
+        assert_eq!(parsed(strtoul(c"-1", 10)), (u64::MAX, 2, None));
+    }
+
+    #[test]
+    fn decimal_floats() {
+        assert_eq!(parsed(strtod(c" 1.5e3x")), (1500.0, 6, None));
+        assert_eq!(parsed(strtod(c"-.5")), (-0.5, 3, None));
+        assert_eq!(parsed(strtod(c"5.")), (5.0, 2, None));
+        assert_eq!(parsed(strtod(c"1e")), (1.0, 1, None));
+        assert_eq!(parsed(strtod(c"1e+")), (1.0, 1, None));
+        assert_eq!(parsed(strtod(c".")), (0.0, 0, None));
+        assert_eq!(parsed(strtod(c"0.1")), (0.1, 3, None));
+        assert_eq!(
+            parsed(strtod(c"2.2250738585072011e-308")),
+            (2.225073858507201e-308, 23, Some(Errno::ERANGE))
+        );
+        assert_eq!(
+            parsed(strtod(c"1e400")),
+            (f64::INFINITY, 5, Some(Errno::ERANGE))
+        );
+        assert_eq!(parsed(strtod(c"1e-400")), (0.0, 6, Some(Errno::ERANGE)));
+        assert_eq!(parsed(strtod(c"0e-400")), (0.0, 6, None));
+        assert_eq!(parsed(strtof(c"3.4028235e38")), (f32::MAX, 12, None));
+        assert_eq!(
+            parsed(strtof(c"3.5e38")),
+            (f32::INFINITY, 6, Some(Errno::ERANGE))
+        );
+    }
+
+    #[test]
+    fn hex_floats() {
+        assert_eq!(parsed(strtod(c"0x1p4")), (16.0, 5, None));
+        assert_eq!(parsed(strtod(c"0x.8")), (0.5, 4, None));
+        assert_eq!(parsed(strtod(c"-0X1.8P-1")), (-0.75, 9, None));
+        assert_eq!(parsed(strtod(c"0x1p")), (1.0, 3, None));
+        assert_eq!(parsed(strtod(c"0xp1")), (0.0, 1, None));
+        assert_eq!(
+            parsed(strtod(c"0x1.fffffffffffffp1023")),
+            (f64::MAX, 22, None)
+        );
+        assert_eq!(
+            parsed(strtod(c"0x1p1024")),
+            (f64::INFINITY, 8, Some(Errno::ERANGE))
+        );
+        assert_eq!(parsed(strtod(c"0x1p-1074")), (f64::from_bits(1), 9, None));
+        assert_eq!(parsed(strtod(c"0x1p-1076")), (0.0, 9, Some(Errno::ERANGE)));
+
+        // Ties round to even, unless lower digits break the tie
+        assert_eq!(strtod(c"0x1.00000000000008p0").value, 1.0);
+        assert_eq!(
+            strtod(c"0x1.000000000000080001p0").value,
+            1.0 + f64::EPSILON
+        );
+        assert_eq!(
+            strtod(c"0x1.00000000000018p0").value,
+            1.0 + 2.0 * f64::EPSILON
+        );
+        assert_eq!(strtod(c"0x1.fffffffffffff8p0").value, 2.0);
+        assert_eq!(strtof(c"0x1.000001p0").value, 1.0);
+        assert_eq!(strtof(c"0x1.000003p0").value, 1.0 + 2.0 * f32::EPSILON);
+    }
+
+    #[test]
+    fn special_floats() {
+        assert_eq!(parsed(strtod(c"inf")), (f64::INFINITY, 3, None));
+        assert_eq!(parsed(strtod(c"-INFINITYx")), (f64::NEG_INFINITY, 9, None));
+        assert_eq!(parsed(strtod(c"infinit")), (f64::INFINITY, 3, None));
+        assert!(strtod(c"nan").value.is_nan());
+        assert_eq!(strtod(c"NaN(0x1_f)").consumed, 10);
+        assert_eq!(strtod(c"nan(").consumed, 3);
+        assert_eq!(strtod(c"nan(-)").consumed, 3);
+        assert!(strtod(c"-nan").value.is_sign_negative());
+    }
+
+    #[test]
+    fn ascii_conversions() {
+        assert_eq!(atoi(c" 42abc"), 42);
+        assert_eq!(atoi(c"+7"), 7);
+        assert_eq!(atoi(c"abc"), 0);
+        assert_eq!(atoi(c"-0"), 0);
+        assert_eq!(atol(c"\xff"), 0);
+        assert_eq!(atoll(c"-123456789012"), -123456789012);
+        assert_eq!(atof(c"  2.5e-1 "), 0.25);
+    }
 }
@@ -0,0 +1,78 @@
+//! Integer division returning both quotient and remainder
+#![allow(non_camel_case_types)]
+use core::ffi::{c_int, c_long, c_longlong};
+
+/// Greatest-width signed integer type
+pub type intmax_t = i64;
+
+/// Quotient and remainder of an integer division, laid out like C's `div_t` family
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(C)]
+pub struct DivResult<T> {
+    pub quot: T,
+    pub rem: T,
+}
+
+/// Result of [div]
+pub type div_t = DivResult<c_int>;
+/// Result of [ldiv]
+pub type ldiv_t = DivResult<c_long>;
+/// Result of [lldiv]
+pub type lldiv_t = DivResult<c_longlong>;
+/// Result of [imaxdiv]
+pub type imaxdiv_t = DivResult<intmax_t>;
+
+macro_rules! division {
+    ($(#[$meta:meta])* $name:ident -> $result:ty, $type:ty) => {
+        $(#[$meta])*
+        ///
+        /// The quotient is truncated toward zero, and the remainder has the sign of `numer`
+        ///
+        /// # Panics
+        ///
+        /// If `denom` is zero, or the quotient overflows
+        pub fn $name(numer: $type, denom: $type) -> $result {
+            DivResult {
+                quot: numer / denom,
+                rem: numer % denom,
+            }
+        }
+    };
+}
+
+division!(
+    /// Divide two `int`s
+    div -> div_t, c_int
+);
+division!(
+    /// Divide two `long`s
+    ldiv -> ldiv_t, c_long
+);
+division!(
+    /// Divide two `long long`s
+    lldiv -> lldiv_t, c_longlong
+);
+division!(
+    /// Divide two `intmax_t`s
+    imaxdiv -> imaxdiv_t, intmax_t
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_toward_zero() {
+        assert_eq!(div(7, 2), DivResult { quot: 3, rem: 1 });
+        assert_eq!(div(-7, 2), DivResult { quot: -3, rem: -1 });
+        assert_eq!(ldiv(7, -2), DivResult { quot: -3, rem: 1 });
+        assert_eq!(lldiv(-7, -2), DivResult { quot: 3, rem: -1 });
+        assert_eq!(
+            imaxdiv(intmax_t::MIN, 10),
+            DivResult {
+                quot: -922337203685477580,
+                rem: -8
+            }
+        );
+    }
+}
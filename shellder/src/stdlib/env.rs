@@ -0,0 +1,66 @@
+use core::{
+    ffi::{CStr, c_char},
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+static ENVIRON: AtomicPtr<*const c_char> = AtomicPtr::new(ptr::null_mut());
+
+/// Set the environment used by [getenv]
+///
+/// # Safety
+///
+/// `envp` must be NULL or a NULL-terminated array of pointers to null-terminated `NAME=value`
+/// strings, which must stay valid for the rest of the program
+pub unsafe fn set_environ(envp: *const *const c_char) {
+    ENVIRON.store(envp as *mut _, Ordering::Relaxed);
+}
+
+/// Return the environment, as set by [set_environ]
+pub fn environ() -> *const *const c_char {
+    ENVIRON.load(Ordering::Relaxed)
+}
+
+/// Look up the value of environment variable `name`
+pub fn getenv(name: &CStr) -> Option<&'static CStr> {
+    let name = name.to_bytes();
+    let mut envp = environ();
+    if envp.is_null() {
+        return None;
+    }
+
+    // Safety: guaranteed by `set_environ`
+    unsafe {
+        while !(*envp).is_null() {
+            let var = CStr::from_ptr(*envp).to_bytes_with_nul();
+            if var.starts_with(name) && var[name.len()] == b'=' {
+                return Some(CStr::from_bytes_with_nul_unchecked(&var[name.len() + 1..]));
+            }
+            envp = envp.add(1);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let vars = [
+            c"HOME=/root".as_ptr(),
+            c"HOMER=simpson".as_ptr(),
+            c"EMPTY=".as_ptr(),
+            ptr::null(),
+        ];
+        unsafe { set_environ(vars.as_ptr()) };
+        assert_eq!(getenv(c"HOME"), Some(c"/root"));
+        assert_eq!(getenv(c"HOMER"), Some(c"simpson"));
+        assert_eq!(getenv(c"EMPTY"), Some(c""));
+        assert_eq!(getenv(c"HOM"), None);
+        assert_eq!(getenv(c"PATH"), None);
+        unsafe { set_environ(ptr::null()) };
+    }
+}
@@ -1,4 +1,4 @@
-use crate::malloc;
+use crate::{malloc, types::*, unistd};
 use core::ffi::c_int;
 
 /// Causes normal process termination with status `status`
@@ -6,10 +6,26 @@ pub fn exit_without_cleanup(status: c_int) -> ! {
     if malloc::get_num_allocations() != 0 {
         panic!("Memory leak detected on exit!");
     }
-    crate::unistd::exit(status)
+    unistd::exit(status)
 }
 
-/// Causes abnormal process termination
+/// Causes abnormal process termination by raising `SIGABRT`
+///
+/// `SIGABRT` is unblocked first. If a handler returns, the default action is restored and the
+/// signal raised again, so the process is killed and can dump core
 pub fn abort() -> ! {
-    crate::unistd::exit(1)
+    let mut set = SigSet::empty();
+    set.add(SIGABRT).expect("SIGABRT is a valid signal");
+    let _ = unistd::rt_sigprocmask(SIG_UNBLOCK, Some(&set), None);
+    let _ = unistd::raise(SIGABRT);
+
+    let default = KernelSigAction {
+        handler: SIG_DFL,
+        ..Default::default()
+    };
+    let _ = unsafe { unistd::rt_sigaction(SIGABRT, Some(&default), None) };
+    let _ = unistd::raise(SIGABRT);
+
+    // Nothing should be able to stop the default action, but make sure we never return
+    unistd::exit(127)
 }
@@ -1,5 +1,17 @@
+mod arc4random;
 mod conversions;
+mod div;
+mod env;
 mod exit;
+mod random;
+mod realpath;
+mod sort;
 
+pub use arc4random::*;
 pub use conversions::*;
+pub use div::*;
+pub use env::*;
 pub use exit::*;
+pub use random::*;
+pub use realpath::*;
+pub use sort::*;
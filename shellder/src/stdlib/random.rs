@@ -0,0 +1,508 @@
+//! Pseudo-random number generators, producing the same sequences as glibc
+use crate::errno::Errno;
+use core::{
+    ffi::{c_char, c_double, c_int, c_long, c_uint, c_ushort},
+    ptr::{self, NonNull},
+};
+use spin::Mutex;
+
+/// Largest value returned by [rand]
+pub const RAND_MAX: c_int = c_int::MAX;
+
+// Generator types, as (minimum buffer size in bytes, degree, separation). Type 0 is a plain linear
+// congruential generator, and the rest are additive feedback generators
+const TYPES: [(usize, usize, usize); 5] = [
+    (8, 0, 0),
+    (32, 7, 3),
+    (64, 15, 1),
+    (128, 31, 3),
+    (256, 63, 1),
+];
+const MAX_TYPES: i32 = TYPES.len() as i32;
+
+/// State of a [random] generator, kept in a caller-supplied buffer like glibc's `random_data`
+///
+/// The first word of the buffer records the generator type and position, so a generator can be
+/// resumed from its buffer with [RandomState::resume]
+#[derive(Debug)]
+pub struct RandomState {
+    // Points one word into the buffer
+    state: *mut i32,
+    kind: usize,
+    front: usize,
+    rear: usize,
+}
+
+// The buffer is only accessed through the state, which callers must synchronize
+unsafe impl Send for RandomState {}
+
+impl RandomState {
+    /// Initialize a generator in the `n` byte buffer `buffer`, seeded with `seed`. Larger buffers
+    /// give better generators, up to 256 bytes
+    ///
+    /// # Returns
+    ///
+    /// `EINVAL` if `n` is less than 8
+    ///
+    /// # Safety
+    ///
+    /// `buffer` must be valid for reads and writes of `n` bytes for as long as the generator is
+    /// used
+    pub unsafe fn new(seed: c_uint, buffer: NonNull<c_char>, n: usize) -> Result<Self, Errno> {
+        let kind = TYPES
+            .iter()
+            .rposition(|(size, ..)| n >= *size)
+            .ok_or(Errno::EINVAL)?;
+        let mut state = Self {
+            state: buffer.as_ptr().cast::<i32>().wrapping_add(1),
+            kind,
+            front: 0,
+            rear: 0,
+        };
+        state.seed(seed);
+        state.save();
+        Ok(state)
+    }
+
+    /// Resume a generator from a buffer previously used by a generator
+    ///
+    /// # Returns
+    ///
+    /// `EINVAL` if the buffer doesn't hold a valid generator
+    ///
+    /// # Safety
+    ///
+    /// `buffer` must have been initialized with [RandomState::new], and stay valid for as long as
+    /// the generator is used
+    pub unsafe fn resume(buffer: NonNull<c_char>) -> Result<Self, Errno> {
+        let info = unsafe { buffer.as_ptr().cast::<i32>().read_unaligned() };
+        let kind = usize::try_from(info % MAX_TYPES).map_err(|_| Errno::EINVAL)?;
+        let (_, degree, separation) = TYPES[kind];
+        let rear = usize::try_from(info / MAX_TYPES).map_err(|_| Errno::EINVAL)?;
+        if kind != 0 && rear >= degree {
+            return Err(Errno::EINVAL);
+        }
+        Ok(Self {
+            state: buffer.as_ptr().cast::<i32>().wrapping_add(1),
+            kind,
+            front: if kind == 0 {
+                0
+            } else {
+                (rear + separation) % degree
+            },
+            rear,
+        })
+    }
+
+    /// The buffer holding this generator
+    pub fn buffer(&self) -> NonNull<c_char> {
+        NonNull::new(self.state.wrapping_sub(1).cast()).expect("Buffer is not null")
+    }
+
+    fn degree(&self) -> usize {
+        TYPES[self.kind].1
+    }
+
+    fn word(&self, index: usize) -> i32 {
+        unsafe { self.state.add(index).read_unaligned() }
+    }
+
+    fn set_word(&mut self, index: usize, value: i32) {
+        unsafe { self.state.add(index).write_unaligned(value) }
+    }
+
+    // Record the type and position in the buffer, so the generator can be resumed
+    fn save(&mut self) {
+        let info = if self.kind == 0 {
+            0
+        } else {
+            self.rear as i32 * MAX_TYPES + self.kind as i32
+        };
+        unsafe { self.state.sub(1).write_unaligned(info) };
+    }
+
+    /// Reseed the generator
+    pub fn seed(&mut self, seed: c_uint) {
+        let seed = if seed == 0 { 1 } else { seed };
+        self.set_word(0, seed as i32);
+        if self.kind == 0 {
+            return;
+        }
+
+        // state[i] = (16807 * state[i - 1]) % 2147483647, without overflowing 31 bits
+        let mut word = seed as i32;
+        for i in 1..self.degree() {
+            let (hi, lo) = (word / 127773, word % 127773);
+            word = 16807 * lo - 2836 * hi;
+            if word < 0 {
+                word += 2147483647;
+            }
+            self.set_word(i, word);
+        }
+
+        self.front = TYPES[self.kind].2;
+        self.rear = 0;
+        for _ in 0..10 * self.degree() {
+            self.generate();
+        }
+    }
+
+    /// Generate the next number, between 0 and [RAND_MAX]
+    pub fn generate(&mut self) -> i32 {
+        if self.kind == 0 {
+            let value = (self.word(0) as u32)
+                .wrapping_mul(1103515245)
+                .wrapping_add(12345)
+                & 0x7fffffff;
+            self.set_word(0, value as i32);
+            return value as i32;
+        }
+
+        let value = (self.word(self.front) as u32).wrapping_add(self.word(self.rear) as u32);
+        self.set_word(self.front, value as i32);
+        self.front = (self.front + 1) % self.degree();
+        self.rear = (self.rear + 1) % self.degree();
+        // The least significant bit is the least random
+        (value >> 1) as i32
+    }
+}
+
+// The buffer of the global generator, until `initstate()` or `setstate()` replace it
+static mut DEFAULT_BUFFER: [i32; 32] = [0; 32];
+static RANDOM: Mutex<Option<RandomState>> = Mutex::new(None);
+
+fn with_random<T>(f: impl FnOnce(&mut RandomState) -> T) -> T {
+    let mut random = RANDOM.lock();
+    let state = random.get_or_insert_with(|| {
+        let buffer = NonNull::new(ptr::addr_of_mut!(DEFAULT_BUFFER).cast()).unwrap();
+        // An unseeded generator behaves as if seeded with 1
+        unsafe { RandomState::new(1, buffer, size_of::<[i32; 32]>()) }.unwrap()
+    });
+    f(state)
+}
+
+/// Generate a number between 0 and [RAND_MAX] from the global generator
+pub fn random() -> c_long {
+    with_random(|state| state.generate()).into()
+}
+
+/// Seed the global generator
+pub fn srandom(seed: c_uint) {
+    with_random(|state| state.seed(seed));
+}
+
+/// Switch the global generator to a new generator in `buffer`. See [RandomState::new]
+///
+/// # Returns
+///
+/// The previous buffer
+///
+/// # Safety
+///
+/// See [RandomState::new]
+pub unsafe fn initstate(
+    seed: c_uint,
+    buffer: NonNull<c_char>,
+    n: usize,
+) -> Result<NonNull<c_char>, Errno> {
+    with_random(|state| {
+        state.save();
+        let new = unsafe { RandomState::new(seed, buffer, n)? };
+        Ok(core::mem::replace(state, new).buffer())
+    })
+}
+
+/// Switch the global generator to the generator in `buffer`. See [RandomState::resume]
+///
+/// # Returns
+///
+/// The previous buffer
+///
+/// # Safety
+///
+/// See [RandomState::resume]
+pub unsafe fn setstate(buffer: NonNull<c_char>) -> Result<NonNull<c_char>, Errno> {
+    with_random(|state| {
+        state.save();
+        let new = unsafe { RandomState::resume(buffer)? };
+        Ok(core::mem::replace(state, new).buffer())
+    })
+}
+
+/// Generate a number between 0 and [RAND_MAX]. This is the same generator as [random]
+pub fn rand() -> c_int {
+    random() as c_int
+}
+
+/// Seed the generator used by [rand]
+pub fn srand(seed: c_uint) {
+    srandom(seed);
+}
+
+/// Generate a number between 0 and [RAND_MAX], using `seed` as the entire state
+pub fn rand_r(seed: &mut c_uint) -> c_int {
+    let mut next = *seed;
+    let mut step = |modulus: c_uint| {
+        next = next.wrapping_mul(1103515245).wrapping_add(12345);
+        (next / 65536 % modulus) as c_int
+    };
+    let mut result = step(2048);
+    result = (result << 10) ^ step(1024);
+    result = (result << 10) ^ step(1024);
+    *seed = next;
+    result
+}
+
+/// State of the 48-bit linear congruential generator used by [drand48] and friends
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Rand48 {
+    x: [c_ushort; 3],
+    a: u64,
+    c: u64,
+}
+
+impl Rand48 {
+    const DEFAULT_A: u64 = 0x5deece66d;
+    const DEFAULT_C: u64 = 0xb;
+
+    pub const fn new() -> Self {
+        Self {
+            x: [0; 3],
+            a: Self::DEFAULT_A,
+            c: Self::DEFAULT_C,
+        }
+    }
+
+    fn join(x: &[c_ushort; 3]) -> u64 {
+        u64::from(x[2]) << 32 | u64::from(x[1]) << 16 | u64::from(x[0])
+    }
+
+    fn split(value: u64) -> [c_ushort; 3] {
+        [
+            value as c_ushort,
+            (value >> 16) as c_ushort,
+            (value >> 32) as c_ushort,
+        ]
+    }
+
+    // Advance `xsubi` with this generator's parameters, returning the new 48-bit value
+    fn step(&self, xsubi: &mut [c_ushort; 3]) -> u64 {
+        let x = Self::join(xsubi).wrapping_mul(self.a).wrapping_add(self.c) & ((1 << 48) - 1);
+        *xsubi = Self::split(x);
+        x
+    }
+
+    fn step_own(&mut self) -> u64 {
+        let mut x = self.x;
+        let value = self.step(&mut x);
+        self.x = x;
+        value
+    }
+
+    /// Uniformly distributed double in [0, 1), advancing `xsubi` instead of the internal state
+    pub fn erand48(&self, xsubi: &mut [c_ushort; 3]) -> c_double {
+        self.step(xsubi) as c_double / (1u64 << 48) as c_double
+    }
+
+    /// Uniformly distributed non-negative long in [0, 2^31), advancing `xsubi`
+    pub fn nrand48(&self, xsubi: &mut [c_ushort; 3]) -> c_long {
+        (self.step(xsubi) >> 17) as c_long
+    }
+
+    /// Uniformly distributed signed long in [-2^31, 2^31), advancing `xsubi`
+    pub fn jrand48(&self, xsubi: &mut [c_ushort; 3]) -> c_long {
+        ((self.step(xsubi) >> 16) as u32 as i32).into()
+    }
+
+    /// Like [Rand48::erand48], but advancing the internal state
+    pub fn drand48(&mut self) -> c_double {
+        self.step_own() as c_double / (1u64 << 48) as c_double
+    }
+
+    /// Like [Rand48::nrand48], but advancing the internal state
+    pub fn lrand48(&mut self) -> c_long {
+        (self.step_own() >> 17) as c_long
+    }
+
+    /// Like [Rand48::jrand48], but advancing the internal state
+    pub fn mrand48(&mut self) -> c_long {
+        ((self.step_own() >> 16) as u32 as i32).into()
+    }
+
+    /// Seed the high 32 bits of the state, and restore the default parameters
+    pub fn srand48(&mut self, seed: c_long) {
+        self.x = Self::split((seed as u64 & 0xffff_ffff) << 16 | 0x330e);
+        self.a = Self::DEFAULT_A;
+        self.c = Self::DEFAULT_C;
+    }
+
+    /// Set the entire state and restore the default parameters, returning the previous state
+    pub fn seed48(&mut self, seed: [c_ushort; 3]) -> [c_ushort; 3] {
+        self.a = Self::DEFAULT_A;
+        self.c = Self::DEFAULT_C;
+        core::mem::replace(&mut self.x, seed)
+    }
+
+    /// Set the state (`param[0..3]`), multiplier (`param[3..6]`), and addend (`param[6]`)
+    pub fn lcong48(&mut self, param: [c_ushort; 7]) {
+        self.x = [param[0], param[1], param[2]];
+        self.a = Self::join(&[param[3], param[4], param[5]]);
+        self.c = param[6].into();
+    }
+}
+
+impl Default for Rand48 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static RAND48: Mutex<Rand48> = Mutex::new(Rand48::new());
+
+/// Uniformly distributed double in [0, 1) from the global 48-bit generator
+pub fn drand48() -> c_double {
+    RAND48.lock().drand48()
+}
+
+/// Uniformly distributed double in [0, 1), using `xsubi` as the generator state
+pub fn erand48(xsubi: &mut [c_ushort; 3]) -> c_double {
+    RAND48.lock().erand48(xsubi)
+}
+
+/// Uniformly distributed long in [0, 2^31) from the global 48-bit generator
+pub fn lrand48() -> c_long {
+    RAND48.lock().lrand48()
+}
+
+/// Uniformly distributed long in [0, 2^31), using `xsubi` as the generator state
+pub fn nrand48(xsubi: &mut [c_ushort; 3]) -> c_long {
+    RAND48.lock().nrand48(xsubi)
+}
+
+/// Uniformly distributed long in [-2^31, 2^31) from the global 48-bit generator
+pub fn mrand48() -> c_long {
+    RAND48.lock().mrand48()
+}
+
+/// Uniformly distributed long in [-2^31, 2^31), using `xsubi` as the generator state
+pub fn jrand48(xsubi: &mut [c_ushort; 3]) -> c_long {
+    RAND48.lock().jrand48(xsubi)
+}
+
+/// Seed the global 48-bit generator
+pub fn srand48(seed: c_long) {
+    RAND48.lock().srand48(seed);
+}
+
+/// Set the state of the global 48-bit generator, returning the previous state
+pub fn seed48(seed: [c_ushort; 3]) -> [c_ushort; 3] {
+    RAND48.lock().seed48(seed)
+}
+
+/// Set the state and parameters of the global 48-bit generator. See [Rand48::lcong48]
+pub fn lcong48(param: [c_ushort; 7]) {
+    RAND48.lock().lcong48(param);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generator(seed: c_uint, buffer: &mut [u8]) -> RandomState {
+        let len = buffer.len();
+        unsafe { RandomState::new(seed, NonNull::from(buffer).cast(), len) }.unwrap()
+    }
+
+    #[test]
+    fn glibc_sequence() {
+        // The first numbers glibc's `rand()` produces after `srand(1)`
+        let mut buffer = [0; 128];
+        let mut state = generator(1, &mut buffer);
+        for expected in [1804289383, 846930886, 1681692777, 1714636915, 1957747793] {
+            assert_eq!(state.generate(), expected);
+        }
+
+        // Type 0 is glibc's linear congruential generator
+        let mut buffer = [0; 8];
+        let mut state = generator(1, &mut buffer);
+        assert_eq!(state.generate(), 1103527590);
+        assert_eq!(
+            state.generate(),
+            (1103527590u32.wrapping_mul(1103515245).wrapping_add(12345) & 0x7fffffff) as i32
+        );
+    }
+
+    #[test]
+    fn buffer_sizes() {
+        let mut buffer = [0; 300];
+        assert!(unsafe { RandomState::new(1, NonNull::from(&mut buffer).cast(), 7) }.is_err());
+        for (n, kind) in [
+            (8, 0),
+            (31, 0),
+            (32, 1),
+            (64, 2),
+            (128, 3),
+            (255, 3),
+            (300, 4),
+        ] {
+            assert_eq!(generator(5, &mut buffer[..n]).kind, kind);
+        }
+    }
+
+    #[test]
+    fn resume() {
+        for n in [8, 32, 64, 128, 256] {
+            let mut buffer = vec![0; n];
+            let mut state = generator(42, &mut buffer);
+            for _ in 0..n {
+                state.generate();
+            }
+            state.save();
+            let mut copy = buffer.clone();
+            let mut resumed =
+                unsafe { RandomState::resume(NonNull::from(&mut copy[..]).cast()) }.unwrap();
+            let mut state =
+                unsafe { RandomState::resume(NonNull::from(&mut buffer[..]).cast()) }.unwrap();
+            for _ in 0..100 {
+                assert_eq!(resumed.generate(), state.generate());
+            }
+        }
+    }
+
+    #[test]
+    fn reentrant_rand() {
+        let mut seed = 1;
+        let first = rand_r(&mut seed);
+        assert_ne!(seed, 1);
+        assert!((0..=RAND_MAX).contains(&first));
+        let mut again = 1;
+        assert_eq!(rand_r(&mut again), first);
+        assert_eq!(again, seed);
+    }
+
+    #[test]
+    fn rand48() {
+        let mut rand48 = Rand48::new();
+        rand48.srand48(1234);
+        assert_eq!(rand48.x, Rand48::split(1234 << 16 | 0x330e));
+        let copy = rand48;
+
+        // The `xsubi` variants advance the given state like the others advance the internal state
+        let mut xsubi = copy.x;
+        assert_eq!(copy.erand48(&mut xsubi), rand48.drand48());
+        assert_eq!(xsubi, rand48.x);
+        assert_eq!(copy.nrand48(&mut xsubi), rand48.lrand48());
+        assert_eq!(copy.jrand48(&mut xsubi), rand48.mrand48());
+        assert_eq!(rand48.seed48([1, 2, 3]), xsubi);
+
+        // Each function takes different bits of the same sequence
+        let (mut a, mut b) = (copy, copy);
+        let double = a.drand48();
+        assert_eq!((double * (1u64 << 48) as f64) as c_long >> 17, b.lrand48());
+        assert_eq!(a.mrand48() >> 1 & 0x7fffffff, b.lrand48());
+
+        rand48.lcong48([1, 0, 0, 2, 0, 0, 3]);
+        assert_eq!(rand48.lrand48(), 0);
+        assert_eq!(rand48.x, [5, 0, 0]);
+    }
+}
@@ -0,0 +1,137 @@
+//! Path resolution, following symbolic links in userspace
+use crate::{errno::Errno, types::*, unistd};
+use core::ffi::CStr;
+
+// Most symbolic links to follow in one path, like Linux
+const MAXSYMLINKS: usize = 40;
+
+/// Get the absolute path of `path` without ".", ".." or symbolic links, in `resolved`
+///
+/// # C Signature
+///
+/// `char *realpath(const char *restrict path, char *restrict resolved_path);`
+///
+/// # Returns
+///
+/// The path, which is in `resolved`. Every component must exist
+pub fn realpath<'a>(path: &CStr, resolved: &'a mut [u8; PATH_MAX]) -> Result<&'a CStr, Errno> {
+    let path = path.to_bytes();
+    if path.is_empty() {
+        return Err(Errno::ENOENT);
+    }
+    // What's left to resolve is `rest[pos..rest_len]`
+    let mut rest = [0; PATH_MAX];
+    if path.len() >= PATH_MAX {
+        return Err(Errno::ENAMETOOLONG);
+    }
+    rest[..path.len()].copy_from_slice(path);
+    let mut rest_len = path.len();
+    let mut pos = 0;
+
+    let mut len = if path[0] == b'/' {
+        resolved[0] = b'/';
+        1
+    } else {
+        unistd::getcwd(resolved)?.to_bytes().len()
+    };
+    let mut links = 0;
+    while pos < rest_len {
+        while pos < rest_len && rest[pos] == b'/' {
+            pos += 1;
+        }
+        let start = pos;
+        while pos < rest_len && rest[pos] != b'/' {
+            pos += 1;
+        }
+        match &rest[start..pos] {
+            b"" | b"." => continue,
+            b".." => {
+                // Everything resolved so far is a real directory, so ".." is its parent
+                if let Some(slash) = resolved[..len].iter().rposition(|c| *c == b'/') {
+                    len = slash.max(1);
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        let parent_len = len;
+        if resolved[len - 1] != b'/' {
+            resolved[len] = b'/';
+            len += 1;
+        }
+        if len + (pos - start) >= PATH_MAX {
+            return Err(Errno::ENAMETOOLONG);
+        }
+        resolved[len..len + (pos - start)].copy_from_slice(&rest[start..pos]);
+        len += pos - start;
+        resolved[len] = 0;
+
+        let current = CStr::from_bytes_with_nul(&resolved[..=len]).expect("Path is terminated");
+        let stat = unistd::fstatat(AT_FDCWD, current, AtFlags::AT_SYMLINK_NOFOLLOW)?;
+        match stat.mode & S_IFMT {
+            S_IFLNK => {
+                links += 1;
+                if links > MAXSYMLINKS {
+                    return Err(Errno::ELOOP);
+                }
+                // What's left is the link's target, then the rest of the path
+                let mut target = [0; PATH_MAX];
+                let target_len = unistd::readlink(current, &mut target)?;
+                let remaining = rest_len - pos;
+                if target_len + remaining >= PATH_MAX {
+                    return Err(Errno::ENAMETOOLONG);
+                }
+                target[target_len..target_len + remaining].copy_from_slice(&rest[pos..rest_len]);
+                rest = target;
+                rest_len = target_len + remaining;
+                pos = 0;
+                len = if rest.first() == Some(&b'/') {
+                    1
+                } else {
+                    parent_len
+                };
+            }
+            S_IFDIR => {}
+            // Only the last component can be something other than a directory
+            _ if pos < rest_len => return Err(Errno::ENOTDIR),
+            _ => {}
+        }
+    }
+    resolved[len] = 0;
+    Ok(CStr::from_bytes_with_nul(&resolved[..=len]).expect("Path is terminated"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_paths() {
+        let mut buf = [0; PATH_MAX];
+        assert_eq!(realpath(c"/", &mut buf), Ok(c"/"));
+        assert_eq!(realpath(c"//usr/./bin/..//", &mut buf), Ok(c"/usr"));
+        assert_eq!(realpath(c"/..", &mut buf), Ok(c"/"));
+        assert_eq!(realpath(c"/proc/self/root/usr", &mut buf), Ok(c"/usr"));
+
+        let cwd = std::env::current_dir().unwrap();
+        let expected =
+            std::ffi::CString::new(cwd.join("src").into_os_string().into_encoded_bytes());
+        assert_eq!(
+            realpath(c"src/../src/.", &mut buf),
+            Ok(expected.unwrap().as_c_str())
+        );
+    }
+
+    #[test]
+    fn rejects_bad_paths() {
+        let mut buf = [0; PATH_MAX];
+        assert_eq!(realpath(c"", &mut buf), Err(Errno::ENOENT));
+        assert_eq!(
+            realpath(c"/no_such_directory/x", &mut buf),
+            Err(Errno::ENOENT)
+        );
+        assert_eq!(realpath(c"Cargo.toml/", &mut buf), Err(Errno::ENOTDIR));
+        assert_eq!(realpath(c"Cargo.toml/..", &mut buf), Err(Errno::ENOTDIR));
+    }
+}
@@ -0,0 +1,309 @@
+//! Sorting and searching of untyped arrays
+use core::{cmp::Ordering, ffi::c_void, ptr};
+
+// Slices this short are insertion sorted
+const INSERTION_SORT_THRESHOLD: usize = 16;
+
+// An untyped array of `len` elements of `size` bytes
+struct Elements {
+    base: *mut u8,
+    size: usize,
+}
+
+impl Elements {
+    fn at(&self, index: usize) -> *mut u8 {
+        self.base.wrapping_add(index * self.size)
+    }
+
+    // Safety: both indices must be in bounds
+    unsafe fn swap(&self, a: usize, b: usize) {
+        if a != b {
+            unsafe { ptr::swap_nonoverlapping(self.at(a), self.at(b), self.size) };
+        }
+    }
+
+    fn sub(&self, start: usize) -> Self {
+        Self {
+            base: self.at(start),
+            size: self.size,
+        }
+    }
+}
+
+type Compare<'a> = &'a mut dyn FnMut(*const c_void, *const c_void) -> Ordering;
+
+fn compare(elements: &Elements, compar: Compare, a: usize, b: usize) -> Ordering {
+    compar(
+        elements.at(a) as *const c_void,
+        elements.at(b) as *const c_void,
+    )
+}
+
+unsafe fn insertion_sort(elements: &Elements, len: usize, compar: Compare) {
+    for i in 1..len {
+        let mut j = i;
+        while j > 0 && compare(elements, compar, j - 1, j) == Ordering::Greater {
+            unsafe { elements.swap(j - 1, j) };
+            j -= 1;
+        }
+    }
+}
+
+unsafe fn sift_down(elements: &Elements, mut root: usize, len: usize, compar: Compare) {
+    loop {
+        let mut child = 2 * root + 1;
+        if child >= len {
+            return;
+        }
+        if child + 1 < len && compare(elements, compar, child, child + 1) == Ordering::Less {
+            child += 1;
+        }
+        if compare(elements, compar, root, child) != Ordering::Less {
+            return;
+        }
+        unsafe { elements.swap(root, child) };
+        root = child;
+    }
+}
+
+unsafe fn heap_sort(elements: &Elements, len: usize, compar: Compare) {
+    for root in (0..len / 2).rev() {
+        unsafe { sift_down(elements, root, len, compar) };
+    }
+    for end in (1..len).rev() {
+        unsafe {
+            elements.swap(0, end);
+            sift_down(elements, 0, end, compar);
+        }
+    }
+}
+
+// Partition around the median of the first, middle and last elements, returning the pivot's final
+// index. Elements equal to the pivot are spread over both sides, so runs of duplicates don't
+// degrade to quadratic time
+unsafe fn partition(elements: &Elements, len: usize, compar: Compare) -> usize {
+    let (mid, last) = (len / 2, len - 1);
+    unsafe {
+        if compare(elements, compar, mid, 0) == Ordering::Less {
+            elements.swap(mid, 0);
+        }
+        if compare(elements, compar, last, mid) == Ordering::Less {
+            elements.swap(last, mid);
+            if compare(elements, compar, mid, 0) == Ordering::Less {
+                elements.swap(mid, 0);
+            }
+        }
+        // The pivot stays at index 0 until it's moved into place
+        elements.swap(0, mid);
+    }
+
+    let (mut i, mut j) = (1, last);
+    loop {
+        while i <= j && compare(elements, compar, i, 0) == Ordering::Less {
+            i += 1;
+        }
+        while i <= j && compare(elements, compar, j, 0) == Ordering::Greater {
+            j -= 1;
+        }
+        if i >= j {
+            break;
+        }
+        unsafe { elements.swap(i, j) };
+        i += 1;
+        j -= 1;
+    }
+    unsafe { elements.swap(0, j) };
+    j
+}
+
+// Introsort: quicksort, falling back to heapsort if partitioning goes badly
+unsafe fn intro_sort(mut elements: Elements, mut len: usize, compar: Compare) {
+    let mut depth_limit = 2 * (usize::BITS - len.leading_zeros());
+    while len > INSERTION_SORT_THRESHOLD {
+        if depth_limit == 0 {
+            unsafe { heap_sort(&elements, len, compar) };
+            return;
+        }
+        depth_limit -= 1;
+
+        let pivot = unsafe { partition(&elements, len, compar) };
+        let right = elements.sub(pivot + 1);
+        let right_len = len - pivot - 1;
+
+        // Recurse into the smaller side, so the stack depth stays logarithmic
+        if pivot < right_len {
+            unsafe { intro_sort(elements, pivot, compar) };
+            elements = right;
+            len = right_len;
+        } else {
+            unsafe { intro_sort(right, right_len, compar) };
+            len = pivot;
+        }
+    }
+    unsafe { insertion_sort(&elements, len, compar) };
+}
+
+/// Sort an array of `nmemb` elements of `size` bytes in place, without allocating. The sort is not
+/// stable
+///
+/// # C Signature
+///
+/// `void qsort(void *base, size_t nmemb, size_t size, int (*compar)(const void *, const void *));`
+///
+/// # Safety
+///
+/// `base` must point to `nmemb * size` bytes of readable and writable memory, and `compar` must
+/// be safe to call on pointers to any two elements
+pub unsafe fn qsort(
+    base: *mut c_void,
+    nmemb: usize,
+    size: usize,
+    mut compar: impl FnMut(*const c_void, *const c_void) -> Ordering,
+) {
+    if nmemb < 2 || size == 0 {
+        return;
+    }
+    let elements = Elements {
+        base: base as *mut u8,
+        size,
+    };
+    unsafe { intro_sort(elements, nmemb, &mut compar) }
+}
+
+/// Search a sorted array of `nmemb` elements of `size` bytes for an element matching `key`
+///
+/// # C Signature
+///
+/// `void *bsearch(const void *key, const void *base, size_t nmemb, size_t size,
+///                int (*compar)(const void *, const void *));`
+///
+/// # Returns
+///
+/// A pointer to a matching element, or `None` if there isn't one
+///
+/// # Safety
+///
+/// `base` must point to `nmemb * size` bytes of readable memory, and `compar` must be safe to call
+/// with `key` and a pointer to any element
+pub unsafe fn bsearch(
+    key: *const c_void,
+    base: *const c_void,
+    nmemb: usize,
+    size: usize,
+    mut compar: impl FnMut(*const c_void, *const c_void) -> Ordering,
+) -> Option<*const c_void> {
+    let (mut low, mut high) = (0, nmemb);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let element = (base as *const u8).wrapping_add(mid * size) as *const c_void;
+        match compar(key, element) {
+            Ordering::Less => high = mid,
+            Ordering::Greater => low = mid + 1,
+            Ordering::Equal => return Some(element),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn sorted_by<T: Copy>(mut vec: Vec<T>, mut compare: impl FnMut(&T, &T) -> Ordering) -> Vec<T> {
+        unsafe {
+            qsort(
+                vec.as_mut_ptr() as *mut c_void,
+                vec.len(),
+                size_of::<T>(),
+                |a, b| compare(&*(a as *const T), &*(b as *const T)),
+            )
+        };
+        vec
+    }
+
+    fn check_by<T: Copy + Eq + core::fmt::Debug>(
+        vec: Vec<T>,
+        mut compare: impl FnMut(&T, &T) -> Ordering,
+    ) {
+        let mut expected = vec.clone();
+        expected.sort_unstable_by(&mut compare);
+        assert_eq!(sorted_by(vec, compare), expected);
+    }
+
+    fn check<T: Copy + Ord + core::fmt::Debug>(vec: Vec<T>) {
+        check_by(vec, T::cmp);
+    }
+
+    #[test]
+    fn matches_sort_unstable() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let len = rng.gen_range(0..300);
+            check((0..len).map(|_| rng.r#gen::<u8>()).collect());
+            check((0..len).map(|_| rng.r#gen::<i32>()).collect());
+            check((0..len).map(|_| rng.gen_range(0..4u64)).collect());
+            // Odd-sized elements
+            check((0..len).map(|_| rng.r#gen::<[u8; 3]>()).collect());
+            check_by((0..len).map(|_| rng.r#gen::<i16>()).collect(), |a, b| {
+                b.cmp(a)
+            });
+        }
+    }
+
+    #[test]
+    fn adversarial_inputs() {
+        check((0..1000).collect::<Vec<u32>>());
+        check((0..1000).rev().collect::<Vec<u32>>());
+        check(vec![7u16; 1000]);
+        check((0..1000).map(|i| i % 2).collect::<Vec<u32>>());
+        check(
+            (0..1000)
+                .map(|i| if i < 500 { i } else { 1000 - i })
+                .collect::<Vec<u32>>(),
+        );
+        check(Vec::<u8>::new());
+    }
+
+    #[test]
+    fn heap_sort_fallback() {
+        let mut rng = rand::thread_rng();
+        let mut vec: Vec<i64> = (0..500).map(|_| rng.r#gen()).collect();
+        let elements = Elements {
+            base: vec.as_mut_ptr() as *mut u8,
+            size: size_of::<i64>(),
+        };
+        unsafe {
+            heap_sort(&elements, vec.len(), &mut |a, b| {
+                (*(a as *const i64)).cmp(&*(b as *const i64))
+            })
+        };
+        assert!(vec.is_sorted());
+    }
+
+    #[test]
+    fn binary_search() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let len = rng.gen_range(0..100);
+            let vec = sorted_by(
+                (0..len).map(|_| rng.gen_range(0..200u32) * 2).collect(),
+                u32::cmp,
+            );
+            let find = |key: u32| unsafe {
+                bsearch(
+                    ptr::from_ref(&key) as *const c_void,
+                    vec.as_ptr() as *const c_void,
+                    vec.len(),
+                    size_of::<u32>(),
+                    |a, b| (*(a as *const u32)).cmp(&*(b as *const u32)),
+                )
+                .map(|found| *(found as *const u32))
+            };
+            for value in &vec {
+                assert_eq!(find(*value), Some(*value));
+                assert_eq!(find(*value + 1), None);
+            }
+        }
+    }
+}
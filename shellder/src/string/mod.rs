@@ -6,8 +6,12 @@ use core::{
 
 mod ctype;
 mod mem;
+mod strerror;
+mod strsignal;
 pub use ctype::*;
 pub use mem::*;
+pub use strerror::*;
+pub use strsignal::*;
 
 /// Calculate the length of a null-terminated string
 ///
@@ -0,0 +1,152 @@
+//! Error descriptions
+
+use crate::errno::Errno;
+use core::{
+    ffi::{CStr, c_int},
+    fmt::{self, Write},
+};
+
+// Writes as much as fits into a buffer, always leaving room for a terminating NUL
+pub(super) struct TruncatingWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+    truncated: bool,
+}
+
+impl<'a> TruncatingWriter<'a> {
+    pub(super) fn new(buf: &'a mut [u8]) -> Self {
+        Self {
+            buf,
+            len: 0,
+            truncated: false,
+        }
+    }
+
+    // Terminate the string, returning whether it was truncated
+    pub(super) fn finish(self) -> bool {
+        if let Some(nul) = self.buf.get_mut(self.len) {
+            *nul = 0;
+        }
+        self.truncated || self.buf.is_empty()
+    }
+}
+
+impl Write for TruncatingWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let room = self.buf.len().saturating_sub(self.len + 1);
+        let count = s.len().min(room);
+        self.buf[self.len..self.len + count].copy_from_slice(&s.as_bytes()[..count]);
+        self.len += count;
+        self.truncated |= count < s.len();
+        Ok(())
+    }
+}
+
+// Write the description of `errnum` into `buf`, returning whether it was truncated
+fn describe(errnum: c_int, buf: &mut [u8]) -> bool {
+    let mut writer = TruncatingWriter::new(buf);
+    let _ = match strerror(errnum) {
+        Some(message) => writer.write_str(message.to_str().unwrap_or_default()),
+        None => write!(writer, "Unknown error {errnum}"),
+    };
+    writer.finish()
+}
+
+fn known(errnum: c_int) -> Option<Errno> {
+    let errno = Errno::from_int(errnum);
+    (errno.to_posix() == errno).then_some(errno)
+}
+
+/// Return the description of error number `errnum`, or `None` if it isn't a known error
+#[must_use]
+pub fn strerror(errnum: c_int) -> Option<&'static CStr> {
+    if errnum == 0 {
+        return Some(c"Success");
+    }
+    known(errnum).map(Errno::message)
+}
+
+/// Return the name of error number `errnum` (e.g. "EPERM"), or `None` if it isn't a known error
+#[must_use]
+pub fn strerrorname_np(errnum: c_int) -> Option<&'static CStr> {
+    if errnum == 0 {
+        return Some(c"0");
+    }
+    known(errnum).map(Errno::name)
+}
+
+/// Write the description of error number `errnum` into `buf` (XSI-compliant variant)
+///
+/// # Returns
+///
+/// `EINVAL` if `errnum` is not a known error, or `ERANGE` if the description had to be truncated
+/// to fit into `buf`
+pub fn strerror_r(errnum: c_int, buf: &mut [u8]) -> Result<(), Errno> {
+    let truncated = describe(errnum, buf);
+    if errnum != 0 && known(errnum).is_none() {
+        Err(Errno::EINVAL)
+    } else if truncated {
+        Err(Errno::ERANGE)
+    } else {
+        Ok(())
+    }
+}
+
+/// Return the description of error number `errnum` (GNU-specific variant). Descriptions of unknown
+/// errors are written, possibly truncated, to `buf`
+pub fn strerror_r_gnu(errnum: c_int, buf: &mut [u8]) -> &CStr {
+    if let Some(message) = strerror(errnum) {
+        return message;
+    }
+    if buf.is_empty() {
+        return c"";
+    }
+    describe(errnum, buf);
+    CStr::from_bytes_until_nul(buf).expect("Description is nul-terminated")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn descriptions() {
+        assert_eq!(strerror(0), Some(c"Success"));
+        assert_eq!(strerror(2), Some(c"No such file or directory"));
+        assert_eq!(strerror(41), None);
+        assert_eq!(strerror(Errno::CloysterFmtError.as_positive()), None);
+        assert_eq!(strerrorname_np(0), Some(c"0"));
+        assert_eq!(strerrorname_np(11), Some(c"EAGAIN"));
+        assert_eq!(strerrorname_np(4096), None);
+    }
+
+    #[test]
+    fn xsi_strerror_r() {
+        let mut buf = [0x7f; 64];
+        assert!(strerror_r(1, &mut buf).is_ok());
+        assert_eq!(
+            CStr::from_bytes_until_nul(&buf).unwrap(),
+            c"Operation not permitted"
+        );
+
+        assert_eq!(strerror_r(4096, &mut buf), Err(Errno::EINVAL));
+        assert_eq!(
+            CStr::from_bytes_until_nul(&buf).unwrap(),
+            c"Unknown error 4096"
+        );
+
+        let mut buf = [0x7f; 8];
+        assert_eq!(strerror_r(1, &mut buf), Err(Errno::ERANGE));
+        assert_eq!(CStr::from_bytes_until_nul(&buf).unwrap(), c"Operati");
+
+        assert_eq!(strerror_r(1, &mut []), Err(Errno::ERANGE));
+    }
+
+    #[test]
+    fn gnu_strerror_r() {
+        let mut buf = [0x7f; 8];
+        assert_eq!(strerror_r_gnu(1, &mut buf), c"Operation not permitted");
+        assert_eq!(strerror_r_gnu(-5, &mut buf), c"Unknown");
+        assert_eq!(strerror_r_gnu(-5, &mut []), c"");
+    }
+}
@@ -0,0 +1,88 @@
+//! Signal descriptions
+
+use super::strerror::TruncatingWriter;
+use crate::types::*;
+use core::{
+    ffi::{CStr, c_int},
+    fmt::Write,
+};
+
+const DESCRIPTIONS: [&CStr; 31] = [
+    c"Hangup",
+    c"Interrupt",
+    c"Quit",
+    c"Illegal instruction",
+    c"Trace/breakpoint trap",
+    c"Aborted",
+    c"Bus error",
+    c"Floating point exception",
+    c"Killed",
+    c"User defined signal 1",
+    c"Segmentation fault",
+    c"User defined signal 2",
+    c"Broken pipe",
+    c"Alarm clock",
+    c"Terminated",
+    c"Stack fault",
+    c"Child exited",
+    c"Continued",
+    c"Stopped (signal)",
+    c"Stopped",
+    c"Stopped (tty input)",
+    c"Stopped (tty output)",
+    c"Urgent I/O condition",
+    c"CPU time limit exceeded",
+    c"File size limit exceeded",
+    c"Virtual timer expired",
+    c"Profiling timer expired",
+    c"Window changed",
+    c"I/O possible",
+    c"Power failure",
+    c"Bad system call",
+];
+
+/// Return the description of signal `sig`. Descriptions of real-time and unknown signals are
+/// written, possibly truncated, to `buf`
+pub fn strsignal(sig: c_int, buf: &mut [u8]) -> &CStr {
+    if let Some(description) = usize::try_from(sig)
+        .ok()
+        .and_then(|sig| sig.checked_sub(1))
+        .and_then(|index| DESCRIPTIONS.get(index))
+    {
+        return description;
+    }
+    if buf.is_empty() {
+        return c"";
+    }
+
+    let mut writer = TruncatingWriter::new(buf);
+    let _ = if (SIGRTMIN..=SIGRTMAX).contains(&sig) {
+        write!(writer, "Real-time signal {}", sig - SIGRTMIN)
+    } else {
+        write!(writer, "Unknown signal {sig}")
+    };
+    writer.finish();
+    CStr::from_bytes_until_nul(buf).expect("Description is nul-terminated")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn descriptions() {
+        let mut buf = [0x7f; 32];
+        assert_eq!(strsignal(SIGHUP, &mut buf), c"Hangup");
+        assert_eq!(strsignal(SIGUSR1, &mut buf), c"User defined signal 1");
+        assert_eq!(strsignal(SIGSYS, &mut buf), c"Bad system call");
+        assert_eq!(strsignal(SIGRTMIN + 2, &mut buf), c"Real-time signal 2");
+        assert_eq!(strsignal(32, &mut buf), c"Unknown signal 32");
+        assert_eq!(strsignal(-1, &mut buf), c"Unknown signal -1");
+        assert_eq!(strsignal(0, &mut buf), c"Unknown signal 0");
+        assert_eq!(
+            strsignal(c_int::MIN, &mut buf),
+            c"Unknown signal -2147483648"
+        );
+        assert_eq!(strsignal(1000, &mut buf[..10]), c"Unknown s");
+    }
+}
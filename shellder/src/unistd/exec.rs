@@ -0,0 +1,293 @@
+//! Program execution with a `PATH` search
+use super::execve;
+use crate::{
+    errno::Errno,
+    malloc::{calloc, free},
+    stdlib::getenv,
+    types::PATH_MAX,
+};
+use core::{
+    ffi::{CStr, c_char},
+    ptr, slice,
+};
+
+// Searched when `PATH` isn't set, like glibc
+const DEFAULT_PATH: &CStr = c"/bin:/usr/bin";
+
+// Runs files the kernel doesn't know how to execute
+const SHELL: &CStr = c"/bin/sh";
+
+// Write `directory/file` to `buf`. An empty directory means the current directory
+fn join<'a>(buf: &'a mut [u8; PATH_MAX], directory: &[u8], file: &[u8]) -> Result<&'a CStr, Errno> {
+    let separator = usize::from(!directory.is_empty());
+    let len = directory.len() + separator + file.len();
+    if len >= PATH_MAX {
+        return Err(Errno::ENAMETOOLONG);
+    }
+    buf[..directory.len()].copy_from_slice(directory);
+    if separator == 1 {
+        buf[directory.len()] = b'/';
+    }
+    buf[directory.len() + separator..len].copy_from_slice(file);
+    buf[len] = 0;
+    CStr::from_bytes_with_nul(&buf[..=len]).map_err(|_| Errno::EINVAL)
+}
+
+// Try `exec` on `file` in each directory in the colon-separated `path`, returning the error that
+// should be reported once every directory has failed
+fn search_path(file: &CStr, path: &CStr, mut exec: impl FnMut(&CStr) -> Errno) -> Errno {
+    let mut buf = [0; PATH_MAX];
+    let mut denied = false;
+    for directory in path.to_bytes().split(|c| *c == b':') {
+        let err = match join(&mut buf, directory, file.to_bytes()) {
+            Ok(candidate) => exec(candidate),
+            Err(err) => err,
+        };
+        match err {
+            // Remember that the program exists, in case it isn't found anywhere else
+            Errno::EACCES => denied = true,
+            // The program isn't in this directory, or the directory can't be searched
+            Errno::ENOENT
+            | Errno::ENOTDIR
+            | Errno::ENAMETOOLONG
+            | Errno::ENODEV
+            | Errno::ESTALE
+            | Errno::ETIMEDOUT => {}
+            err => return err,
+        }
+    }
+    if denied { Errno::EACCES } else { Errno::ENOENT }
+}
+
+/// Get how many pointers [execvpe_in] needs for running a script with `argv`: the shell and the
+/// script, everything after `argv[0]`, then NULL
+///
+/// # Safety
+///
+/// `argv` must be a NULL-terminated array of pointers
+pub unsafe fn script_argv_len(argv: *const *const c_char) -> usize {
+    let mut argc = 0;
+    while !unsafe { *argv.add(argc) }.is_null() {
+        argc += 1;
+    }
+    argc.max(1) + 2
+}
+
+// Run `file` with `exec`, and if the kernel doesn't recognize it as an executable, run it as a
+// shell script, with `argv[0]` replaced by the shell and `file`. The shell's arguments are built
+// in `script_argv`
+unsafe fn exec_or_script(
+    file: &CStr,
+    argv: *const *const c_char,
+    script_argv: &mut [*const c_char],
+    mut exec: impl FnMut(&CStr, *const *const c_char) -> Errno,
+) -> Errno {
+    let err = exec(file, argv);
+    if err != Errno::ENOEXEC {
+        return err;
+    }
+
+    let len = unsafe { script_argv_len(argv) };
+    assert!(
+        script_argv.len() >= len,
+        "No room for the script's arguments"
+    );
+    script_argv[0] = SHELL.as_ptr();
+    script_argv[1] = file.as_ptr();
+    // `argv[0]` is replaced, and the rest, including the NULL, is copied
+    let rest = &mut script_argv[2..len];
+    if len > 3 {
+        rest.copy_from_slice(unsafe { slice::from_raw_parts(argv.add(1), rest.len()) });
+    } else {
+        rest[0] = ptr::null();
+    }
+    exec(SHELL, script_argv.as_ptr())
+}
+
+/// Like [execve], but if `file` doesn't contain a slash, look for it in the directories in
+/// `PATH`. A file that isn't an executable the kernel recognizes is run as a script by
+/// `/bin/sh`, like glibc
+///
+/// # Returns
+///
+/// Only returns on failure, with the reason
+///
+/// # Safety
+///
+/// See [execve]
+pub unsafe fn execvpe(
+    file: &CStr,
+    argv: *const *const c_char,
+    envp: *const *const c_char,
+) -> Errno {
+    let len = unsafe { script_argv_len(argv) };
+    let script_argv = match calloc(len, size_of::<*const c_char>()) {
+        Ok(script_argv) => script_argv.cast::<*const c_char>(),
+        Err(err) => return err,
+    };
+    let err = unsafe {
+        execvpe_in(
+            file,
+            argv,
+            envp,
+            slice::from_raw_parts_mut(script_argv.as_ptr(), len),
+        )
+    };
+    unsafe {
+        let _ = free(script_argv.cast());
+    }
+    err
+}
+
+/// Like [execvpe], but a script's arguments are built in `script_argv`, which needs
+/// [script_argv_len] pointers, instead of being allocated. For a child that shares the parent's
+/// memory, which would otherwise be left with the allocation after the exec
+///
+/// # Returns
+///
+/// Only returns on failure, with the reason
+///
+/// # Safety
+///
+/// See [execve]
+pub unsafe fn execvpe_in(
+    file: &CStr,
+    argv: *const *const c_char,
+    envp: *const *const c_char,
+    script_argv: &mut [*const c_char],
+) -> Errno {
+    if file.is_empty() {
+        return Errno::ENOENT;
+    }
+    let exec = |file: &CStr, argv| unsafe { execve(file, argv, envp) };
+    if file.to_bytes().contains(&b'/') {
+        return unsafe { exec_or_script(file, argv, script_argv, exec) };
+    }
+    let path = getenv(c"PATH").unwrap_or(DEFAULT_PATH);
+    search_path(file, path, |candidate| unsafe {
+        exec_or_script(candidate, argv, script_argv, exec)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    #[test]
+    fn joins_paths() {
+        let mut buf = [0; PATH_MAX];
+        assert_eq!(join(&mut buf, b"/bin", b"true"), Ok(c"/bin/true"));
+        assert_eq!(join(&mut buf, b"", b"true"), Ok(c"true"));
+        assert_eq!(
+            join(&mut buf, &[b'a'; PATH_MAX - 5], b"true"),
+            Err(Errno::ENAMETOOLONG)
+        );
+    }
+
+    #[test]
+    fn searches_each_directory() {
+        let mut tried = std::vec::Vec::new();
+        let err = search_path(c"ls", c"/nope::/denied:/bin", |candidate| {
+            tried.push(candidate.to_owned());
+            match candidate.to_bytes() {
+                b"/denied/ls" => Errno::EACCES,
+                b"/bin/ls" => Errno::E2BIG,
+                _ => Errno::ENOENT,
+            }
+        });
+        // Stops at the first error other than the program not being found
+        assert_eq!(err, Errno::E2BIG);
+        assert_eq!(tried, [c"/nope/ls", c"ls", c"/denied/ls", c"/bin/ls"]);
+
+        let err = search_path(c"ls", c"/denied:/nope", |candidate| {
+            if candidate == c"/denied/ls" {
+                Errno::EACCES
+            } else {
+                Errno::ENOENT
+            }
+        });
+        assert_eq!(err, Errno::EACCES);
+    }
+
+    // Run `exec_or_script` with a fake `exec`, returning its error and the program and arguments
+    // of each call to `exec`
+    fn exec_args(
+        argv: &[&CStr],
+        mut result: impl FnMut(&CStr) -> Errno,
+    ) -> (Errno, Vec<Vec<CString>>) {
+        let mut argv: Vec<_> = argv.iter().map(|arg| arg.as_ptr()).collect();
+        argv.push(ptr::null());
+        let mut script_argv = vec![ptr::dangling(); unsafe { script_argv_len(argv.as_ptr()) }];
+        let mut calls = Vec::new();
+        let err = unsafe {
+            exec_or_script(
+                c"dir/script",
+                argv.as_ptr(),
+                &mut script_argv,
+                |file, argv| {
+                    let mut call = vec![file.to_owned()];
+                    let mut arg = argv;
+                    while !(*arg).is_null() {
+                        call.push(CStr::from_ptr(*arg).to_owned());
+                        arg = arg.add(1);
+                    }
+                    calls.push(call);
+                    result(file)
+                },
+            )
+        };
+        (err, calls)
+    }
+
+    #[test]
+    fn runs_scripts_with_the_shell() {
+        let script_result = |file: &CStr| {
+            if file == SHELL {
+                Errno::E2BIG
+            } else {
+                Errno::ENOEXEC
+            }
+        };
+        assert_eq!(
+            exec_args(&[c"script", c"a", c"b"], script_result),
+            (
+                Errno::E2BIG,
+                vec![
+                    vec![
+                        c"dir/script".to_owned(),
+                        c"script".into(),
+                        c"a".into(),
+                        c"b".into()
+                    ],
+                    vec![
+                        SHELL.to_owned(),
+                        c"/bin/sh".into(),
+                        c"dir/script".into(),
+                        c"a".into(),
+                        c"b".into()
+                    ],
+                ]
+            )
+        );
+        // With no `argv[0]`, the shell still gets the script
+        assert_eq!(
+            exec_args(&[], script_result),
+            (
+                Errno::E2BIG,
+                vec![
+                    vec![c"dir/script".to_owned()],
+                    vec![SHELL.to_owned(), c"/bin/sh".into(), c"dir/script".into()],
+                ]
+            )
+        );
+        // Any other failure is reported as is
+        assert_eq!(
+            exec_args(&[c"script"], |_| Errno::EACCES),
+            (
+                Errno::EACCES,
+                vec![vec![c"dir/script".to_owned(), c"script".into()]]
+            )
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{errno::Errno, types::*};
 use core::{
-    ffi::{CStr, c_int, c_void},
+    ffi::{CStr, c_char, c_int, c_short, c_ulong, c_void},
     ptr::{self, NonNull},
 };
 use syscalls::Sysno;
@@ -29,6 +29,172 @@ pub unsafe fn read(fd: c_int, buf: *mut c_void, count: usize) -> Result<c_int, E
     )
 }
 
+/// Like [write], but at `offset` in the file, without moving the file offset
+///
+/// # Safety
+///
+/// See [write]
+pub unsafe fn pwrite(
+    fd: c_int,
+    buf: *const c_void,
+    count: usize,
+    offset: off_t,
+) -> Result<usize, Errno> {
+    assert!(!buf.is_null());
+    Ok(unsafe {
+        syscalls::syscall4(
+            Sysno::pwrite64,
+            fd as usize,
+            buf as usize,
+            count,
+            offset as usize,
+        )?
+    })
+}
+
+/// Like [read], but at `offset` in the file, without moving the file offset
+///
+/// # Safety
+///
+/// See [read]
+pub unsafe fn pread(
+    fd: c_int,
+    buf: *mut c_void,
+    count: usize,
+    offset: off_t,
+) -> Result<usize, Errno> {
+    assert!(!buf.is_null());
+    Ok(unsafe {
+        syscalls::syscall4(
+            Sysno::pread64,
+            fd as usize,
+            buf as usize,
+            count,
+            offset as usize,
+        )?
+    })
+}
+
+/// Write the buffers in `iov` to `fd` in order, as one write
+///
+/// # Returns
+///
+/// The number of bytes written, which may be less than the total length of the buffers
+///
+/// # Safety
+///
+/// Each buffer must be valid for reads of its length
+pub unsafe fn writev(fd: c_int, iov: &[IoVec]) -> Result<usize, Errno> {
+    Ok(
+        unsafe {
+            syscalls::syscall3(Sysno::writev, fd as usize, iov.as_ptr() as usize, iov.len())?
+        },
+    )
+}
+
+/// Read from `fd` into the buffers in `iov` in order, filling each before moving on
+///
+/// # Returns
+///
+/// The number of bytes read, or 0 on end of file
+///
+/// # Safety
+///
+/// Each buffer must be valid for writes of its length
+pub unsafe fn readv(fd: c_int, iov: &[IoVec]) -> Result<usize, Errno> {
+    Ok(unsafe { syscalls::syscall3(Sysno::readv, fd as usize, iov.as_ptr() as usize, iov.len())? })
+}
+
+/// Like [writev], but at `offset` in the file, without moving the file offset
+///
+/// # Safety
+///
+/// See [writev]
+pub unsafe fn pwritev(fd: c_int, iov: &[IoVec], offset: off_t) -> Result<usize, Errno> {
+    // The offset is passed as low and high words, but the high word is unused on 64-bit
+    Ok(unsafe {
+        syscalls::syscall5(
+            Sysno::pwritev,
+            fd as usize,
+            iov.as_ptr() as usize,
+            iov.len(),
+            offset as usize,
+            0,
+        )?
+    })
+}
+
+/// Like [readv], but at `offset` in the file, without moving the file offset
+///
+/// # Safety
+///
+/// See [readv]
+pub unsafe fn preadv(fd: c_int, iov: &[IoVec], offset: off_t) -> Result<usize, Errno> {
+    // The offset is passed as low and high words, but the high word is unused on 64-bit
+    Ok(unsafe {
+        syscalls::syscall5(
+            Sysno::preadv,
+            fd as usize,
+            iov.as_ptr() as usize,
+            iov.len(),
+            offset as usize,
+            0,
+        )?
+    })
+}
+
+/// Like [pwritev], with per-call `flags`. An `offset` of -1 writes at the file offset, and moves
+/// it
+///
+/// # Safety
+///
+/// See [writev]
+pub unsafe fn pwritev2(
+    fd: c_int,
+    iov: &[IoVec],
+    offset: off_t,
+    flags: RwfFlags,
+) -> Result<usize, Errno> {
+    // The offset is passed as low and high words, but the high word is unused on 64-bit
+    Ok(unsafe {
+        syscalls::syscall6(
+            Sysno::pwritev2,
+            fd as usize,
+            iov.as_ptr() as usize,
+            iov.len(),
+            offset as usize,
+            0,
+            flags.bits() as usize,
+        )?
+    })
+}
+
+/// Like [preadv], with per-call `flags`. An `offset` of -1 reads from the file offset, and moves
+/// it
+///
+/// # Safety
+///
+/// See [readv]
+pub unsafe fn preadv2(
+    fd: c_int,
+    iov: &[IoVec],
+    offset: off_t,
+    flags: RwfFlags,
+) -> Result<usize, Errno> {
+    // The offset is passed as low and high words, but the high word is unused on 64-bit
+    Ok(unsafe {
+        syscalls::syscall6(
+            Sysno::preadv2,
+            fd as usize,
+            iov.as_ptr() as usize,
+            iov.len(),
+            offset as usize,
+            0,
+            flags.bits() as usize,
+        )?
+    })
+}
+
 /// Wrapper for `mmap` syscall
 ///
 /// # Safety
@@ -57,16 +223,17 @@ pub unsafe fn mmap(
     NonNull::new(val as *mut c_void).ok_or(Errno::CloysterUnknown)
 }
 
-/// Wrapper for `munmap` syscall
+/// Wrapper for `munmap` syscall. `length` is rounded up to a whole number of pages
+///
+/// # Returns
+///
+/// `EINVAL` if `addr` isn't page-aligned or `length` is 0
 ///
 /// # Safety
 ///
 /// See man page
 pub unsafe fn munmap(addr: NonNull<c_void>, length: usize) -> Result<c_int, Errno> {
     let addr = addr.as_ptr();
-    assert!(length > 0);
-    assert!(((addr as usize) & 0xFFF) == 0);
-    assert!((length & 0xFFF) == 0);
     unsafe {
         syscalls::syscall2(Sysno::munmap, addr as usize, length)?;
     }
@@ -115,16 +282,15 @@ pub unsafe fn open(pathname: &CStr, flags: OpenFlags, mode_t: ModeFlags) -> Resu
 }
 
 /// Repositions the file offset of the file descriptor to the direction of `whence`
-pub fn lseek(fd: c_int, offset: off_t, whence: c_int) -> Result<c_int, Errno> {
+pub fn lseek(fd: c_int, offset: off_t, whence: c_int) -> Result<off_t, Errno> {
     Ok(unsafe {
         syscalls::syscall3(
             Sysno::lseek,
             fd.try_into()?,
-            offset.try_into()?,
+            offset as usize,
             whence.try_into()?,
         )?
-    }
-    .try_into()?)
+    } as off_t)
 }
 
 /// Close file descriptor
@@ -132,6 +298,305 @@ pub fn close(fd: c_int) -> Result<c_int, Errno> {
     Ok(unsafe { syscalls::syscall1(Sysno::close, fd.try_into()?)? }.try_into()?)
 }
 
+/// Get the metadata of the file open as `fd`
+pub fn fstat(fd: c_int) -> Result<Stat, Errno> {
+    let mut stat = Stat::default();
+    unsafe { syscalls::syscall2(Sysno::fstat, fd as usize, &raw mut stat as usize)? };
+    Ok(stat)
+}
+
+/// Get the metadata of the file at `path`, relative to the directory open as `dirfd` unless
+/// `path` is absolute. `dirfd` can be [AT_FDCWD] for the current directory
+pub fn fstatat(dirfd: c_int, path: &CStr, flags: AtFlags) -> Result<Stat, Errno> {
+    #[cfg(target_arch = "x86_64")]
+    const FSTATAT: Sysno = Sysno::newfstatat;
+    #[cfg(target_arch = "riscv64")]
+    const FSTATAT: Sysno = Sysno::fstatat;
+
+    let mut stat = Stat::default();
+    unsafe {
+        syscalls::syscall4(
+            FSTATAT,
+            dirfd as usize,
+            path.as_ptr() as usize,
+            &raw mut stat as usize,
+            flags.bits() as usize,
+        )?
+    };
+    Ok(stat)
+}
+
+/// Like [fstatat], but with more metadata. `mask` says which fields are wanted, though the
+/// kernel may fill in others or leave some out. Check [Statx::mask] for what was filled in
+pub fn statx(dirfd: c_int, path: &CStr, flags: AtFlags, mask: StatxMask) -> Result<Statx, Errno> {
+    let mut statx = Statx::default();
+    unsafe {
+        syscalls::syscall5(
+            Sysno::statx,
+            dirfd as usize,
+            path.as_ptr() as usize,
+            flags.bits() as usize,
+            mask.bits() as usize,
+            &raw mut statx as usize,
+        )?
+    };
+    Ok(statx)
+}
+
+/// Read directory entries from the directory open as `fd` into `buf`, as packed [Dirent]s each
+/// [Dirent::reclen] bytes long
+///
+/// # Returns
+///
+/// The number of bytes read, or 0 at the end of the directory
+pub fn getdents64(fd: c_int, buf: &mut [u8]) -> Result<usize, Errno> {
+    Ok(unsafe {
+        syscalls::syscall3(
+            Sysno::getdents64,
+            fd as usize,
+            buf.as_mut_ptr() as usize,
+            buf.len(),
+        )?
+    })
+}
+
+/// Create the directory `path`, relative to the directory open as `dirfd` unless `path` is
+/// absolute, with permissions `mode` less the umask
+pub fn mkdirat(dirfd: c_int, path: &CStr, mode: ModeFlags) -> Result<(), Errno> {
+    unsafe {
+        syscalls::syscall3(
+            Sysno::mkdirat,
+            dirfd as usize,
+            path.as_ptr() as usize,
+            mode.bits() as usize,
+        )?
+    };
+    Ok(())
+}
+
+/// Create the directory `path`
+pub fn mkdir(path: &CStr, mode: ModeFlags) -> Result<(), Errno> {
+    mkdirat(AT_FDCWD, path, mode)
+}
+
+/// Remove the name `path`, relative to the directory open as `dirfd` unless `path` is
+/// absolute. With [AtFlags::AT_REMOVEDIR], `path` must be an empty directory
+pub fn unlinkat(dirfd: c_int, path: &CStr, flags: AtFlags) -> Result<(), Errno> {
+    unsafe {
+        syscalls::syscall3(
+            Sysno::unlinkat,
+            dirfd as usize,
+            path.as_ptr() as usize,
+            flags.bits() as usize,
+        )?
+    };
+    Ok(())
+}
+
+/// Remove the name `path`, which can't be a directory
+pub fn unlink(path: &CStr) -> Result<(), Errno> {
+    unlinkat(AT_FDCWD, path, AtFlags::empty())
+}
+
+/// Remove the empty directory `path`
+pub fn rmdir(path: &CStr) -> Result<(), Errno> {
+    unlinkat(AT_FDCWD, path, AtFlags::AT_REMOVEDIR)
+}
+
+/// Rename `oldpath`, relative to `olddirfd`, to `newpath`, relative to `newdirfd`
+pub fn renameat2(
+    olddirfd: c_int,
+    oldpath: &CStr,
+    newdirfd: c_int,
+    newpath: &CStr,
+    flags: RenameFlags,
+) -> Result<(), Errno> {
+    unsafe {
+        syscalls::syscall5(
+            Sysno::renameat2,
+            olddirfd as usize,
+            oldpath.as_ptr() as usize,
+            newdirfd as usize,
+            newpath.as_ptr() as usize,
+            flags.bits() as usize,
+        )?
+    };
+    Ok(())
+}
+
+/// Rename `oldpath` to `newpath`, replacing `newpath` if it exists
+pub fn rename(oldpath: &CStr, newpath: &CStr) -> Result<(), Errno> {
+    renameat2(AT_FDCWD, oldpath, AT_FDCWD, newpath, RenameFlags::empty())
+}
+
+/// Make `newpath`, relative to `newdirfd`, a new name for `oldpath`, relative to `olddirfd`
+pub fn linkat(
+    olddirfd: c_int,
+    oldpath: &CStr,
+    newdirfd: c_int,
+    newpath: &CStr,
+    flags: AtFlags,
+) -> Result<(), Errno> {
+    unsafe {
+        syscalls::syscall5(
+            Sysno::linkat,
+            olddirfd as usize,
+            oldpath.as_ptr() as usize,
+            newdirfd as usize,
+            newpath.as_ptr() as usize,
+            flags.bits() as usize,
+        )?
+    };
+    Ok(())
+}
+
+/// Make `newpath` a new name for `oldpath`
+pub fn link(oldpath: &CStr, newpath: &CStr) -> Result<(), Errno> {
+    linkat(AT_FDCWD, oldpath, AT_FDCWD, newpath, AtFlags::empty())
+}
+
+/// Create a symbolic link at `linkpath`, relative to `newdirfd`, that points to `target`
+pub fn symlinkat(target: &CStr, newdirfd: c_int, linkpath: &CStr) -> Result<(), Errno> {
+    unsafe {
+        syscalls::syscall3(
+            Sysno::symlinkat,
+            target.as_ptr() as usize,
+            newdirfd as usize,
+            linkpath.as_ptr() as usize,
+        )?
+    };
+    Ok(())
+}
+
+/// Create a symbolic link at `linkpath` that points to `target`
+pub fn symlink(target: &CStr, linkpath: &CStr) -> Result<(), Errno> {
+    symlinkat(target, AT_FDCWD, linkpath)
+}
+
+/// Read what the symbolic link `path`, relative to `dirfd`, points to into `buf`
+///
+/// # Returns
+///
+/// The number of bytes read, which aren't null-terminated and are cut off at the end of `buf`
+pub fn readlinkat(dirfd: c_int, path: &CStr, buf: &mut [u8]) -> Result<usize, Errno> {
+    Ok(unsafe {
+        syscalls::syscall4(
+            Sysno::readlinkat,
+            dirfd as usize,
+            path.as_ptr() as usize,
+            buf.as_mut_ptr() as usize,
+            buf.len(),
+        )?
+    })
+}
+
+/// Read what the symbolic link `path` points to into `buf`. See [readlinkat]
+pub fn readlink(path: &CStr, buf: &mut [u8]) -> Result<usize, Errno> {
+    readlinkat(AT_FDCWD, path, buf)
+}
+
+/// Change the permissions of `path`, relative to `dirfd`
+pub fn fchmodat(dirfd: c_int, path: &CStr, mode: ModeFlags) -> Result<(), Errno> {
+    unsafe {
+        syscalls::syscall3(
+            Sysno::fchmodat,
+            dirfd as usize,
+            path.as_ptr() as usize,
+            mode.bits() as usize,
+        )?
+    };
+    Ok(())
+}
+
+/// Change the permissions of `path`
+pub fn chmod(path: &CStr, mode: ModeFlags) -> Result<(), Errno> {
+    fchmodat(AT_FDCWD, path, mode)
+}
+
+/// Change the permissions of the file open as `fd`
+pub fn fchmod(fd: c_int, mode: ModeFlags) -> Result<(), Errno> {
+    unsafe { syscalls::syscall2(Sysno::fchmod, fd as usize, mode.bits() as usize)? };
+    Ok(())
+}
+
+/// Change the owner and group of `path`, relative to `dirfd`. An ID of -1 is left unchanged
+pub fn fchownat(
+    dirfd: c_int,
+    path: &CStr,
+    owner: uid_t,
+    group: gid_t,
+    flags: AtFlags,
+) -> Result<(), Errno> {
+    unsafe {
+        syscalls::syscall5(
+            Sysno::fchownat,
+            dirfd as usize,
+            path.as_ptr() as usize,
+            owner as usize,
+            group as usize,
+            flags.bits() as usize,
+        )?
+    };
+    Ok(())
+}
+
+/// Change the owner and group of `path`. An ID of -1 is left unchanged
+pub fn chown(path: &CStr, owner: uid_t, group: gid_t) -> Result<(), Errno> {
+    fchownat(AT_FDCWD, path, owner, group, AtFlags::empty())
+}
+
+/// Change the owner and group of the file open as `fd`. An ID of -1 is left unchanged
+pub fn fchown(fd: c_int, owner: uid_t, group: gid_t) -> Result<(), Errno> {
+    unsafe { syscalls::syscall3(Sysno::fchown, fd as usize, owner as usize, group as usize)? };
+    Ok(())
+}
+
+/// Cut off or zero-extend the file at `path` to `length` bytes
+pub fn truncate(path: &CStr, length: off_t) -> Result<(), Errno> {
+    unsafe { syscalls::syscall2(Sysno::truncate, path.as_ptr() as usize, length as usize)? };
+    Ok(())
+}
+
+/// Cut off or zero-extend the file open as `fd` to `length` bytes
+pub fn ftruncate(fd: c_int, length: off_t) -> Result<(), Errno> {
+    unsafe { syscalls::syscall2(Sysno::ftruncate, fd as usize, length as usize)? };
+    Ok(())
+}
+
+/// Set the last access and modification times of `path`, relative to `dirfd`, or of the file
+/// open as `dirfd` if `path` is `None`. `None` for `times` sets both to the current time, as
+/// does [UTIME_NOW] in a nanoseconds field. [UTIME_OMIT] leaves that time unchanged
+pub fn utimensat(
+    dirfd: c_int,
+    path: Option<&CStr>,
+    times: Option<&[TimeSpec; 2]>,
+    flags: AtFlags,
+) -> Result<(), Errno> {
+    let path = path.map_or(ptr::null(), CStr::as_ptr);
+    let times = times.map_or(ptr::null(), |times| times.as_ptr());
+    unsafe {
+        syscalls::syscall4(
+            Sysno::utimensat,
+            dirfd as usize,
+            path as usize,
+            times as usize,
+            flags.bits() as usize,
+        )?
+    };
+    Ok(())
+}
+
+/// Set the mask of permissions new files are created without
+///
+/// # Returns
+///
+/// The old mask
+pub fn umask(mask: ModeFlags) -> ModeFlags {
+    let old = unsafe { syscalls::syscall1(Sysno::umask, mask.bits() as usize) }
+        .expect("umask() cannot fail");
+    ModeFlags::from_bits_retain(old as c_int)
+}
+
 /// Get time
 pub fn time() -> Result<time_t, Errno> {
     let tv = TimeVal::default();
@@ -159,6 +624,490 @@ pub fn nanosleep(req: *const TimeSpec, rem: Option<NonNull<TimeSpec>>) -> Result
     Ok(unsafe { syscalls::syscall2(Sysno::nanosleep, req as usize, rem)? }.try_into()?)
 }
 
+/// Fill `buf` with up to `buflen` random bytes from the kernel
+///
+/// # Safety
+///
+/// `buf` must point to a valid writable region of memory that is valid for at least `buflen` bytes
+pub unsafe fn getrandom(
+    buf: *mut c_void,
+    buflen: usize,
+    flags: GetRandomFlags,
+) -> Result<usize, Errno> {
+    assert!(!buf.is_null());
+    Ok(unsafe {
+        syscalls::syscall3(
+            Sysno::getrandom,
+            buf as usize,
+            buflen,
+            flags.bits().try_into()?,
+        )?
+    })
+}
+
+/// Fill `buf` with random bytes from the kernel, blocking until enough entropy is available
+///
+/// # Returns
+///
+/// `EIO` if `buf` is longer than 256 bytes
+pub fn getentropy(buf: &mut [u8]) -> Result<(), Errno> {
+    if buf.len() > 256 {
+        return Err(Errno::EIO);
+    }
+
+    let mut filled = 0;
+    while filled < buf.len() {
+        let rest = &mut buf[filled..];
+        match unsafe {
+            getrandom(
+                rest.as_mut_ptr().cast(),
+                rest.len(),
+                GetRandomFlags::empty(),
+            )
+        } {
+            Ok(count) => filled += count,
+            Err(Errno::EINTR) => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// Get the ID of the calling process
+pub fn getpid() -> pid_t {
+    unsafe { syscalls::syscall0(Sysno::getpid) }.expect("getpid() cannot fail") as pid_t
+}
+
+/// Get the ID of the calling thread
+pub fn gettid() -> pid_t {
+    unsafe { syscalls::syscall0(Sysno::gettid) }.expect("gettid() cannot fail") as pid_t
+}
+
+/// Send signal `sig` to thread `tid` in thread group `tgid`
+pub fn tgkill(tgid: pid_t, tid: pid_t, sig: c_int) -> Result<(), Errno> {
+    unsafe {
+        syscalls::syscall3(
+            Sysno::tgkill,
+            tgid.try_into()?,
+            tid.try_into()?,
+            sig.try_into()?,
+        )?
+    };
+    Ok(())
+}
+
+/// Send signal `sig` to the calling thread
+pub fn raise(sig: c_int) -> Result<(), Errno> {
+    tgkill(getpid(), gettid(), sig)
+}
+
+/// Send signal `sig` to process `pid`, or to a process group if `pid` is 0 or negative
+pub fn kill(pid: pid_t, sig: c_int) -> Result<(), Errno> {
+    // Negative PIDs are meaningful, so sign-extend rather than convert
+    unsafe { syscalls::syscall2(Sysno::kill, pid as usize, sig.try_into()?)? };
+    Ok(())
+}
+
+/// Examine and change the calling thread's blocked signals
+///
+/// `how` is one of [SIG_BLOCK], [SIG_UNBLOCK] or [SIG_SETMASK]
+pub fn rt_sigprocmask(
+    how: c_int,
+    set: Option<&SigSet>,
+    oldset: Option<&mut SigSet>,
+) -> Result<(), Errno> {
+    let set = set.map_or(ptr::null(), ptr::from_ref);
+    let oldset = oldset.map_or(ptr::null_mut(), ptr::from_mut);
+    unsafe {
+        syscalls::syscall4(
+            Sysno::rt_sigprocmask,
+            how.try_into()?,
+            set as usize,
+            oldset as usize,
+            size_of::<u64>(),
+        )?
+    };
+    Ok(())
+}
+
+/// Examine and change the action taken on receipt of signal `sig`
+///
+/// # Safety
+///
+/// The handler in `act` must be [SIG_DFL], [SIG_IGN], or a function that's safe to call from a
+/// signal handler. On x86_64, a handler also needs a restorer that calls `rt_sigreturn`
+pub unsafe fn rt_sigaction(
+    sig: c_int,
+    act: Option<&KernelSigAction>,
+    oldact: Option<&mut KernelSigAction>,
+) -> Result<(), Errno> {
+    let act = act.map_or(ptr::null(), ptr::from_ref);
+    let oldact = oldact.map_or(ptr::null_mut(), ptr::from_mut);
+    unsafe {
+        syscalls::syscall4(
+            Sysno::rt_sigaction,
+            sig.try_into()?,
+            act as usize,
+            oldact as usize,
+            size_of::<u64>(),
+        )?
+    };
+    Ok(())
+}
+
+/// Examine and change the action taken on receipt of signal `sig`
+///
+/// # Safety
+///
+/// Same as [rt_sigaction]. On x86_64, `act` needs [SigActionFlags::SA_RESTORER] and a restorer
+/// if it installs a handler
+pub unsafe fn sigaction(
+    sig: c_int,
+    act: Option<&SigAction>,
+    oldact: Option<&mut SigAction>,
+) -> Result<(), Errno> {
+    let act = act.map(KernelSigAction::from);
+    let mut old = KernelSigAction::default();
+    unsafe { rt_sigaction(sig, act.as_ref(), oldact.is_some().then_some(&mut old))? };
+    if let Some(oldact) = oldact {
+        *oldact = SigAction::from(&old);
+    }
+    Ok(())
+}
+
+/// Get the signals that are pending delivery to the calling thread
+pub fn sigpending() -> Result<SigSet, Errno> {
+    let mut set = SigSet::empty();
+    unsafe {
+        syscalls::syscall2(
+            Sysno::rt_sigpending,
+            ptr::from_mut(&mut set) as usize,
+            size_of::<u64>(),
+        )?
+    };
+    Ok(set)
+}
+
+/// Temporarily replace the blocked signals with `mask`, and wait for a signal to be handled
+///
+/// # Returns
+///
+/// `EINTR` once a handler has run. This never succeeds
+pub fn sigsuspend(mask: &SigSet) -> Result<(), Errno> {
+    unsafe {
+        syscalls::syscall2(
+            Sysno::rt_sigsuspend,
+            ptr::from_ref(mask) as usize,
+            size_of::<u64>(),
+        )?
+    };
+    Ok(())
+}
+
+/// Set and/or get the calling thread's alternate signal stack, on which handlers installed with
+/// [SigActionFlags::SA_ONSTACK] run
+///
+/// # Safety
+///
+/// Unless `ss` has [SS_DISABLE] set, it must describe a writable region of memory that stays
+/// valid for as long as it's in use
+pub unsafe fn sigaltstack(ss: Option<&StackT>, old_ss: Option<&mut StackT>) -> Result<(), Errno> {
+    let ss = ss.map_or(ptr::null(), ptr::from_ref);
+    let old_ss = old_ss.map_or(ptr::null_mut(), ptr::from_mut);
+    unsafe { syscalls::syscall2(Sysno::sigaltstack, ss as usize, old_ss as usize)? };
+    Ok(())
+}
+
+/// Create a file descriptor from which the signals in `mask` can be read as [SignalFdSigInfo]s,
+/// or change the mask of an existing one if `fd` isn't -1
+///
+/// The signals should be blocked, so they aren't handled as usual
+pub fn signalfd(fd: c_int, mask: &SigSet, flags: SignalFdFlags) -> Result<c_int, Errno> {
+    Ok(unsafe {
+        syscalls::syscall4(
+            Sysno::signalfd4,
+            fd as usize,
+            ptr::from_ref(mask) as usize,
+            size_of::<u64>(),
+            flags.bits().try_into()?,
+        )?
+    }
+    .try_into()?)
+}
+
+/// Create a child process that's a copy of the calling process
+///
+/// # Returns
+///
+/// The child's PID in the parent, and 0 in the child
+pub fn fork() -> Result<pid_t, Errno> {
+    // Not every architecture has a `fork` syscall, but they all have `clone`. The child gets a
+    // copy of the stack, so none of the other arguments are needed
+    Ok(unsafe { syscalls::syscall5(Sysno::clone, SIGCHLD as usize, 0, 0, 0, 0)? } as pid_t)
+}
+
+/// Create a child that starts by calling `function(arg)` on `stack`, and exits with its return
+/// value. `exit_signal` is sent to the parent when the child exits
+///
+/// # Returns
+///
+/// The child's ID, in the parent only
+///
+/// # Safety
+///
+/// `stack` must point just past the end of a writable region of memory, aligned to 16 bytes, which
+/// is big enough for `function` and stays valid while the child runs. If `flags` shares anything
+/// with the parent, `function` must not break the parent's assumptions about it
+pub unsafe fn clone(
+    flags: CloneFlags,
+    exit_signal: c_int,
+    stack: NonNull<u8>,
+    function: extern "C" fn(*mut c_void) -> c_int,
+    arg: *mut c_void,
+) -> Result<pid_t, Errno> {
+    let flags = flags.bits() | c_ulong::try_from(exit_signal)?;
+    let ret: usize;
+    // The child can't return from here, as it has none of the parent's stack frames
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        core::arch::asm!(
+            "syscall",
+            "test rax, rax",
+            "jnz 2f",
+            "mov rdi, r13",
+            "call r12",
+            "mov edi, eax",
+            "mov eax, {exit}",
+            "syscall",
+            "ud2",
+            "2:",
+            exit = const Sysno::exit as usize,
+            inlateout("rax") Sysno::clone as usize => ret,
+            in("rdi") flags,
+            in("rsi") stack.as_ptr(),
+            in("rdx") 0,
+            in("r10") 0,
+            in("r8") 0,
+            in("r12") function,
+            in("r13") arg,
+            lateout("rcx") _,
+            lateout("r11") _,
+            options(nostack),
+        );
+    }
+    #[cfg(target_arch = "riscv64")]
+    unsafe {
+        core::arch::asm!(
+            "ecall",
+            "bnez a0, 2f",
+            "mv a0, t1",
+            "jalr t0",
+            "li a7, {exit}",
+            "ecall",
+            "unimp",
+            "2:",
+            exit = const Sysno::exit as usize,
+            inlateout("a0") flags => ret,
+            in("a1") stack.as_ptr(),
+            in("a2") 0,
+            in("a3") 0,
+            in("a4") 0,
+            in("a7") Sysno::clone as usize,
+            in("t0") function,
+            in("t1") arg,
+            options(nostack),
+        );
+    }
+    Ok(syscalls::Errno::from_ret(ret)? as pid_t)
+}
+
+/// Replace the calling process with the program at `pathname`
+///
+/// # Returns
+///
+/// Only returns on failure, with the reason
+///
+/// # Safety
+///
+/// `argv` and `envp` must be NULL-terminated arrays of pointers to null-terminated strings
+pub unsafe fn execve(
+    pathname: &CStr,
+    argv: *const *const c_char,
+    envp: *const *const c_char,
+) -> Errno {
+    let result = unsafe {
+        syscalls::syscall3(
+            Sysno::execve,
+            pathname.as_ptr() as usize,
+            argv as usize,
+            envp as usize,
+        )
+    };
+    match result {
+        Ok(_) => unreachable!("execve() returned without an error"),
+        Err(err) => err.into(),
+    }
+}
+
+/// Wait for a child to change state
+///
+/// `pid` is a child's PID, -1 for any child, 0 for any child in the caller's process group, or
+/// less than -1 for any child in process group `-pid`
+///
+/// # Returns
+///
+/// The PID of the child whose status was reported, or 0 if [WaitFlags::WNOHANG] is set and no
+/// child has changed state
+pub fn wait4(
+    pid: pid_t,
+    status: Option<&mut c_int>,
+    options: WaitFlags,
+    rusage: Option<&mut RUsage>,
+) -> Result<pid_t, Errno> {
+    let status = status.map_or(ptr::null_mut(), ptr::from_mut);
+    let rusage = rusage.map_or(ptr::null_mut(), ptr::from_mut);
+    Ok(unsafe {
+        syscalls::syscall4(
+            Sysno::wait4,
+            // Negative PIDs are meaningful, so sign-extend rather than convert
+            pid as usize,
+            status as usize,
+            options.bits().try_into()?,
+            rusage as usize,
+        )?
+    } as pid_t)
+}
+
+/// Create a pipe
+///
+/// # Returns
+///
+/// The read and write ends, in that order
+pub fn pipe2(flags: OpenFlags) -> Result<[c_int; 2], Errno> {
+    let mut fds = [0; 2];
+    unsafe {
+        syscalls::syscall2(
+            Sysno::pipe2,
+            fds.as_mut_ptr() as usize,
+            flags.bits().try_into()?,
+        )?
+    };
+    Ok(fds)
+}
+
+/// Perform the file descriptor operation `cmd` on `fd`
+pub fn fcntl(fd: c_int, cmd: c_int, arg: usize) -> Result<c_int, Errno> {
+    Ok(unsafe { syscalls::syscall3(Sysno::fcntl, fd as usize, cmd.try_into()?, arg)? } as c_int)
+}
+
+/// Duplicate `oldfd` onto the lowest free file descriptor
+pub fn dup(oldfd: c_int) -> Result<c_int, Errno> {
+    Ok(unsafe { syscalls::syscall1(Sysno::dup, oldfd as usize)? } as c_int)
+}
+
+/// Make `newfd` a copy of `oldfd`, closing `newfd` first if it's open
+pub fn dup2(oldfd: c_int, newfd: c_int) -> Result<c_int, Errno> {
+    if oldfd == newfd {
+        // `dup3` rejects this, but it should succeed if `oldfd` is open
+        fcntl(oldfd, F_GETFD, 0)?;
+        return Ok(newfd);
+    }
+    dup3(oldfd, newfd, OpenFlags::empty())
+}
+
+/// Like [dup2], but `flags` can set [OpenFlags::O_CLOEXEC] on `newfd`, and `oldfd` and `newfd`
+/// must differ
+pub fn dup3(oldfd: c_int, newfd: c_int, flags: OpenFlags) -> Result<c_int, Errno> {
+    Ok(unsafe {
+        syscalls::syscall3(
+            Sysno::dup3,
+            oldfd as usize,
+            newfd as usize,
+            flags.bits().try_into()?,
+        )?
+    } as c_int)
+}
+
+/// Apply or remove an advisory lock on the whole file open as `fd`
+pub fn flock(fd: c_int, operation: FlockFlags) -> Result<(), Errno> {
+    unsafe { syscalls::syscall2(Sysno::flock, fd as usize, operation.bits().try_into()?)? };
+    Ok(())
+}
+
+/// Lock, unlock or test the `len` bytes from the current position of `fd` with process locks.
+/// Negative lengths lock the bytes before the current position, and 0 locks up to the end of the
+/// file
+pub fn lockf(fd: c_int, cmd: c_int, len: off_t) -> Result<(), Errno> {
+    let mut lock = Flock {
+        l_type: F_WRLCK,
+        l_whence: SEEK_CUR as c_short,
+        l_start: 0,
+        l_len: len,
+        l_pid: 0,
+    };
+    let cmd = match cmd {
+        F_TEST => {
+            lock.l_type = F_RDLCK;
+            fcntl(fd, F_GETLK, &raw mut lock as usize)?;
+            if lock.l_type == F_UNLCK || lock.l_pid == getpid() {
+                return Ok(());
+            }
+            return Err(Errno::EACCES);
+        }
+        F_ULOCK => {
+            lock.l_type = F_UNLCK;
+            F_SETLK
+        }
+        F_LOCK => F_SETLKW,
+        F_TLOCK => F_SETLK,
+        _ => return Err(Errno::EINVAL),
+    };
+    fcntl(fd, cmd, &raw mut lock as usize)?;
+    Ok(())
+}
+
+/// Get the absolute path of the working directory, in `buf`
+///
+/// # Returns
+///
+/// `ERANGE` if `buf` is too short, or `ENOENT` if the working directory isn't under the root
+/// directory
+pub fn getcwd(buf: &mut [u8]) -> Result<&CStr, Errno> {
+    let len = unsafe { syscalls::syscall2(Sysno::getcwd, buf.as_mut_ptr() as usize, buf.len())? };
+    // The kernel marks paths it can't reach from the root with a prefix instead of failing
+    if buf.first() != Some(&b'/') {
+        return Err(Errno::ENOENT);
+    }
+    Ok(CStr::from_bytes_with_nul(&buf[..len]).expect("getcwd() returns a null-terminated path"))
+}
+
+/// Change the working directory to `path`
+pub fn chdir(path: &CStr) -> Result<(), Errno> {
+    unsafe { syscalls::syscall1(Sysno::chdir, path.as_ptr() as usize)? };
+    Ok(())
+}
+
+/// Change the working directory to the directory open as `fd`
+pub fn fchdir(fd: c_int) -> Result<(), Errno> {
+    unsafe { syscalls::syscall1(Sysno::fchdir, fd as usize)? };
+    Ok(())
+}
+
+/// Start a new session, with the calling process as its leader
+///
+/// # Returns
+///
+/// The ID of the new session
+pub fn setsid() -> Result<pid_t, Errno> {
+    Ok(unsafe { syscalls::syscall0(Sysno::setsid)? } as pid_t)
+}
+
+/// Move process `pid` into process group `pgid`. 0 for either means the calling process
+pub fn setpgid(pid: pid_t, pgid: pid_t) -> Result<(), Errno> {
+    unsafe { syscalls::syscall2(Sysno::setpgid, pid.try_into()?, pgid.try_into()?)? };
+    Ok(())
+}
+
 /// # Safety
 ///
 /// See man page
@@ -1,6 +1,10 @@
 pub mod types;
 
+#[cfg(target_os = "linux")]
+mod exec;
 #[cfg(target_os = "linux")]
 mod linux;
 #[cfg(target_os = "linux")]
+pub use exec::*;
+#[cfg(target_os = "linux")]
 pub use linux::*;
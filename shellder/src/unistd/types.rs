@@ -1,7 +1,11 @@
 #![allow(non_camel_case_types)]
-use crate::types::time_t;
+use crate::errno::Errno;
+use crate::types::{TimeSpec, time_t};
 use bitflags::bitflags;
-use core::ffi::c_int;
+use core::{
+    ffi::{CStr, c_int, c_long, c_short, c_uint, c_ulong, c_void},
+    mem, ptr,
+};
 
 /// Special "file descriptor" value used in openat(2) and linkat(2)
 /// Makes the pathname interpreted relative to the current working directory, as opposed to a
@@ -9,15 +13,34 @@ use core::ffi::c_int;
 #[cfg(target_os = "linux")]
 pub const AT_FDCWD: c_int = -100;
 
+/// Maximum length of a path, including the terminating null byte
+pub const PATH_MAX: usize = 4096;
+
 /// Type used for file offsets
 pub type off_t = isize;
 
+/// `lseek()` whence: offsets are from the start of the file
+pub const SEEK_SET: c_int = 0;
+/// `lseek()` whence: offsets are from the current position
+pub const SEEK_CUR: c_int = 1;
+/// `lseek()` whence: offsets are from the end of the file
+pub const SEEK_END: c_int = 2;
+
 /// Represents the SUB-SECOND time in microseconds
 pub type suseconds_t = i32;
 
 /// Clock ID for clock ad timer functions
 pub type clockid_t = i32;
 
+/// Process ID
+pub type pid_t = c_int;
+
+/// User ID
+pub type uid_t = c_uint;
+
+/// Group ID
+pub type gid_t = c_uint;
+
 bitflags! {
     /// Flags for [mmap]
     #[derive(Copy, Clone, PartialEq, Eq)]
@@ -41,6 +64,20 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Flags for `getrandom()`
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct GetRandomFlags: c_uint {
+        /// Fail with `EAGAIN` instead of blocking if the entropy pool isn't initialized
+        const GRND_NONBLOCK = 0x1;
+        /// Draw from the blocking `/dev/random` pool instead of `/dev/urandom`
+        const GRND_RANDOM = 0x2;
+        /// Don't block, even if the entropy pool isn't initialized
+        const GRND_INSECURE = 0x4;
+    }
+}
+
 bitflags! {
     /// Protection flags for [mmap]
     #[derive(Copy, Clone, PartialEq, Eq)]
@@ -82,6 +119,12 @@ bitflags! {
         const OWNER_WRITE = 0x80;
         /// Group has execute permissions
         const OWNER_READ = 0x100;
+        /// Others can't delete or rename files they don't own in this directory
+        const STICKY = 0o1000;
+        /// Run with the group of the file
+        const SET_GID = 0o2000;
+        /// Run as the owner of the file
+        const SET_UID = 0o4000;
     }
 }
 
@@ -108,9 +151,351 @@ bitflags! {
         const O_WRONLY = 0x1;
         /// Read or write
         const O_RDWR = 0x2;
+        /// Create the file if it doesn't exist
+        const O_CREAT = 0o100;
+        /// With [OpenFlags::O_CREAT], fail with `EEXIST` if the file exists
+        const O_EXCL = 0o200;
+        /// Don't make a terminal the controlling terminal
+        const O_NOCTTY = 0o400;
+        /// Truncate the file to length 0
+        const O_TRUNC = 0o1000;
+        /// Write to the end of the file
+        const O_APPEND = 0o2000;
+        /// Fail with `EAGAIN` instead of blocking
+        const O_NONBLOCK = 0o4000;
+        /// Fail with `ENOTDIR` unless the path is a directory
+        const O_DIRECTORY = 0o200000;
+        /// Close the descriptor on a successful `execve()`
+        const O_CLOEXEC = 0o2000000;
+    }
+}
+
+/// Hangup of the controlling terminal
+pub const SIGHUP: c_int = 1;
+/// Interrupt from the keyboard
+pub const SIGINT: c_int = 2;
+/// Quit from the keyboard
+pub const SIGQUIT: c_int = 3;
+/// Illegal instruction
+pub const SIGILL: c_int = 4;
+/// Trace or breakpoint trap
+pub const SIGTRAP: c_int = 5;
+/// Abnormal termination, as by `abort()`
+pub const SIGABRT: c_int = 6;
+/// Bus error
+pub const SIGBUS: c_int = 7;
+/// Arithmetic error
+pub const SIGFPE: c_int = 8;
+/// Kill, which can't be caught or ignored
+pub const SIGKILL: c_int = 9;
+/// User-defined signal 1
+pub const SIGUSR1: c_int = 10;
+/// Invalid memory reference
+pub const SIGSEGV: c_int = 11;
+/// User-defined signal 2
+pub const SIGUSR2: c_int = 12;
+/// Write to a pipe with no readers
+pub const SIGPIPE: c_int = 13;
+/// Timer from `alarm()`
+pub const SIGALRM: c_int = 14;
+/// Termination request
+pub const SIGTERM: c_int = 15;
+/// Coprocessor stack fault
+pub const SIGSTKFLT: c_int = 16;
+/// Child stopped or terminated
+pub const SIGCHLD: c_int = 17;
+/// Continue if stopped
+pub const SIGCONT: c_int = 18;
+/// Stop, which can't be caught or ignored
+pub const SIGSTOP: c_int = 19;
+/// Stop from the terminal
+pub const SIGTSTP: c_int = 20;
+/// Terminal input for a background process
+pub const SIGTTIN: c_int = 21;
+/// Terminal output for a background process
+pub const SIGTTOU: c_int = 22;
+/// Urgent condition on a socket
+pub const SIGURG: c_int = 23;
+/// CPU time limit exceeded
+pub const SIGXCPU: c_int = 24;
+/// File size limit exceeded
+pub const SIGXFSZ: c_int = 25;
+/// Virtual alarm clock
+pub const SIGVTALRM: c_int = 26;
+/// Profiling timer expired
+pub const SIGPROF: c_int = 27;
+/// Window resize
+pub const SIGWINCH: c_int = 28;
+/// I/O now possible
+pub const SIGIO: c_int = 29;
+/// Power failure
+pub const SIGPWR: c_int = 30;
+/// Bad system call
+pub const SIGSYS: c_int = 31;
+
+// Real-time signals glibc reserves for its threading implementation. We do the same, so programs
+// see the same set of usable signals
+const RESERVED_SIGNALS: [c_int; 2] = [32, 33];
+
+/// First real-time signal available to programs
+pub const SIGRTMIN: c_int = 34;
+/// Last real-time signal
+pub const SIGRTMAX: c_int = 64;
+
+/// Number of signals the kernel supports, plus one
+pub const NSIG: c_int = 65;
+
+/// Default signal disposition
+pub const SIG_DFL: usize = 0;
+/// Ignore the signal
+pub const SIG_IGN: usize = 1;
+/// Returned by `signal()` on error
+pub const SIG_ERR: usize = usize::MAX;
+
+/// Add signals to the blocked set, for `sigprocmask()`
+pub const SIG_BLOCK: c_int = 0;
+/// Remove signals from the blocked set, for `sigprocmask()`
+pub const SIG_UNBLOCK: c_int = 1;
+/// Replace the blocked set, for `sigprocmask()`
+pub const SIG_SETMASK: c_int = 2;
+
+/// Set of signals
+/// Laid out like glibc's `sigset_t`, of which the kernel only uses the first 64 bits
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(C)]
+pub struct SigSet {
+    bits: [c_ulong; 16],
+}
+
+impl Default for SigSet {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl SigSet {
+    /// A set containing no signals
+    pub const fn empty() -> Self {
+        Self { bits: [0; 16] }
+    }
+
+    /// A set containing every signal, except the reserved ones
+    pub const fn full() -> Self {
+        let mut bits = [0; 16];
+        bits[0] =
+            c_ulong::MAX & !(1 << (RESERVED_SIGNALS[0] - 1)) & !(1 << (RESERVED_SIGNALS[1] - 1));
+        Self { bits }
+    }
+
+    // Word and bit of signal `signum`
+    fn position(signum: c_int) -> Result<(usize, c_ulong), Errno> {
+        if !(1..NSIG).contains(&signum) {
+            return Err(Errno::EINVAL);
+        }
+        let index = (signum - 1) as usize;
+        Ok((
+            index / c_ulong::BITS as usize,
+            1 << (index % c_ulong::BITS as usize),
+        ))
+    }
+
+    /// Add signal `signum` to the set
+    ///
+    /// # Returns
+    ///
+    /// `EINVAL` if `signum` isn't a valid signal, or is reserved
+    pub fn add(&mut self, signum: c_int) -> Result<(), Errno> {
+        if RESERVED_SIGNALS.contains(&signum) {
+            return Err(Errno::EINVAL);
+        }
+        let (word, bit) = Self::position(signum)?;
+        self.bits[word] |= bit;
+        Ok(())
+    }
+
+    /// Remove signal `signum` from the set
+    ///
+    /// # Returns
+    ///
+    /// `EINVAL` if `signum` isn't a valid signal, or is reserved
+    pub fn remove(&mut self, signum: c_int) -> Result<(), Errno> {
+        if RESERVED_SIGNALS.contains(&signum) {
+            return Err(Errno::EINVAL);
+        }
+        let (word, bit) = Self::position(signum)?;
+        self.bits[word] &= !bit;
+        Ok(())
+    }
+
+    /// Check if signal `signum` is in the set
+    pub fn contains(&self, signum: c_int) -> Result<bool, Errno> {
+        let (word, bit) = Self::position(signum)?;
+        Ok(self.bits[word] & bit != 0)
+    }
+
+    /// Check if the set contains no signals
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|word| *word == 0)
+    }
+
+    // The part of the set the kernel looks at
+    pub(crate) fn kernel_bits(&self) -> u64 {
+        self.bits[0]
+    }
+
+    pub(crate) fn from_kernel_bits(bits: u64) -> Self {
+        let mut set = Self::empty();
+        set.bits[0] = bits;
+        set
+    }
+}
+
+bitflags! {
+    /// Flags for [SigAction]
+    #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct SigActionFlags: c_int {
+        /// Don't send `SIGCHLD` when children stop or continue
+        const SA_NOCLDSTOP = 0x1;
+        /// Don't turn terminated children into zombies
+        const SA_NOCLDWAIT = 0x2;
+        /// The handler takes three arguments, and is `sa_sigaction` rather than `sa_handler`
+        const SA_SIGINFO = 0x4;
+        /// `restorer` is set
+        const SA_RESTORER = 0x04000000;
+        /// Run the handler on the alternate signal stack
+        const SA_ONSTACK = 0x08000000;
+        /// Restart syscalls interrupted by the handler
+        const SA_RESTART = 0x10000000;
+        /// Don't block the signal while its handler runs
+        const SA_NODEFER = 0x40000000;
+        /// Restore the default action once the handler has been called
+        const SA_RESETHAND = 0x80000000_u32 as c_int;
+    }
+}
+
+/// Signal action
+/// Laid out like glibc's `struct sigaction`
+#[derive(Debug, Default, Copy, Clone)]
+#[repr(C)]
+pub struct SigAction {
+    /// [SIG_DFL], [SIG_IGN], or the address of the handler
+    pub handler: usize,
+    /// Signals blocked while the handler runs
+    pub mask: SigSet,
+    pub flags: SigActionFlags,
+    /// Called when the handler returns, to restore the interrupted context
+    pub restorer: usize,
+}
+
+/// Signal action, laid out like the kernel's `struct sigaction`
+#[derive(Debug, Default, Copy, Clone)]
+#[repr(C)]
+pub struct KernelSigAction {
+    pub handler: usize,
+    pub flags: c_ulong,
+    // Other architectures return from handlers through the vDSO
+    #[cfg(target_arch = "x86_64")]
+    pub restorer: usize,
+    pub mask: u64,
+}
+
+impl From<&SigAction> for KernelSigAction {
+    fn from(act: &SigAction) -> Self {
+        Self {
+            handler: act.handler,
+            flags: act.flags.bits() as c_uint as c_ulong,
+            #[cfg(target_arch = "x86_64")]
+            restorer: act.restorer,
+            mask: act.mask.kernel_bits(),
+        }
+    }
+}
+
+impl From<&KernelSigAction> for SigAction {
+    fn from(act: &KernelSigAction) -> Self {
+        Self {
+            handler: act.handler,
+            mask: SigSet::from_kernel_bits(act.mask),
+            flags: SigActionFlags::from_bits_retain(act.flags as c_int),
+            #[cfg(target_arch = "x86_64")]
+            restorer: act.restorer,
+            #[cfg(not(target_arch = "x86_64"))]
+            restorer: 0,
+        }
+    }
+}
+
+/// The thread is running on its alternate signal stack, for [StackT]
+pub const SS_ONSTACK: c_int = 1;
+/// The alternate signal stack is disabled, for [StackT]
+pub const SS_DISABLE: c_int = 2;
+/// Smallest alternate signal stack the kernel accepts
+pub const MINSIGSTKSZ: usize = 2048;
+/// Recommended size of an alternate signal stack
+pub const SIGSTKSZ: usize = 8192;
+
+/// Alternate signal stack
+/// Corresponds to the C `stack_t` struct
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct StackT {
+    pub sp: *mut c_void,
+    pub flags: c_int,
+    pub size: usize,
+}
+
+impl Default for StackT {
+    fn default() -> Self {
+        Self {
+            sp: ptr::null_mut(),
+            flags: 0,
+            size: 0,
+        }
+    }
+}
+
+bitflags! {
+    /// Flags for `signalfd()`
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct SignalFdFlags: c_int {
+        /// Reads fail with `EAGAIN` instead of blocking
+        const SFD_NONBLOCK = 0o4000;
+        /// Close the file descriptor on `exec()`
+        const SFD_CLOEXEC = 0o2000000;
     }
 }
 
+/// A signal read from a `signalfd()` file descriptor
+/// Corresponds to the C `signalfd_siginfo` struct
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct SignalFdSigInfo {
+    pub ssi_signo: u32,
+    pub ssi_errno: i32,
+    pub ssi_code: i32,
+    pub ssi_pid: u32,
+    pub ssi_uid: u32,
+    pub ssi_fd: i32,
+    pub ssi_tid: u32,
+    pub ssi_band: u32,
+    pub ssi_overrun: u32,
+    pub ssi_trapno: u32,
+    pub ssi_status: i32,
+    pub ssi_int: i32,
+    pub ssi_ptr: u64,
+    pub ssi_utime: u64,
+    pub ssi_stime: u64,
+    pub ssi_addr: u64,
+    pub ssi_addr_lsb: u16,
+    _pad2: u16,
+    pub ssi_syscall: i32,
+    pub ssi_call_addr: u64,
+    pub ssi_arch: u32,
+    _pad: [u8; 28],
+}
+
 /// Codes for linux's `arch_prctl()` syscall
 #[derive(Copy, Clone, PartialEq, Eq)]
 #[repr(i32)]
@@ -128,3 +513,531 @@ pub struct TimeVal {
     pub seconds: time_t,
     pub useconds: suseconds_t,
 }
+
+bitflags! {
+    /// Options for `waitpid()` and `wait4()`
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct WaitFlags: c_int {
+        /// Return immediately if no child has changed state
+        const WNOHANG = 0x1;
+        /// Also report children that have stopped
+        const WUNTRACED = 0x2;
+        /// Also report stopped children that have been resumed by `SIGCONT`
+        const WCONTINUED = 0x8;
+    }
+}
+
+/// Resource usage of a process
+/// Corresponds to the POSIX `rusage` struct
+#[derive(Debug, Default, Copy, Clone)]
+#[repr(C)]
+pub struct RUsage {
+    /// User CPU time used
+    pub utime: TimeVal,
+    /// System CPU time used
+    pub stime: TimeVal,
+    /// Maximum resident set size, in kilobytes
+    pub maxrss: c_long,
+    pub ixrss: c_long,
+    pub idrss: c_long,
+    pub isrss: c_long,
+    /// Page faults serviced without I/O
+    pub minflt: c_long,
+    /// Page faults that needed I/O
+    pub majflt: c_long,
+    pub nswap: c_long,
+    /// Number of times the filesystem had to perform input
+    pub inblock: c_long,
+    /// Number of times the filesystem had to perform output
+    pub oublock: c_long,
+    pub msgsnd: c_long,
+    pub msgrcv: c_long,
+    pub nsignals: c_long,
+    /// Voluntary context switches
+    pub nvcsw: c_long,
+    /// Involuntary context switches
+    pub nivcsw: c_long,
+}
+
+bitflags! {
+    /// What a child created by `clone()` shares with its parent. The low byte holds the signal
+    /// sent to the parent when the child exits, which is passed separately
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct CloneFlags: c_ulong {
+        /// Share the address space
+        const CLONE_VM = 0x100;
+        /// Share the working directory, root and umask
+        const CLONE_FS = 0x200;
+        /// Share the file descriptor table
+        const CLONE_FILES = 0x400;
+        /// Share signal handlers
+        const CLONE_SIGHAND = 0x800;
+        /// Suspend the parent until the child execs or exits
+        const CLONE_VFORK = 0x4000;
+        /// Put the child in the caller's thread group
+        const CLONE_THREAD = 0x10000;
+    }
+}
+
+/// `fcntl()` command to duplicate a file descriptor onto the lowest free one at or above the
+/// argument
+pub const F_DUPFD: c_int = 0;
+/// `fcntl()` command to get the file descriptor flags
+pub const F_GETFD: c_int = 1;
+/// `fcntl()` command to set the file descriptor flags
+pub const F_SETFD: c_int = 2;
+/// `fcntl()` command to get the file status flags
+pub const F_GETFL: c_int = 3;
+/// `fcntl()` command to set the file status flags
+pub const F_SETFL: c_int = 4;
+/// `fcntl()` command to find a process lock that would block the [Flock] argument
+pub const F_GETLK: c_int = 5;
+/// `fcntl()` command to take or release a process lock, failing if it's held
+pub const F_SETLK: c_int = 6;
+/// `fcntl()` command to take or release a process lock, waiting if it's held
+pub const F_SETLKW: c_int = 7;
+/// Like [F_GETLK], for locks owned by the open file description
+pub const F_OFD_GETLK: c_int = 36;
+/// Like [F_SETLK], for locks owned by the open file description
+pub const F_OFD_SETLK: c_int = 37;
+/// Like [F_SETLKW], for locks owned by the open file description
+pub const F_OFD_SETLKW: c_int = 38;
+/// Like [F_DUPFD], with [FD_CLOEXEC] set on the new descriptor
+pub const F_DUPFD_CLOEXEC: c_int = 1030;
+/// File descriptor flag: close the descriptor on a successful `execve()`
+pub const FD_CLOEXEC: c_int = 1;
+
+/// [Flock] type: shared lock
+pub const F_RDLCK: c_short = 0;
+/// [Flock] type: exclusive lock
+pub const F_WRLCK: c_short = 1;
+/// [Flock] type: no lock
+pub const F_UNLCK: c_short = 2;
+
+/// A byte range lock for `fcntl()`, laid out like `struct flock`
+#[derive(Debug, Default, Copy, Clone)]
+#[repr(C)]
+pub struct Flock {
+    /// [F_RDLCK], [F_WRLCK] or [F_UNLCK]
+    pub l_type: c_short,
+    /// What [Flock::l_start] is relative to, like `lseek()`'s `whence`
+    pub l_whence: c_short,
+    /// Offset of the range
+    pub l_start: off_t,
+    /// Length of the range. 0 means up to the end of the file, however long it gets
+    pub l_len: off_t,
+    /// Process holding a conflicting lock, from [F_GETLK]
+    pub l_pid: pid_t,
+}
+
+bitflags! {
+    /// Operations for `flock()`
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct FlockFlags: c_int {
+        /// Take a shared lock
+        const LOCK_SH = 1;
+        /// Take an exclusive lock
+        const LOCK_EX = 2;
+        /// Fail with `EWOULDBLOCK` instead of blocking
+        const LOCK_NB = 4;
+        /// Release the lock
+        const LOCK_UN = 8;
+    }
+}
+
+/// `lockf()` command to release a lock
+pub const F_ULOCK: c_int = 0;
+/// `lockf()` command to take a lock, waiting if it's held
+pub const F_LOCK: c_int = 1;
+/// `lockf()` command to take a lock, failing if it's held
+pub const F_TLOCK: c_int = 2;
+/// `lockf()` command to check whether another process holds a lock
+pub const F_TEST: c_int = 3;
+
+bitflags! {
+    /// Which of a [SpawnAttr]'s attributes `posix_spawn()` applies
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct SpawnFlags: c_short {
+        /// Move the child into [SpawnAttr::pgroup], or a new group if that's 0
+        const POSIX_SPAWN_SETPGROUP = 0x02;
+        /// Reset the signals in [SpawnAttr::sigdefault] to their default actions
+        const POSIX_SPAWN_SETSIGDEF = 0x04;
+        /// Block [SpawnAttr::sigmask] in the child instead of the caller's blocked signals
+        const POSIX_SPAWN_SETSIGMASK = 0x08;
+        /// Suspend the caller until the child has exec'd. Always the case here
+        const POSIX_SPAWN_USEVFORK = 0x40;
+        /// Start a new session for the child
+        const POSIX_SPAWN_SETSID = 0x80;
+    }
+}
+
+/// Attributes of a child started by `posix_spawn()`
+/// Laid out like glibc's `posix_spawnattr_t`
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct SpawnAttr {
+    pub flags: SpawnFlags,
+    pub pgroup: pid_t,
+    pub sigdefault: SigSet,
+    pub sigmask: SigSet,
+    // Scheduling parameters, which aren't supported
+    sched_priority: c_int,
+    policy: c_int,
+    pad: [c_int; 16],
+}
+
+impl Default for SpawnAttr {
+    fn default() -> Self {
+        Self {
+            flags: SpawnFlags::empty(),
+            pgroup: 0,
+            sigdefault: SigSet::empty(),
+            sigmask: SigSet::empty(),
+            sched_priority: 0,
+            policy: 0,
+            pad: [0; 16],
+        }
+    }
+}
+
+/// One buffer of a vectored read or write
+/// Corresponds to the C `struct iovec`
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct IoVec {
+    pub base: *mut c_void,
+    pub len: usize,
+}
+
+impl From<&[u8]> for IoVec {
+    /// Only for writing. The buffer must outlive the [IoVec]
+    fn from(buf: &[u8]) -> Self {
+        Self {
+            base: buf.as_ptr().cast_mut().cast(),
+            len: buf.len(),
+        }
+    }
+}
+
+bitflags! {
+    /// Flags for `preadv2()` and `pwritev2()`
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct RwfFlags: c_int {
+        /// Poll for completion, on devices that support it
+        const RWF_HIPRI = 0x1;
+        /// Like [OpenFlags] `O_DSYNC`, for this write
+        const RWF_DSYNC = 0x2;
+        /// Like [OpenFlags] `O_SYNC`, for this write
+        const RWF_SYNC = 0x4;
+        /// Fail with `EAGAIN` instead of waiting for data that isn't available yet
+        const RWF_NOWAIT = 0x8;
+        /// Like [OpenFlags::O_APPEND], for this write
+        const RWF_APPEND = 0x10;
+    }
+}
+
+bitflags! {
+    /// Flags for `fstatat()`, `statx()` and the other `*at()` syscalls
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct AtFlags: c_int {
+        /// Act on a symbolic link itself, rather than what it points to
+        const AT_SYMLINK_NOFOLLOW = 0x100;
+        /// `unlinkat()`: Remove a directory instead of a file
+        const AT_REMOVEDIR = 0x200;
+        /// `linkat()`: Link to what a symbolic link points to, rather than the link itself
+        const AT_SYMLINK_FOLLOW = 0x400;
+        /// Don't automount the last component of the path
+        const AT_NO_AUTOMOUNT = 0x800;
+        /// An empty path means the file open as the directory descriptor
+        const AT_EMPTY_PATH = 0x1000;
+        /// `statx()`: Sync attributes with a remote server first
+        const AT_STATX_FORCE_SYNC = 0x2000;
+        /// `statx()`: Don't sync attributes with a remote server
+        const AT_STATX_DONT_SYNC = 0x4000;
+    }
+}
+
+bitflags! {
+    /// Flags for `renameat2()`
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct RenameFlags: c_uint {
+        /// Fail with `EEXIST` instead of replacing the new path
+        const RENAME_NOREPLACE = 0x1;
+        /// Swap the old and new paths, which must both exist
+        const RENAME_EXCHANGE = 0x2;
+        /// Leave a whiteout in place of the old path, for overlay filesystems
+        const RENAME_WHITEOUT = 0x4;
+    }
+}
+
+/// `utimensat()`: Set the timestamp to the current time
+pub const UTIME_NOW: i64 = (1 << 30) - 1;
+/// `utimensat()`: Leave the timestamp unchanged
+pub const UTIME_OMIT: i64 = (1 << 30) - 2;
+
+/// Mask of the file type bits of a mode
+pub const S_IFMT: c_uint = 0o170000;
+/// File type: socket
+pub const S_IFSOCK: c_uint = 0o140000;
+/// File type: symbolic link
+pub const S_IFLNK: c_uint = 0o120000;
+/// File type: regular file
+pub const S_IFREG: c_uint = 0o100000;
+/// File type: block device
+pub const S_IFBLK: c_uint = 0o060000;
+/// File type: directory
+pub const S_IFDIR: c_uint = 0o040000;
+/// File type: character device
+pub const S_IFCHR: c_uint = 0o020000;
+/// File type: FIFO
+pub const S_IFIFO: c_uint = 0o010000;
+
+/// File metadata
+/// Corresponds to the C `stat` struct, which is laid out differently on each architecture
+#[cfg(target_arch = "x86_64")]
+#[derive(Debug, Default, Copy, Clone)]
+#[repr(C)]
+pub struct Stat {
+    /// Device containing the file
+    pub dev: u64,
+    /// Inode number
+    pub ino: u64,
+    /// Number of hard links
+    pub nlink: u64,
+    /// File type and permissions
+    pub mode: c_uint,
+    pub uid: c_uint,
+    pub gid: c_uint,
+    pad0: c_int,
+    /// Device the file represents, if it's a special file
+    pub rdev: u64,
+    /// Size in bytes
+    pub size: i64,
+    /// Preferred block size for I/O
+    pub blksize: i64,
+    /// Number of 512 byte blocks allocated
+    pub blocks: i64,
+    /// Last access
+    pub atime: TimeSpec,
+    /// Last modification
+    pub mtime: TimeSpec,
+    /// Last status change
+    pub ctime: TimeSpec,
+    unused: [i64; 3],
+}
+
+/// File metadata
+/// Corresponds to the C `stat` struct, which is laid out differently on each architecture
+#[cfg(target_arch = "riscv64")]
+#[derive(Debug, Default, Copy, Clone)]
+#[repr(C)]
+pub struct Stat {
+    /// Device containing the file
+    pub dev: u64,
+    /// Inode number
+    pub ino: u64,
+    /// File type and permissions
+    pub mode: c_uint,
+    /// Number of hard links
+    pub nlink: c_uint,
+    pub uid: c_uint,
+    pub gid: c_uint,
+    /// Device the file represents, if it's a special file
+    pub rdev: u64,
+    pad1: u64,
+    /// Size in bytes
+    pub size: i64,
+    /// Preferred block size for I/O
+    pub blksize: c_int,
+    pad2: c_int,
+    /// Number of 512 byte blocks allocated
+    pub blocks: i64,
+    /// Last access
+    pub atime: TimeSpec,
+    /// Last modification
+    pub mtime: TimeSpec,
+    /// Last status change
+    pub ctime: TimeSpec,
+    unused: [c_uint; 2],
+}
+
+#[cfg(target_arch = "x86_64")]
+const _: () = assert!(mem::size_of::<Stat>() == 144);
+#[cfg(target_arch = "riscv64")]
+const _: () = assert!(mem::size_of::<Stat>() == 128);
+
+bitflags! {
+    /// Which fields of [Statx] to fill in
+    #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct StatxMask: c_uint {
+        const STATX_TYPE = 0x1;
+        const STATX_MODE = 0x2;
+        const STATX_NLINK = 0x4;
+        const STATX_UID = 0x8;
+        const STATX_GID = 0x10;
+        const STATX_ATIME = 0x20;
+        const STATX_MTIME = 0x40;
+        const STATX_CTIME = 0x80;
+        const STATX_INO = 0x100;
+        const STATX_SIZE = 0x200;
+        const STATX_BLOCKS = 0x400;
+        /// Everything in [Stat]
+        const STATX_BASIC_STATS = 0x7ff;
+        /// Creation time
+        const STATX_BTIME = 0x800;
+        const STATX_MNT_ID = 0x1000;
+        /// Direct I/O alignment
+        const STATX_DIOALIGN = 0x2000;
+    }
+}
+
+/// Timestamp in [Statx]
+/// Corresponds to the C `statx_timestamp` struct
+#[derive(Debug, Default, Copy, Clone)]
+#[repr(C)]
+pub struct StatxTimestamp {
+    pub seconds: i64,
+    pub nanoseconds: c_uint,
+    reserved: c_int,
+}
+
+/// Extended file metadata
+/// Corresponds to the C `statx` struct
+#[derive(Debug, Default, Copy, Clone)]
+#[repr(C)]
+pub struct Statx {
+    /// Which fields were filled in
+    pub mask: StatxMask,
+    /// Preferred block size for I/O
+    pub blksize: c_uint,
+    pub attributes: u64,
+    /// Number of hard links
+    pub nlink: c_uint,
+    pub uid: c_uint,
+    pub gid: c_uint,
+    /// File type and permissions
+    pub mode: u16,
+    spare0: u16,
+    /// Inode number
+    pub ino: u64,
+    /// Size in bytes
+    pub size: u64,
+    /// Number of 512 byte blocks allocated
+    pub blocks: u64,
+    /// Which bits of [Statx::attributes] the filesystem supports
+    pub attributes_mask: u64,
+    /// Last access
+    pub atime: StatxTimestamp,
+    /// Creation
+    pub btime: StatxTimestamp,
+    /// Last status change
+    pub ctime: StatxTimestamp,
+    /// Last modification
+    pub mtime: StatxTimestamp,
+    /// Device the file represents, if it's a special file
+    pub rdev_major: c_uint,
+    pub rdev_minor: c_uint,
+    /// Device containing the file
+    pub dev_major: c_uint,
+    pub dev_minor: c_uint,
+    pub mnt_id: u64,
+    pub dio_mem_align: c_uint,
+    pub dio_offset_align: c_uint,
+    spare3: [u64; 12],
+}
+
+const _: () = assert!(mem::size_of::<Statx>() == 0x100);
+
+/// Directory entry type: unknown, so `stat()` the entry to find out
+pub const DT_UNKNOWN: u8 = 0;
+/// Directory entry type: FIFO
+pub const DT_FIFO: u8 = 1;
+/// Directory entry type: character device
+pub const DT_CHR: u8 = 2;
+/// Directory entry type: directory
+pub const DT_DIR: u8 = 4;
+/// Directory entry type: block device
+pub const DT_BLK: u8 = 6;
+/// Directory entry type: regular file
+pub const DT_REG: u8 = 8;
+/// Directory entry type: symbolic link
+pub const DT_LNK: u8 = 10;
+/// Directory entry type: socket
+pub const DT_SOCK: u8 = 12;
+
+/// Directory entry
+/// Corresponds to the C `dirent` struct, and the kernel's `linux_dirent64`
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct Dirent {
+    /// Inode number
+    pub ino: u64,
+    /// Position of the next entry, for `seekdir()`
+    pub off: off_t,
+    /// Length of the entry as read from the kernel, which may be shorter than this struct
+    pub reclen: u16,
+    /// One of the `DT_*` types
+    pub file_type: u8,
+    /// Null-terminated name
+    pub name: [u8; 256],
+}
+
+impl Dirent {
+    /// The entry's name
+    pub fn name(&self) -> &CStr {
+        // The kernel null-terminates names, and they're at most 255 bytes
+        CStr::from_bytes_until_nul(&self.name).unwrap_or_default()
+    }
+}
+
+bitflags! {
+    /// Flags for `nftw()`
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct FtwFlags: c_int {
+        /// Report symbolic links themselves, instead of following them
+        const FTW_PHYS = 1;
+        /// Stay on the file system the walk started on
+        const FTW_MOUNT = 2;
+        /// Change to each directory before reporting its contents
+        const FTW_CHDIR = 4;
+        /// Report directories after their contents, instead of before
+        const FTW_DEPTH = 8;
+        /// Let the callback's return value skip subtrees or siblings
+        const FTW_ACTIONRETVAL = 16;
+    }
+}
+
+/// `nftw()` type: regular file, or anything else that isn't listed
+pub const FTW_F: c_int = 0;
+/// `nftw()` type: directory, reported before its contents
+pub const FTW_D: c_int = 1;
+/// `nftw()` type: directory that can't be read
+pub const FTW_DNR: c_int = 2;
+/// `nftw()` type: file that `stat()` failed on
+pub const FTW_NS: c_int = 3;
+/// `nftw()` type: symbolic link, with [FtwFlags::FTW_PHYS]
+pub const FTW_SL: c_int = 4;
+/// `nftw()` type: directory, reported after its contents with [FtwFlags::FTW_DEPTH]
+pub const FTW_DP: c_int = 5;
+/// `nftw()` type: symbolic link to a file that doesn't exist
+pub const FTW_SLN: c_int = 6;
+
+/// Where `nftw()` is in the walk
+/// Corresponds to the C `FTW` struct
+#[derive(Debug, Default, Copy, Clone)]
+#[repr(C)]
+pub struct Ftw {
+    /// Offset of the file's name in its path
+    pub base: c_int,
+    /// Depth below the starting directory
+    pub level: c_int,
+}